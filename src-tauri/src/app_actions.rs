@@ -0,0 +1,354 @@
+use serde::Serialize;
+use std::path::Path;
+#[cfg(not(target_os = "windows"))]
+use std::{fs, path::PathBuf};
+
+use crate::desktop_entry::{self, DesktopEntry};
+
+/// アプリケーションが提供する副次的なアクション（「新しいプライベートウィンドウ」など）。
+///
+/// `path` / `arguments` / `working_dir` はそのまま `launch_app` に渡せる形式です。
+#[derive(Debug, Serialize)]
+pub struct AppAction {
+    /// アクションの識別子 (.desktop のアクション名、またはジャンプリスト内の連番)
+    pub id: String,
+    /// 表示名
+    pub name: String,
+    /// 起動するプログラムのパス
+    pub path: String,
+    /// 起動引数（空白を含む引数はダブルクォートで囲み、`desktop_entry::split_args` で分割できる形式）
+    pub arguments: String,
+    /// 作業ディレクトリ
+    pub working_dir: String,
+    /// アイコン (.desktop の `Icon=` 値、またはアイコンリソースのパス)
+    pub icon: String,
+    /// 取得元 ("desktop": Desktop Action, "jumplist": Windows ジャンプリスト)
+    pub source: String,
+}
+
+/// 指定されたターゲットに紐づくアクションの一覧を取得します。
+///
+/// - `.desktop` ファイルの場合は `[Desktop Action ...]` セクションを読み取ります。
+/// - Windows では、ジャンプリスト (`CustomDestinations`) からターゲットと同じ実行ファイルを
+///   起動するタスクを抽出します。`.lnk` の場合はリンク先を解決してから検索します。
+/// - その他の OS では、`Exec=` がターゲットと一致する `.desktop` ファイルを探索します。
+///
+/// アクションが見つからない場合は空のリストを返します。
+pub fn get_actions(path: &str) -> Result<Vec<AppAction>, String> {
+    if path.to_lowercase().ends_with(".desktop") {
+        let entry = DesktopEntry::load(Path::new(path))?;
+        return Ok(actions_from_desktop_entry(&entry));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let target = if path.to_lowercase().ends_with(".lnk") {
+            crate::shortcut_utils::resolve_lnk(path)?.target
+        } else {
+            path.to_string()
+        };
+        if target.is_empty() {
+            return Ok(Vec::new());
+        }
+        jumplist::tasks_for_target(&target)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        match find_desktop_entry_for(path) {
+            Some(entry) => Ok(actions_from_desktop_entry(&entry)),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Desktop Entry の `Actions=` に列挙された各アクションを `AppAction` に変換します。
+///
+/// `Exec=` を持たないアクションは起動できないためスキップします。
+pub fn actions_from_desktop_entry(entry: &DesktopEntry) -> Vec<AppAction> {
+    actions_for_lang(entry, desktop_entry::current_lang().as_deref())
+}
+
+/// `actions_from_desktop_entry` の本体。表示名は `lang` のロケールを優先します。
+fn actions_for_lang(entry: &DesktopEntry, lang: Option<&str>) -> Vec<AppAction> {
+    let default_icon = entry.get("Desktop Entry", "Icon").unwrap_or_default();
+    let working_dir = entry.get("Desktop Entry", "Path").unwrap_or_default();
+
+    entry
+        .action_ids()
+        .into_iter()
+        .filter_map(|id| {
+            let group = format!("Desktop Action {}", id);
+            let exec = entry.get(&group, "Exec")?;
+            let mut argv = desktop_entry::split_exec(exec).into_iter();
+            let program = argv.next()?;
+            let name = entry
+                .get_localized(&group, "Name", lang)
+                .unwrap_or(&id)
+                .to_string();
+            let icon = entry
+                .get(&group, "Icon")
+                .unwrap_or(default_icon)
+                .to_string();
+
+            Some(AppAction {
+                id: id.clone(),
+                name,
+                path: program,
+                arguments: desktop_entry::join_args(&argv.collect::<Vec<_>>()),
+                working_dir: working_dir.to_string(),
+                icon,
+                source: "desktop".to_string(),
+            })
+        })
+        .collect()
+}
+
+/// `Exec=` のプログラムがターゲットと一致する `.desktop` ファイルを探します。
+///
+/// ターゲットがフルパスの場合はパスの一致を優先し、なければファイル名で比較します。
+#[cfg(not(target_os = "windows"))]
+fn find_desktop_entry_for(target: &str) -> Option<DesktopEntry> {
+    let target_path = Path::new(target);
+    let target_name = target_path.file_name()?.to_string_lossy().to_string();
+    let mut name_match: Option<DesktopEntry> = None;
+
+    for dir in desktop_entry::application_dirs() {
+        for file in desktop_files_in(&dir) {
+            let Ok(entry) = DesktopEntry::load(&file) else {
+                continue;
+            };
+            let Some(program) = entry
+                .get("Desktop Entry", "Exec")
+                .and_then(|exec| desktop_entry::split_exec(exec).into_iter().next())
+            else {
+                continue;
+            };
+
+            if Path::new(&program) == target_path {
+                return Some(entry);
+            }
+            if name_match.is_none()
+                && Path::new(&program)
+                    .file_name()
+                    .is_some_and(|n| n.to_string_lossy() == target_name)
+            {
+                name_match = Some(entry);
+            }
+        }
+    }
+
+    name_match
+}
+
+/// ディレクトリ内の `.desktop` ファイルを列挙します（存在しない場合は空）。
+#[cfg(not(target_os = "windows"))]
+pub fn desktop_files_in(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "desktop"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+mod jumplist {
+    use super::AppAction;
+    use crate::shortcut_utils::ComGuard;
+    use std::fs;
+    use std::path::PathBuf;
+    use windows::core::Interface;
+    use windows::Win32::System::Com::{CoCreateInstance, IPersistStream, CLSCTX_INPROC_SERVER};
+    use windows::Win32::UI::Shell::PropertiesSystem::{IPropertyStore, PROPERTYKEY};
+    use windows::Win32::UI::Shell::{IShellLinkW, SHCreateMemStream, ShellLink};
+
+    /// シリアライズされたシェルリンクの先頭 (HeaderSize = 0x4C + LinkCLSID)
+    const LNK_SIGNATURE: [u8; 20] = [
+        0x4C, 0x00, 0x00, 0x00, 0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x46,
+    ];
+
+    /// ジャンプリスト項目の表示名が格納されるプロパティ (PKEY_Title)
+    const PKEY_TITLE: PROPERTYKEY = PROPERTYKEY {
+        fmtid: windows::core::GUID::from_u128(0xf29f85e0_4ff9_1068_ab91_08002b27b3d9),
+        pid: 2,
+    };
+
+    /// `%APPDATA%\Microsoft\Windows\Recent\CustomDestinations` のジャンプリストから、
+    /// 指定されたターゲットを起動するタスクを抽出します。
+    ///
+    /// ジャンプリストのファイル名は AppUserModelID のハッシュのため、ファイルを特定せずに
+    /// 全ファイル内のリンクを読み込み、リンク先がターゲットと一致するものを採用します。
+    pub fn tasks_for_target(target: &str) -> Result<Vec<AppAction>, String> {
+        let Ok(appdata) = std::env::var("APPDATA") else {
+            return Ok(Vec::new());
+        };
+        let dir = PathBuf::from(appdata).join(r"Microsoft\Windows\Recent\CustomDestinations");
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return Ok(Vec::new());
+        };
+
+        let target_lower = target.to_lowercase();
+        let mut actions = Vec::new();
+
+        let _com = ComGuard::init();
+        for entry in entries.filter_map(|e| e.ok()) {
+            let Ok(data) = fs::read(entry.path()) else {
+                continue;
+            };
+            for offset in find_link_offsets(&data) {
+                let Some(action) = (unsafe { load_link(&data[offset..], actions.len()) }) else {
+                    continue;
+                };
+                if action.path.to_lowercase() == target_lower {
+                    actions.push(action);
+                }
+            }
+        }
+
+        Ok(actions)
+    }
+
+    /// バイト列中のシェルリンクの開始位置を列挙します。
+    fn find_link_offsets(data: &[u8]) -> Vec<usize> {
+        data.windows(LNK_SIGNATURE.len())
+            .enumerate()
+            .filter(|(_, w)| *w == LNK_SIGNATURE)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// メモリ上のシェルリンクを読み込み、`AppAction` に変換します。
+    /// COM は呼び出し側で初期化済みである必要があります。
+    unsafe fn load_link(data: &[u8], seq: usize) -> Option<AppAction> {
+        let shell_link: IShellLinkW =
+            CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER).ok()?;
+        let persist_stream: IPersistStream = shell_link.cast().ok()?;
+        let stream = SHCreateMemStream(Some(data))?;
+        persist_stream.Load(&stream).ok()?;
+
+        let mut target_buffer = [0u16; 520];
+        let _ = shell_link.GetPath(&mut target_buffer, std::ptr::null_mut(), 0);
+        let path = from_wide(&target_buffer);
+        if path.is_empty() {
+            return None;
+        }
+
+        let mut args_buffer = [0u16; 1024];
+        let _ = shell_link.GetArguments(&mut args_buffer);
+        let mut cwd_buffer = [0u16; 520];
+        let _ = shell_link.GetWorkingDirectory(&mut cwd_buffer);
+        let mut icon_buffer = [0u16; 520];
+        let mut icon_index = 0i32;
+        let _ = shell_link.GetIconLocation(&mut icon_buffer, &mut icon_index);
+
+        // 表示名は PKEY_Title、なければ説明文、それもなければ引数を使う
+        let title = shell_link
+            .cast::<IPropertyStore>()
+            .ok()
+            .and_then(|store| store.GetValue(&PKEY_TITLE).ok())
+            .map(|v| v.to_string())
+            .filter(|t| !t.is_empty());
+        let mut desc_buffer = [0u16; 1024];
+        let _ = shell_link.GetDescription(&mut desc_buffer);
+        let arguments = from_wide(&args_buffer);
+        let name = title
+            .or_else(|| Some(from_wide(&desc_buffer)).filter(|d| !d.is_empty()))
+            .unwrap_or_else(|| arguments.clone());
+
+        let icon = from_wide(&icon_buffer);
+        Some(AppAction {
+            id: format!("jumplist-{}", seq),
+            name,
+            path,
+            arguments,
+            working_dir: from_wide(&cwd_buffer),
+            icon: if icon.is_empty() {
+                icon
+            } else {
+                format!("{},{}", icon, icon_index)
+            },
+            source: "jumplist".to_string(),
+        })
+    }
+
+    fn from_wide(buffer: &[u16]) -> String {
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        String::from_utf16_lossy(&buffer[..len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIREFOX: &str = "[Desktop Entry]
+Name=Firefox
+Exec=/usr/lib/firefox/firefox %u
+Icon=firefox
+Path=/opt/firefox
+Actions=new-window;new-private-window;broken;
+
+[Desktop Action new-window]
+Name=New Window
+Name[ja]=新しいウィンドウ
+Exec=/usr/lib/firefox/firefox --new-window %u
+
+[Desktop Action new-private-window]
+Name=New Private Window
+Exec=\"/opt/fire fox/firefox\" --private-window %F --profile \"/home/me/My Profile\"
+Icon=firefox-private
+
+[Desktop Action broken]
+Name=No Exec
+";
+
+    #[test]
+    fn converts_desktop_actions() {
+        let entry = DesktopEntry::parse(FIREFOX);
+        let actions = actions_for_lang(&entry, Some("ja_JP"));
+
+        // Exec= のないアクションは除外する
+        assert_eq!(actions.len(), 2);
+        let new_window = &actions[0];
+        assert_eq!(new_window.id, "new-window");
+        assert_eq!(new_window.name, "新しいウィンドウ");
+        assert_eq!(new_window.path, "/usr/lib/firefox/firefox");
+        assert_eq!(new_window.arguments, "--new-window");
+        assert_eq!(new_window.working_dir, "/opt/firefox");
+        assert_eq!(new_window.icon, "firefox");
+        assert_eq!(new_window.source, "desktop");
+
+        let private = &actions[1];
+        assert_eq!(private.name, "New Private Window");
+        assert_eq!(private.path, "/opt/fire fox/firefox");
+        assert_eq!(
+            private.arguments,
+            r#"--private-window --profile "/home/me/My Profile""#
+        );
+        assert_eq!(
+            desktop_entry::split_args(&private.arguments),
+            vec!["--private-window", "--profile", "/home/me/My Profile"]
+        );
+        assert_eq!(private.icon, "firefox-private");
+    }
+
+    #[test]
+    fn falls_back_to_untranslated_names() {
+        let entry = DesktopEntry::parse(FIREFOX);
+        let actions = actions_for_lang(&entry, None);
+        assert_eq!(actions[0].name, "New Window");
+        let actions = actions_for_lang(&entry, Some("de_DE"));
+        assert_eq!(actions[0].name, "New Window");
+    }
+
+    #[test]
+    fn entry_without_actions_has_none() {
+        let entry = DesktopEntry::parse("[Desktop Entry]\nName=Plain\nExec=plain\n");
+        assert!(actions_for_lang(&entry, None).is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+use serde::Serialize;
+use std::path::Path;
+#[cfg(target_os = "windows")]
+use std::path::PathBuf;
+
+/// インストール済みアプリの索引の1項目。
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexedApp {
+    /// 表示名（スタートメニューのショートカット名、または `.desktop` の `Name=`）
+    pub name: String,
+    /// 起動対象のパス
+    pub target: String,
+    /// 索引の取得元 ("start_menu" | "desktop")
+    pub source: String,
+}
+
+/// インストール済みアプリの索引を構築します。
+///
+/// - Windows: 全ユーザー・現在のユーザーのスタートメニュー配下の `.lnk` を解決します。
+/// - その他: `applications` ディレクトリの `.desktop` ファイルの `Exec=` を使用します。
+///
+/// リンク先が存在しない項目は索引に含めません。
+pub fn build_app_index() -> Vec<IndexedApp> {
+    #[cfg(target_os = "windows")]
+    {
+        use crate::shortcut_utils::{resolve_lnk_initialized, ComGuard};
+
+        let mut roots = Vec::new();
+        if let Ok(program_data) = std::env::var("ProgramData") {
+            roots.push(PathBuf::from(program_data).join(r"Microsoft\Windows\Start Menu\Programs"));
+        }
+        if let Ok(appdata) = std::env::var("APPDATA") {
+            roots.push(PathBuf::from(appdata).join(r"Microsoft\Windows\Start Menu\Programs"));
+        }
+
+        let mut links = Vec::new();
+        for root in roots {
+            collect_files(&root, "lnk", &mut links);
+        }
+
+        let _com = ComGuard::init();
+        links
+            .into_iter()
+            .filter_map(|link| {
+                let info = unsafe { resolve_lnk_initialized(&link.to_string_lossy()) }.ok()?;
+                if info.target.is_empty() || !Path::new(&info.target).exists() {
+                    return None;
+                }
+                Some(IndexedApp {
+                    name: link.file_stem()?.to_string_lossy().to_string(),
+                    target: info.target,
+                    source: "start_menu".to_string(),
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        use crate::app_actions::desktop_files_in;
+        use crate::desktop_entry::{self, DesktopEntry};
+
+        let lang = desktop_entry::current_lang();
+        desktop_entry::application_dirs()
+            .iter()
+            .flat_map(|dir| desktop_files_in(dir))
+            .filter_map(|file| {
+                let entry = DesktopEntry::load(&file).ok()?;
+                let program = desktop_entry::split_exec(entry.get("Desktop Entry", "Exec")?)
+                    .into_iter()
+                    .next()?;
+                let name = entry.get_localized("Desktop Entry", "Name", lang.as_deref())?;
+                Some(IndexedApp {
+                    name: name.to_string(),
+                    target: program,
+                    source: "desktop".to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// ディレクトリを再帰的に走査し、指定された拡張子のファイルを収集します。
+#[cfg(target_os = "windows")]
+fn collect_files(dir: &Path, extension: &str, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, extension, out);
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+        {
+            out.push(path);
+        }
+    }
+}
+
+/// 名前の比較用に正規化します。
+///
+/// 小文字化し、拡張子と英数字以外の文字、末尾のバージョン番号（`app-1.2.3` の `1.2.3` など）を取り除きます。
+pub fn normalize_app_name(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let stem = match lower.rsplit_once('.') {
+        Some((stem, "exe" | "lnk" | "desktop" | "appimage")) => stem,
+        _ => lower.as_str(),
+    };
+
+    // 末尾のバージョン部分（数字と区切り文字）を削除
+    let trimmed =
+        stem.trim_end_matches(|c: char| c.is_ascii_digit() || matches!(c, '.' | '-' | '_' | ' '));
+    let base = if trimmed.is_empty() { stem } else { trimmed };
+
+    base.chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+/// 名前の候補から、索引内で最も一致する項目を探します。
+///
+/// 正規化した名前が表示名またはターゲットのファイル名と完全一致するものを優先し、
+/// なければ前方一致するものを返します。
+pub fn find_by_name<'a>(index: &'a [IndexedApp], names: &[&str]) -> Option<&'a IndexedApp> {
+    let wanted: Vec<String> = names
+        .iter()
+        .map(|n| normalize_app_name(n))
+        .filter(|n| n.len() >= 2)
+        .collect();
+    if wanted.is_empty() {
+        return None;
+    }
+
+    let keys = |app: &IndexedApp| -> [String; 2] {
+        let file_name = Path::new(&app.target)
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+        [
+            normalize_app_name(&app.name),
+            normalize_app_name(&file_name),
+        ]
+    };
+
+    index
+        .iter()
+        .find(|app| keys(app).iter().any(|k| wanted.contains(k)))
+        .or_else(|| {
+            // 前方一致は短すぎる名前による誤検出を避けるため3文字以上に限定する
+            index.iter().find(|app| {
+                keys(app).iter().filter(|k| k.len() >= 3).any(|k| {
+                    wanted
+                        .iter()
+                        .filter(|w| w.len() >= 3)
+                        .any(|w| k.starts_with(w.as_str()) || w.starts_with(k.as_str()))
+                })
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str, target: &str) -> IndexedApp {
+        IndexedApp {
+            name: name.to_string(),
+            target: target.to_string(),
+            source: "desktop".to_string(),
+        }
+    }
+
+    #[test]
+    fn normalizes_case_extension_and_symbols() {
+        assert_eq!(normalize_app_name("Firefox.EXE"), "firefox");
+        assert_eq!(
+            normalize_app_name("Visual Studio Code.lnk"),
+            "visualstudiocode"
+        );
+        assert_eq!(
+            normalize_app_name("org.gnome.Nautilus.desktop"),
+            "orggnomenautilus"
+        );
+        assert_eq!(normalize_app_name("Notepad++"), "notepad");
+        // 対象外の拡張子は名前の一部として残す
+        assert_eq!(normalize_app_name("archive.zip"), "archivezip");
+    }
+
+    #[test]
+    fn strips_version_suffixes() {
+        assert_eq!(normalize_app_name("blender-4.1.0"), "blender");
+        assert_eq!(normalize_app_name("Krita_5.2 .AppImage"), "krita");
+        assert_eq!(normalize_app_name("App 2"), "app");
+        // 名前全体が数字の場合は削除しない
+        assert_eq!(normalize_app_name("7-Zip"), "7zip");
+        assert_eq!(normalize_app_name("2048"), "2048");
+    }
+
+    #[test]
+    fn prefers_exact_match_over_prefix_match() {
+        let index = vec![
+            app("Code Insiders", "/usr/bin/code-insiders"),
+            app("Visual Studio Code", "/usr/share/code/code"),
+        ];
+        let found = find_by_name(&index, &["Code.exe"]).unwrap();
+        assert_eq!(found.target, "/usr/share/code/code");
+
+        // 表示名・ファイル名のどちらでも一致させる
+        let found = find_by_name(&index, &["code-insiders-1.90"]).unwrap();
+        assert_eq!(found.name, "Code Insiders");
+    }
+
+    #[test]
+    fn falls_back_to_prefix_match() {
+        let index = vec![app("GIMP 2.10", "/usr/bin/gimp-2.10")];
+        assert_eq!(
+            find_by_name(&index, &["gimpshop"]).map(|a| a.name.as_str()),
+            Some("GIMP 2.10")
+        );
+        // 3文字未満の名前は前方一致に使わない
+        let index = vec![app("Gnome Terminal", "/usr/bin/gnome-terminal")];
+        assert!(find_by_name(&index, &["gn"]).is_none());
+    }
+
+    #[test]
+    fn returns_none_without_match() {
+        let index = vec![app("Firefox", "/usr/bin/firefox")];
+        assert!(find_by_name(&index, &["thunderbird"]).is_none());
+        assert!(find_by_name(&index, &["", "x"]).is_none());
+        assert!(find_by_name(&[], &["firefox"]).is_none());
+    }
+}
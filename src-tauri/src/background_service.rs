@@ -0,0 +1,286 @@
+use serde::Serialize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// バックグラウンドサービスの停止要求。
+///
+/// `sleep` は停止が要求されると待機の途中でもすぐに戻るため、
+/// サービスの処理内で長く待つ場合（連続反応防止の待機など）にも使用します。
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 停止を要求し、待機中のスレッドを起こします。
+    pub fn cancel(&self) {
+        let (cancelled, condvar) = &*self.inner;
+        *lock(cancelled) = true;
+        condvar.notify_all();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *lock(&self.inner.0)
+    }
+
+    /// `duration` だけ待機します。停止が要求された場合はすぐに戻り、`true` を返します。
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let (cancelled, condvar) = &*self.inner;
+        let guard = lock(cancelled);
+        let (guard, _) = condvar
+            .wait_timeout_while(guard, duration, |cancelled| !*cancelled)
+            .unwrap_or_else(|e| e.into_inner());
+        *guard
+    }
+}
+
+/// バックグラウンドサービスの状態。
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceStatus {
+    /// サービス名
+    pub name: String,
+    /// 実行中かどうか
+    pub running: bool,
+    /// 処理の間隔（ミリ秒）
+    pub interval_ms: u64,
+    /// 開始してから処理を行った回数
+    pub ticks: u64,
+    /// サービスが停止する原因となったエラー（処理のエラーやパニック）
+    pub last_error: Option<String>,
+}
+
+/// 一定間隔で処理を繰り返すバックグラウンドスレッド。
+///
+/// `stop` は待機中のスレッドをすぐに起こし、スレッドの終了を待ってから戻るため、
+/// 停止直後の `start` で古いスレッドと新しいスレッドが同時に動くことはありません。
+/// 処理がエラーを返すかパニックした場合は、サービスを停止してエラーを状態に記録します。
+/// ロックが毒化（poisoned）していてもパニックせず、そのまま値を使用します。
+pub struct BackgroundService {
+    name: &'static str,
+    interval: Arc<Mutex<Duration>>,
+    worker: Mutex<Option<Worker>>,
+    progress: Arc<Mutex<Progress>>,
+}
+
+struct Worker {
+    token: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct Progress {
+    ticks: u64,
+    last_error: Option<String>,
+}
+
+impl BackgroundService {
+    /// # 引数
+    /// * `name` - サービス名（スレッド名と状態の報告に使用します）
+    /// * `interval` - 処理の間隔
+    pub fn new(name: &'static str, interval: Duration) -> Self {
+        Self {
+            name,
+            interval: Arc::new(Mutex::new(interval)),
+            worker: Mutex::new(None),
+            progress: Arc::new(Mutex::new(Progress::default())),
+        }
+    }
+
+    /// サービスを開始します。既に実行中の場合は何もせず `false` を返します。
+    ///
+    /// `tick` は開始直後と、以降 `interval` ごとにバックグラウンドスレッドで呼び出されます。
+    /// `tick` 内で待機する場合は、引数の `CancellationToken::sleep` を使用してください。
+    pub fn start<F>(&self, mut tick: F) -> Result<bool, String>
+    where
+        F: FnMut(&CancellationToken) -> Result<(), String> + Send + 'static,
+    {
+        let mut worker = lock(&self.worker);
+        if worker
+            .as_ref()
+            .is_some_and(|worker| !worker.handle.is_finished())
+        {
+            return Ok(false);
+        }
+        // エラーで終了したスレッドが残っていれば回収する
+        if let Some(finished) = worker.take() {
+            let _ = finished.handle.join();
+        }
+
+        *lock(&self.progress) = Progress::default();
+        let token = CancellationToken::new();
+        let interval = Arc::clone(&self.interval);
+        let progress = Arc::clone(&self.progress);
+        let thread_token = token.clone();
+
+        let handle = thread::Builder::new()
+            .name(self.name.to_string())
+            .spawn(move || loop {
+                if thread_token.is_cancelled() {
+                    break;
+                }
+                let result = panic::catch_unwind(AssertUnwindSafe(|| tick(&thread_token)))
+                    .unwrap_or_else(|payload| Err(panic_message(payload.as_ref())));
+                match result {
+                    Ok(()) => lock(&progress).ticks += 1,
+                    Err(e) => {
+                        eprintln!("Background service stopped: {}", e);
+                        lock(&progress).last_error = Some(e);
+                        break;
+                    }
+                }
+
+                let interval = *lock(&interval);
+                if thread_token.sleep(interval) {
+                    break;
+                }
+            })
+            .map_err(|e| e.to_string())?;
+
+        *worker = Some(Worker { token, handle });
+        Ok(true)
+    }
+
+    /// サービスを停止し、スレッドの終了を待ちます。実行中でない場合は何もしません。
+    pub fn stop(&self) {
+        let worker = lock(&self.worker).take();
+        if let Some(worker) = worker {
+            worker.token.cancel();
+            if worker.handle.join().is_err() {
+                eprintln!("Background service '{}' panicked", self.name);
+            }
+        }
+    }
+
+    /// 処理の間隔を変更します。次の待機から反映されます。
+    pub fn set_interval(&self, interval: Duration) {
+        *lock(&self.interval) = interval;
+    }
+
+    pub fn status(&self) -> ServiceStatus {
+        let running = lock(&self.worker)
+            .as_ref()
+            .is_some_and(|worker| !worker.handle.is_finished());
+        let progress = lock(&self.progress);
+        ServiceStatus {
+            name: self.name.to_string(),
+            running,
+            interval_ms: lock(&self.interval).as_millis() as u64,
+            ticks: progress.ticks,
+            last_error: progress.last_error.clone(),
+        }
+    }
+}
+
+impl Drop for BackgroundService {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// ロックを取得します。毒化している場合もパニックせずに値を返します。
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// パニックのペイロードからエラーメッセージを作ります。
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    format!("Panicked: {}", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Instant;
+
+    #[test]
+    fn stop_interrupts_sleep_and_joins() {
+        let service = BackgroundService::new("test", Duration::from_secs(60));
+        let ticks = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&ticks);
+        assert_eq!(
+            service.start(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }),
+            Ok(true)
+        );
+        while ticks.load(Ordering::SeqCst) == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let started = Instant::now();
+        service.stop();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(!service.status().running);
+        assert_eq!(ticks.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn start_is_ignored_while_running_and_allowed_after_stop() {
+        let service = BackgroundService::new("test", Duration::from_millis(5));
+        assert_eq!(service.start(|_| Ok(())), Ok(true));
+        assert_eq!(service.start(|_| Ok(())), Ok(false));
+        service.stop();
+        assert_eq!(service.start(|_| Ok(())), Ok(true));
+        assert!(service.status().running);
+    }
+
+    #[test]
+    fn error_or_panic_stops_service_and_is_reported() {
+        let service = BackgroundService::new("test", Duration::from_millis(1));
+        let mut count = 0;
+        service
+            .start(move |_| {
+                count += 1;
+                if count == 3 {
+                    return Err("broken".to_string());
+                }
+                Ok(())
+            })
+            .unwrap();
+        while service.status().running {
+            thread::sleep(Duration::from_millis(1));
+        }
+        let status = service.status();
+        assert_eq!(status.ticks, 2);
+        assert_eq!(status.last_error.as_deref(), Some("broken"));
+
+        service.start(|_| panic!("poisoned")).unwrap();
+        while service.status().running {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(
+            service.status().last_error.as_deref(),
+            Some("Panicked: poisoned")
+        );
+    }
+
+    #[test]
+    fn token_sleep_returns_early_when_cancelled() {
+        let token = CancellationToken::new();
+        assert!(!token.sleep(Duration::from_millis(1)));
+
+        let canceller = token.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            canceller.cancel();
+        });
+        let started = Instant::now();
+        assert!(token.sleep(Duration::from_secs(60)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        handle.join().unwrap();
+    }
+}
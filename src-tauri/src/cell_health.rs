@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::app_index::{self, IndexedApp};
+use crate::target_repair::{self, RepairMethod, RepairProposal};
+use crate::uwp_utils::{self, UwpApp};
+
+/// ヘルスチェック対象となるセルの情報（`settings.json` の `cells` の一部）。
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CellRef {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(rename = "type", default)]
+    pub cell_type: String,
+    pub shortcut: Option<ShortcutRef>,
+    /// ユーザーが設定したカスタムアイコンのパス
+    pub custom_icon: Option<String>,
+    /// レガシーフィールド (shortcut.targetPath 相当)
+    pub target: Option<String>,
+    /// レガシーフィールド (shortcut.workingDirectory 相当)
+    pub working_dir: Option<String>,
+}
+
+/// セルのショートカット情報（フロントエンドの `ShortcutInfo`）。
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutRef {
+    #[serde(default)]
+    pub kind: String,
+    pub target_path: Option<String>,
+    pub working_directory: Option<String>,
+    pub aumid: Option<String>,
+}
+
+/// 検出された問題の種類。
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthIssue {
+    /// ターゲットのファイル・フォルダが存在しない
+    MissingTarget,
+    /// ショートカット(.lnk)自体は存在するが、リンク先が存在しない
+    StaleShortcut,
+    /// UWPアプリのAUMIDがインストール済みアプリ一覧に存在しない
+    MissingUwpApp,
+    /// 作業ディレクトリが存在しない、または読み取れない
+    UnreadableWorkingDir,
+}
+
+/// 問題に対する修正候補。
+#[derive(Debug, Serialize)]
+pub struct SuggestedFix {
+    /// 新しいターゲット（パス、またはUWPアプリの場合はAUMID）
+    pub target: String,
+    /// 候補の表示名
+    pub name: String,
+    /// 候補が見つかった理由
+    pub reason: String,
+}
+
+impl From<RepairProposal> for SuggestedFix {
+    fn from(proposal: RepairProposal) -> Self {
+        let name = Path::new(&proposal.proposed)
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_else(|| proposal.proposed.clone());
+        let reason = match proposal.method {
+            RepairMethod::LinkTracking => "Found by shortcut link tracking",
+            RepairMethod::SiblingVersion => "Found in a newer versioned folder",
+            RepairMethod::AppIndex => "Matched by name in the installed app index",
+        };
+        SuggestedFix {
+            target: proposal.proposed,
+            name,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// 1件の検出結果。
+#[derive(Debug, Serialize)]
+pub struct HealthFinding {
+    /// 対象セルのID
+    pub cell_id: String,
+    /// 対象セルのタイトル
+    pub title: String,
+    /// 問題の種類
+    pub issue: HealthIssue,
+    /// 問題のあるパス（またはAUMID）
+    pub path: String,
+    /// 問題の説明
+    pub message: String,
+    /// 修正候補（見つからない場合は `None`）
+    pub suggestion: Option<SuggestedFix>,
+}
+
+/// `settings.json` を読み込み、保存されている全セルを返します。
+pub fn load_cells_from_settings(app_handle: &tauri::AppHandle) -> Result<Vec<CellRef>, String> {
+    use tauri::Manager;
+
+    let app_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    let path = app_dir.join("settings.json");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    #[derive(Deserialize)]
+    struct SettingsCells {
+        #[serde(default)]
+        cells: Vec<serde_json::Value>,
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let settings: SettingsCells = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    // 1件の不正なセルで全体が失敗しないよう、個別にデシリアライズする
+    Ok(settings
+        .cells
+        .into_iter()
+        .filter_map(|v| serde_json::from_value(v).ok())
+        .collect())
+}
+
+/// 全セルのターゲットを検査し、問題の一覧を返します。
+///
+/// 検査内容:
+/// 1. ファイル・フォルダのターゲットが存在するか
+/// 2. ショートカット(.lnk)のリンク先が存在するか（Windowsのみ）
+/// 3. UWPアプリのAUMIDがインストール済みアプリ一覧に含まれるか
+/// 4. 作業ディレクトリが読み取れるか
+///
+/// 問題が見つかった場合は、`target_repair` で修正候補を探します。
+/// 索引とUWP一覧の取得は重いため、必要になった時点で一度だけ行います。
+pub fn scan(cells: &[CellRef]) -> Vec<HealthFinding> {
+    let mut findings = Vec::new();
+    let mut index: Option<Vec<IndexedApp>> = None;
+    let mut uwp_apps: Option<Option<Vec<UwpApp>>> = None;
+
+    for cell in cells {
+        if !matches!(cell.cell_type.as_str(), "shortcut" | "app") {
+            continue;
+        }
+
+        let kind = cell
+            .shortcut
+            .as_ref()
+            .map(|s| s.kind.as_str())
+            .unwrap_or("file");
+
+        if kind == "uri" {
+            continue;
+        }
+
+        if kind == "uwp" {
+            let Some(aumid) = cell.shortcut.as_ref().and_then(|s| s.aumid.as_deref()) else {
+                continue;
+            };
+            // UWP一覧（非Windowsでは Flatpak / Snap の一覧）が取得できない場合は検査しない
+            let Some(apps) = uwp_apps
+                .get_or_insert_with(|| uwp_utils::get_installed_uwp_apps().ok())
+                .as_ref()
+            else {
+                continue;
+            };
+            if !apps.iter().any(|a| a.aumid.eq_ignore_ascii_case(aumid)) {
+                let suggestion = suggest_uwp(apps, &cell.title);
+                findings.push(HealthFinding {
+                    cell_id: cell.id.clone(),
+                    title: cell.title.clone(),
+                    issue: HealthIssue::MissingUwpApp,
+                    path: aumid.to_string(),
+                    message: format!("UWP app {} is no longer installed", aumid),
+                    suggestion,
+                });
+            }
+            continue;
+        }
+
+        let target = cell
+            .shortcut
+            .as_ref()
+            .and_then(|s| s.target_path.clone())
+            .or_else(|| cell.target.clone())
+            .unwrap_or_default();
+
+        if !target.is_empty() {
+            if let Some((issue, path, message)) = check_target(&target) {
+                let suggestion = target_repair::propose_repair(&target, &cell.title, &mut index)
+                    .map(SuggestedFix::from);
+                findings.push(HealthFinding {
+                    cell_id: cell.id.clone(),
+                    title: cell.title.clone(),
+                    issue,
+                    path,
+                    message,
+                    suggestion,
+                });
+            }
+        }
+
+        let working_dir = cell
+            .shortcut
+            .as_ref()
+            .and_then(|s| s.working_directory.clone())
+            .or_else(|| cell.working_dir.clone())
+            .unwrap_or_default();
+
+        if !working_dir.is_empty() {
+            if let Err(e) = fs::read_dir(&working_dir) {
+                // ターゲットの親フォルダが読み取れる場合はそれを候補とする
+                let suggestion = Path::new(&target)
+                    .parent()
+                    .filter(|p| !p.as_os_str().is_empty() && fs::read_dir(p).is_ok())
+                    .map(|p| SuggestedFix {
+                        target: p.to_string_lossy().to_string(),
+                        name: p.to_string_lossy().to_string(),
+                        reason: "Parent folder of the target".to_string(),
+                    });
+                findings.push(HealthFinding {
+                    cell_id: cell.id.clone(),
+                    title: cell.title.clone(),
+                    issue: HealthIssue::UnreadableWorkingDir,
+                    path: working_dir.clone(),
+                    message: format!("Working directory is not readable: {}", e),
+                    suggestion,
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+/// ターゲットパスを検査し、問題があれば (種類, 問題のパス, 説明) を返します。
+fn check_target(target: &str) -> Option<(HealthIssue, String, String)> {
+    if !Path::new(target).exists() {
+        return Some((
+            HealthIssue::MissingTarget,
+            target.to_string(),
+            format!("Target does not exist: {}", target),
+        ));
+    }
+
+    #[cfg(target_os = "windows")]
+    if target.to_lowercase().ends_with(".lnk") {
+        if let Ok(info) = crate::shortcut_utils::resolve_lnk(target) {
+            if !info.target.is_empty() && !Path::new(&info.target).exists() {
+                return Some((
+                    HealthIssue::StaleShortcut,
+                    info.target.clone(),
+                    format!("Shortcut {} points to a missing target", target),
+                ));
+            }
+        }
+    }
+
+    None
+}
+
+/// セルのタイトルからUWPアプリの代替候補を探します。
+fn suggest_uwp(apps: &[UwpApp], title: &str) -> Option<SuggestedFix> {
+    let wanted = app_index::normalize_app_name(title);
+    if wanted.is_empty() {
+        return None;
+    }
+    apps.iter()
+        .find(|a| app_index::normalize_app_name(&a.name) == wanted)
+        .map(|a| SuggestedFix {
+            target: a.aumid.clone(),
+            name: a.name.clone(),
+            reason: "Matched by name in the installed UWP app list".to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uwp(name: &str, aumid: &str) -> UwpApp {
+        UwpApp {
+            name: name.to_string(),
+            aumid: aumid.to_string(),
+            logo: None,
+        }
+    }
+
+    #[test]
+    fn suggests_uwp_app_with_the_same_normalized_name() {
+        let apps = vec![
+            uwp("Calculator Plus", "Plus!App"),
+            uwp(
+                "Calculator",
+                "Microsoft.WindowsCalculator_8wekyb3d8bbwe!App",
+            ),
+        ];
+        let fix = suggest_uwp(&apps, "calculator").unwrap();
+        assert_eq!(fix.target, "Microsoft.WindowsCalculator_8wekyb3d8bbwe!App");
+        assert_eq!(fix.name, "Calculator");
+
+        // UWP の候補は前方一致では選ばない
+        assert!(suggest_uwp(&apps, "Calc").is_none());
+        assert!(suggest_uwp(&apps, "Photos").is_none());
+        assert!(suggest_uwp(&apps, "!!").is_none());
+    }
+
+    #[test]
+    fn reports_missing_targets() {
+        let missing = std::env::temp_dir().join("cell-health-test-missing/app.exe");
+        let missing = missing.to_string_lossy().to_string();
+        let (issue, path, _) = check_target(&missing).unwrap();
+        assert_eq!(issue, HealthIssue::MissingTarget);
+        assert_eq!(path, missing);
+
+        let existing = std::env::temp_dir().to_string_lossy().to_string();
+        assert!(check_target(&existing).is_none());
+    }
+}
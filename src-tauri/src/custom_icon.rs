@@ -0,0 +1,266 @@
+use image::imageops::{self, FilterType};
+use image::{ImageOutputFormat, RgbaImage};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::ico_reader;
+use crate::svg_render;
+
+/// セルの `customIcon` に保存する、インポート済みアイコンの参照の接頭辞（`custom:<hash>`）
+const REFERENCE_PREFIX: &str = "custom:";
+/// インポート時に生成する正方形のPNGのサイズ（ピクセル）
+const ICON_SIZES: [u32; 4] = [32, 64, 128, 256];
+/// インポートできる画像の拡張子
+const SUPPORTED_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "ico", "svg", "svgz", "webp"];
+
+/// インポートしたカスタムアイコン。
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedIcon {
+    /// セルの `customIcon` に保存する参照（`custom:<hash>`）
+    pub reference: String,
+    /// 元の画像の内容のSHA256ハッシュ
+    pub hash: String,
+    /// 生成したPNGのサイズ（ピクセル）
+    pub sizes: Vec<u32>,
+}
+
+/// セルの `customIcon` の値がインポート済みアイコンの参照（ファイルパスでない）かどうかを返します。
+pub fn is_reference(value: &str) -> bool {
+    value.starts_with(REFERENCE_PREFIX)
+}
+
+/// カスタムアイコンの保存先（`AppData/icons/custom/`）を返します。存在しない場合は作成します。
+///
+/// アイコンキャッシュ（`AppData/cache/icons/`）とは別の場所のため、キャッシュの破棄では削除されません。
+pub fn custom_icon_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+
+    let dir = app_dir.join("icons").join("custom");
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+    Ok(dir)
+}
+
+/// 指定サイズの正方形PNGのファイル名（`<hash>-<size>.png`）を返します。
+pub fn icon_file_name(hash: &str, size: u32) -> String {
+    format!("{}-{}.png", hash, size)
+}
+
+/// 画像ファイルをカスタムアイコンとしてインポートします。
+///
+/// 元の画像を内容のハッシュをファイル名としてアプリのデータフォルダにコピーし、
+/// 正方形に切り抜いた上で各サイズ（`ICON_SIZES`）のPNGを生成します。
+/// 同じ内容の画像は同じ参照になり、生成済みのファイルは再利用します。
+/// 返される参照は元のファイルが移動・削除されても有効です。
+///
+/// # 引数
+/// * `path` - 画像ファイル（PNG / JPEG / ICO / SVG / WebP）のパス
+pub fn import(app_handle: &tauri::AppHandle, path: &str) -> Result<ImportedIcon, String> {
+    let extension = Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !SUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!("Unsupported image format: {}", path));
+    }
+
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let hash = hex::encode(Sha256::digest(&data));
+    let dir = custom_icon_dir(app_handle)?;
+
+    let missing: Vec<u32> = ICON_SIZES
+        .into_iter()
+        .filter(|&size| !dir.join(icon_file_name(&hash, size)).exists())
+        .collect();
+    if !missing.is_empty() {
+        // 読み込めない画像の場合は何もコピーしない
+        let square = to_square(&decode(&data, &extension)?);
+
+        let original = dir.join(format!("{}.{}", hash, extension));
+        if !original.exists() {
+            write_file(&original, &data)?;
+        }
+        for size in missing {
+            let resized = imageops::resize(&square, size, size, FilterType::Lanczos3);
+            write_file(
+                &dir.join(icon_file_name(&hash, size)),
+                &encode_png(resized)?,
+            )?;
+        }
+    }
+
+    Ok(ImportedIcon {
+        reference: format!("{}{}", REFERENCE_PREFIX, hash),
+        hash,
+        sizes: ICON_SIZES.to_vec(),
+    })
+}
+
+/// 画像データを拡張子に応じて読み込みます。
+///
+/// SVG は最大サイズでラスタライズし、ICO は最大サイズのアイコンを使用します。
+fn decode(data: &[u8], extension: &str) -> Result<RgbaImage, String> {
+    let max_size = ICON_SIZES[ICON_SIZES.len() - 1];
+    match extension {
+        "svg" | "svgz" => {
+            let png_data = svg_render::render_svg(data, max_size)?;
+            Ok(image::load_from_memory(&png_data)
+                .map_err(|e| e.to_string())?
+                .to_rgba8())
+        }
+        "ico" => {
+            let entries = ico_reader::read_ico(data)?;
+            ico_reader::best_entry(&entries, max_size)
+                .ok_or("No icon found in ICO file".to_string())?
+                .decode()
+        }
+        _ => Ok(image::load_from_memory(data)
+            .map_err(|e| e.to_string())?
+            .to_rgba8()),
+    }
+}
+
+/// 画像を正方形にします。
+///
+/// 透過を含む画像（ロゴなど）は透明な余白を取り除いた上で、切り取らずに正方形の中央に配置します。
+/// 不透明な画像（写真など）は中央を正方形に切り抜きます。
+fn to_square(image: &RgbaImage) -> RgbaImage {
+    let has_transparency = image.pixels().any(|p| p[3] < 255);
+    let (x, y, width, height) = if has_transparency {
+        opaque_bounds(image).unwrap_or((0, 0, image.width(), image.height()))
+    } else {
+        let side = image.width().min(image.height());
+        (
+            (image.width() - side) / 2,
+            (image.height() - side) / 2,
+            side,
+            side,
+        )
+    };
+    let content = imageops::crop_imm(image, x, y, width, height).to_image();
+    if width == height {
+        return content;
+    }
+
+    let side = width.max(height);
+    let mut square = RgbaImage::new(side, side);
+    imageops::overlay(
+        &mut square,
+        &content,
+        ((side - width) / 2) as i64,
+        ((side - height) / 2) as i64,
+    );
+    square
+}
+
+/// 完全に透明でないピクセルを含む範囲 (x, y, 幅, 高さ) を返します。すべて透明な場合は `None` です。
+fn opaque_bounds(image: &RgbaImage) -> Option<(u32, u32, u32, u32)> {
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel[3] == 0 {
+            continue;
+        }
+        bounds = Some(match bounds {
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+            None => (x, y, x, y),
+        });
+    }
+    bounds.map(|(x0, y0, x1, y1)| (x0, y0, x1 - x0 + 1, y1 - y0 + 1))
+}
+
+fn encode_png(image: RgbaImage) -> Result<Vec<u8>, String> {
+    let mut png_buffer = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(&mut Cursor::new(&mut png_buffer), ImageOutputFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(png_buffer)
+}
+
+/// 一時ファイルに書き込んでからリネームし、書き込み途中のファイルが残らないようにします。
+fn write_file(path: &Path, data: &[u8]) -> Result<(), String> {
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, data).map_err(|e| e.to_string())?;
+    fs::rename(&temp_path, path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+    #[test]
+    fn finds_opaque_bounds() {
+        let mut image = RgbaImage::new(10, 8);
+        assert_eq!(opaque_bounds(&image), None);
+
+        image.put_pixel(3, 2, RED);
+        assert_eq!(opaque_bounds(&image), Some((3, 2, 1, 1)));
+
+        // 半透明のピクセルも範囲に含める
+        image.put_pixel(7, 5, Rgba([0, 0, 0, 1]));
+        assert_eq!(opaque_bounds(&image), Some((3, 2, 5, 4)));
+
+        let single = RgbaImage::from_pixel(1, 1, RED);
+        assert_eq!(opaque_bounds(&single), Some((0, 0, 1, 1)));
+        assert_eq!(opaque_bounds(&RgbaImage::new(1, 1)), None);
+    }
+
+    #[test]
+    fn centers_transparent_content_in_a_padded_square() {
+        // 10x10 の透明な画像の (2, 3) から 6x2 の不透明な帯
+        let mut image = RgbaImage::new(10, 10);
+        for x in 2..8 {
+            for y in 3..5 {
+                image.put_pixel(x, y, RED);
+            }
+        }
+
+        let square = to_square(&image);
+        assert_eq!(square.dimensions(), (6, 6));
+        // 上下に 2px ずつ透明な余白を入れて中央に配置する
+        for y in 0..6 {
+            let expected = if (2..4).contains(&y) { 255 } else { 0 };
+            for x in 0..6 {
+                assert_eq!(square.get_pixel(x, y)[3], expected, "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn crops_opaque_images_to_the_center() {
+        let mut image = RgbaImage::from_pixel(6, 4, Rgba([0, 0, 255, 255]));
+        for y in 0..4 {
+            image.put_pixel(1, y, RED);
+            image.put_pixel(4, y, RED);
+        }
+
+        let square = to_square(&image);
+        assert_eq!(square.dimensions(), (4, 4));
+        // 左右 1px ずつ切り落とされ、赤い列は両端に来る
+        assert_eq!(*square.get_pixel(0, 0), RED);
+        assert_eq!(*square.get_pixel(3, 3), RED);
+        assert_eq!(square.get_pixel(1, 1)[2], 255);
+    }
+
+    #[test]
+    fn keeps_fully_transparent_and_single_pixel_images() {
+        let transparent = to_square(&RgbaImage::new(4, 2));
+        assert_eq!(transparent.dimensions(), (4, 4));
+        assert!(transparent.pixels().all(|p| p[3] == 0));
+
+        let single = to_square(&RgbaImage::from_pixel(1, 1, RED));
+        assert_eq!(single.dimensions(), (1, 1));
+        assert_eq!(*single.get_pixel(0, 0), RED);
+    }
+}
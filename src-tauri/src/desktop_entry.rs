@@ -0,0 +1,338 @@
+use std::fs;
+use std::path::Path;
+#[cfg(not(target_os = "windows"))]
+use std::path::PathBuf;
+
+/// freedesktop.org の Desktop Entry (`.desktop`) ファイルをパースした結果。
+///
+/// グループ（`[Desktop Entry]`, `[Desktop Action new-window]` など）ごとに
+/// キーと値の組を出現順で保持します。
+#[derive(Debug, Default)]
+pub struct DesktopEntry {
+    groups: Vec<(String, Vec<(String, String)>)>,
+}
+
+impl DesktopEntry {
+    /// `.desktop` ファイルの内容をパースします。
+    ///
+    /// コメント行・空行は無視し、値のエスケープシーケンス（`\s`, `\n`, `\t`, `\r`, `\\`）を展開します。
+    pub fn parse(content: &str) -> Self {
+        let mut groups: Vec<(String, Vec<(String, String)>)> = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') && line.ends_with(']') {
+                groups.push((line[1..line.len() - 1].to_string(), Vec::new()));
+                continue;
+            }
+
+            // グループより前に出現したキーは仕様上不正なので無視する
+            if let (Some((key, value)), Some(group)) = (line.split_once('='), groups.last_mut()) {
+                group
+                    .1
+                    .push((key.trim().to_string(), unescape_value(value.trim())));
+            }
+        }
+
+        Self { groups }
+    }
+
+    /// ファイルから読み込んでパースします。
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Ok(Self::parse(&content))
+    }
+
+    /// 指定されたグループのキーの値を取得します。
+    pub fn get(&self, group: &str, key: &str) -> Option<&str> {
+        self.groups
+            .iter()
+            .find(|(name, _)| name == group)
+            .and_then(|(_, entries)| entries.iter().find(|(k, _)| k == key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// ロケール付きのキー（`Name[ja]` など）を優先して値を取得します。
+    ///
+    /// `lang` には `ja_JP` や `ja` のような言語コードを渡します。
+    /// 一致するものがなければロケールなしのキーにフォールバックします。
+    pub fn get_localized(&self, group: &str, key: &str, lang: Option<&str>) -> Option<&str> {
+        if let Some(lang) = lang {
+            if let Some(v) = self.get(group, &format!("{}[{}]", key, lang)) {
+                return Some(v);
+            }
+            if let Some((short, _)) = lang.split_once('_') {
+                if let Some(v) = self.get(group, &format!("{}[{}]", key, short)) {
+                    return Some(v);
+                }
+            }
+        }
+        self.get(group, key)
+    }
+
+    /// `[Desktop Entry]` の `Actions=` に列挙されたアクションIDの一覧を返します。
+    pub fn action_ids(&self) -> Vec<String> {
+        self.get("Desktop Entry", "Actions")
+            .map(split_list)
+            .unwrap_or_default()
+    }
+}
+
+/// `;` 区切りのリスト値を分割します（末尾の `;` や空要素は除外）。
+pub fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// 値文字列のエスケープシーケンスを展開します。
+fn unescape_value(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(other) => {
+                // `\;` などリスト用のエスケープはそのまま残す
+                if other != '\\' {
+                    result.push('\\');
+                }
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// `Exec=` の値をプログラムと引数のリストに分割します。
+///
+/// 仕様に従ってダブルクォートを解除し、フィールドコード（`%f`, `%U` など）を取り除きます。
+/// `%%` はリテラルの `%` として扱います。
+pub fn split_exec(exec: &str) -> Vec<String> {
+    split_args(exec)
+        .into_iter()
+        .filter_map(|arg| strip_field_codes(&arg))
+        .collect()
+}
+
+/// 引数の文字列を空白で分割します。`launch_app` の引数もこの規則で分割します。
+///
+/// ダブルクォートで囲まれた部分は空白を含めて1つの引数になります。
+/// クォート内では `\"`, `` \` ``, `\$`, `\\` をエスケープとして扱い、
+/// それ以外のバックスラッシュ（Windows のパス区切りなど）はそのまま残します。
+pub fn split_args(args: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_token = false;
+    let mut chars = args.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_token = true;
+            }
+            '\\' if in_quotes && matches!(chars.peek(), Some('"' | '`' | '$' | '\\')) => {
+                current.extend(chars.next());
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_token {
+                    result.push(std::mem::take(&mut current));
+                    has_token = false;
+                }
+            }
+            _ => {
+                current.push(c);
+                has_token = true;
+            }
+        }
+    }
+    if has_token {
+        result.push(current);
+    }
+    result
+}
+
+/// 引数のリストを `split_args` で元に戻せる1つの文字列に結合します。
+///
+/// 空白やダブルクォートを含む引数（および空の引数）はダブルクォートで囲みます。
+pub fn join_args<S: AsRef<str>>(args: &[S]) -> String {
+    args.iter()
+        .map(|arg| {
+            let arg = arg.as_ref();
+            if !arg.is_empty() && !arg.chars().any(|c| c.is_whitespace() || c == '"') {
+                return arg.to_string();
+            }
+            let mut quoted = String::with_capacity(arg.len() + 2);
+            quoted.push('"');
+            for c in arg.chars() {
+                if matches!(c, '"' | '`' | '$' | '\\') {
+                    quoted.push('\\');
+                }
+                quoted.push(c);
+            }
+            quoted.push('"');
+            quoted
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 引数からフィールドコードを取り除きます。
+/// フィールドコードのみで構成された引数は `None` を返し、引数ごと削除します。
+fn strip_field_codes(arg: &str) -> Option<String> {
+    let mut result = String::with_capacity(arg.len());
+    let mut removed = false;
+    let mut chars = arg.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => result.push('%'),
+            Some(_) => removed = true,
+            None => result.push('%'),
+        }
+    }
+    if removed && result.is_empty() {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+/// `.desktop` ファイルを探索するディレクトリ（`$XDG_DATA_HOME/applications` と
+/// `$XDG_DATA_DIRS/*/applications`）の一覧を返します。
+#[cfg(not(target_os = "windows"))]
+pub fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    match std::env::var("XDG_DATA_HOME") {
+        Ok(home) if !home.is_empty() => dirs.push(PathBuf::from(home).join("applications")),
+        _ => {
+            if let Ok(home) = std::env::var("HOME") {
+                dirs.push(PathBuf::from(home).join(".local/share/applications"));
+            }
+        }
+    }
+
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    for dir in data_dirs.split(':').filter(|d| !d.is_empty()) {
+        dirs.push(PathBuf::from(dir).join("applications"));
+    }
+
+    dirs
+}
+
+/// 現在のロケールの言語コード（`LC_MESSAGES` / `LANG` から `ja_JP` の形式で）を返します。
+pub fn current_lang() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.is_empty() && v != "C" && v != "POSIX")
+        .map(|v| {
+            // "ja_JP.UTF-8@variant" -> "ja_JP"
+            v.split(['.', '@']).next().unwrap_or_default().to_string()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_groups_and_ignores_comments() {
+        let entry = DesktopEntry::parse(
+            "# comment\nIgnored=before group\n[Desktop Entry]\nName = Editor \n\n# x\nComment=a\\sb\\nc\\;d\n[Desktop Action new]\nName=New\n",
+        );
+        assert_eq!(entry.get("Desktop Entry", "Name"), Some("Editor"));
+        assert_eq!(entry.get("Desktop Entry", "Comment"), Some("a b\nc\\;d"));
+        assert_eq!(entry.get("Desktop Entry", "Ignored"), None);
+        assert_eq!(entry.get("Desktop Action new", "Name"), Some("New"));
+        assert_eq!(entry.get("Desktop Action new", "Comment"), None);
+        assert_eq!(entry.get("Missing", "Name"), None);
+    }
+
+    #[test]
+    fn prefers_localized_keys() {
+        let entry = DesktopEntry::parse(
+            "[Desktop Entry]\nName=Files\nName[ja]=ファイル\nName[pt_BR]=Arquivos\n",
+        );
+        let name = |lang| entry.get_localized("Desktop Entry", "Name", lang);
+        assert_eq!(name(Some("ja_JP")), Some("ファイル"));
+        assert_eq!(name(Some("ja")), Some("ファイル"));
+        assert_eq!(name(Some("pt_BR")), Some("Arquivos"));
+        assert_eq!(name(Some("pt_PT")), Some("Files"));
+        assert_eq!(name(None), Some("Files"));
+    }
+
+    #[test]
+    fn lists_action_ids() {
+        let entry =
+            DesktopEntry::parse("[Desktop Entry]\nActions=new-window; new-private-window;;\n");
+        assert_eq!(entry.action_ids(), vec!["new-window", "new-private-window"]);
+        assert!(DesktopEntry::parse("[Desktop Entry]\n")
+            .action_ids()
+            .is_empty());
+    }
+
+    #[test]
+    fn splits_exec_and_strips_field_codes() {
+        assert_eq!(split_exec("firefox %u"), vec!["firefox"]);
+        assert_eq!(
+            split_exec("\"/opt/my app/run\" --file=%f --name %c %%literal"),
+            vec!["/opt/my app/run", "--file=", "--name", "%literal"]
+        );
+        assert_eq!(
+            split_exec(r#"sh -c "echo \"hi\" \\ there" %U"#),
+            vec!["sh", "-c", r#"echo "hi" \ there"#]
+        );
+        assert_eq!(split_exec("app \"\" %F"), vec!["app", ""]);
+        assert!(split_exec("").is_empty());
+    }
+
+    #[test]
+    fn split_args_keeps_windows_paths_and_round_trips_join() {
+        assert_eq!(
+            split_args(r#"--dir "C:\Program Files\App" /x"#),
+            vec!["--dir", r"C:\Program Files\App", "/x"]
+        );
+        assert_eq!(split_args(r"C:\temp\a.txt"), vec![r"C:\temp\a.txt"]);
+
+        let args = [
+            "--name",
+            "my file.txt",
+            "",
+            r#"say "hi""#,
+            r"C:\dir\",
+            "$HOME",
+        ];
+        let joined = join_args(&args);
+        assert_eq!(
+            joined,
+            r#"--name "my file.txt" "" "say \"hi\"" C:\dir\ $HOME"#
+        );
+        assert_eq!(split_args(&joined), args);
+        assert_eq!(join_args::<&str>(&[]), "");
+    }
+}
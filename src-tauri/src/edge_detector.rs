@@ -0,0 +1,821 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// マウスエッジで反応する画面端・四隅。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotZone {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl HotZone {
+    /// ゾーンの外側（画面の外）に向かう方向 (dx, dy) の一覧。
+    /// 四隅は2辺の方向と対角の方向です。
+    fn outward(self) -> &'static [(i32, i32)] {
+        match self {
+            HotZone::Top => &[(0, -1)],
+            HotZone::Bottom => &[(0, 1)],
+            HotZone::Left => &[(-1, 0)],
+            HotZone::Right => &[(1, 0)],
+            HotZone::TopLeft => &[(-1, 0), (0, -1), (-1, -1)],
+            HotZone::TopRight => &[(1, 0), (0, -1), (1, -1)],
+            HotZone::BottomLeft => &[(-1, 0), (0, 1), (-1, 1)],
+            HotZone::BottomRight => &[(1, 0), (0, 1), (1, 1)],
+        }
+    }
+}
+
+/// ゾーンに到達した際の動作。
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum EdgeAction {
+    /// ランチャーを表示する
+    #[default]
+    ShowLauncher,
+    /// ランチャーを表示し、指定したグループを開く
+    OpenGroup { group_id: String },
+    /// 指定したセルを起動する（ランチャーは表示しない）
+    LaunchCell { cell_id: String },
+    /// ランチャーを表示し、検索を開く
+    OpenSearch,
+    /// ランチャーを隠す
+    Hide,
+}
+
+/// マウスエッジの設定。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MouseEdgeConfig {
+    /// 反応する画面端・四隅
+    pub zones: Vec<HotZone>,
+    /// 画面端とみなすカーソルと端の距離（ピクセル）
+    pub edge_threshold: u32,
+    /// 画面端のうち、四隅とみなす角からの範囲（ピクセル）
+    pub corner_size: u32,
+    /// 反応するまでにゾーンに留まる必要がある時間（ミリ秒）
+    pub dwell_ms: u64,
+    /// 反応後、次に反応できるようになるまでの時間（ミリ秒）
+    pub cooldown_ms: u64,
+    /// 監視しないモニターの名前
+    pub disabled_monitors: Vec<String>,
+    /// 他のモニターと接している画面端（カーソルが隣のモニターへ移動できる端）を無視するか
+    pub ignore_shared_edges: bool,
+    /// ゾーンごとの動作（指定がないゾーンはランチャーを表示します）
+    pub actions: HashMap<HotZone, EdgeAction>,
+    /// マウスのボタンが押されている間（ドラッグ中など）は反応しないか
+    pub suppress_while_button_down: bool,
+    /// 前面のウィンドウが全画面表示（ゲーム・プレゼンテーションなど）の間は反応しないか
+    pub suppress_in_fullscreen: bool,
+    /// 前面にある間は反応しないプロセスの実行ファイル名（大文字小文字と `.exe` の有無は区別しません）
+    pub blocked_processes: Vec<String>,
+}
+
+impl MouseEdgeConfig {
+    /// ゾーンに到達した際の動作を返します。
+    pub fn action_for(&self, zone: HotZone) -> EdgeAction {
+        self.actions.get(&zone).cloned().unwrap_or_default()
+    }
+
+    /// 反応を抑止すべき場合はその理由を返します。
+    ///
+    /// # 引数
+    /// * `button_down` - マウスのボタンが押されているか
+    /// * `foreground` - 前面のウィンドウ（取得できない場合は `None`）
+    pub fn suppression_reason(
+        &self,
+        button_down: bool,
+        foreground: Option<&ForegroundWindow>,
+    ) -> Option<SuppressReason> {
+        if self.suppress_while_button_down && button_down {
+            return Some(SuppressReason::MouseButtonDown);
+        }
+        let foreground = foreground?;
+        if let Some(process) = &foreground.process_name {
+            let name = process_key(process);
+            if self
+                .blocked_processes
+                .iter()
+                .any(|blocked| process_key(blocked) == name)
+            {
+                return Some(SuppressReason::BlockedProcess(process.clone()));
+            }
+        }
+        if self.suppress_in_fullscreen && foreground.fullscreen {
+            return Some(SuppressReason::Fullscreen(foreground.process_name.clone()));
+        }
+        None
+    }
+}
+
+/// 実行ファイル名を比較用に正規化します（小文字化し、`.exe` を除く）。
+fn process_key(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    match name.strip_suffix(".exe") {
+        Some(stem) => stem.to_string(),
+        None => name,
+    }
+}
+
+impl Default for MouseEdgeConfig {
+    fn default() -> Self {
+        Self {
+            zones: vec![HotZone::Top, HotZone::Left],
+            edge_threshold: 5,
+            corner_size: 32,
+            dwell_ms: 0,
+            cooldown_ms: 1000,
+            disabled_monitors: Vec::new(),
+            ignore_shared_edges: true,
+            actions: HashMap::new(),
+            suppress_while_button_down: true,
+            suppress_in_fullscreen: true,
+            blocked_processes: Vec::new(),
+        }
+    }
+}
+
+/// モニターの領域（物理ピクセル、仮想スクリーン座標）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitorRect {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MonitorRect {
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+}
+
+/// ゾーンへの到達。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeHit {
+    /// 到達したモニター
+    pub monitor: MonitorRect,
+    pub zone: HotZone,
+}
+
+/// 前面のウィンドウの情報。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForegroundWindow {
+    /// ウィンドウを所有するプロセスの実行ファイル名（取得できない場合は `None`）
+    pub process_name: Option<String>,
+    /// モニター全体を覆っているか（全画面表示か）
+    pub fullscreen: bool,
+}
+
+/// 反応を抑止した理由。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SuppressReason {
+    /// マウスのボタンが押されている
+    MouseButtonDown,
+    /// 前面のウィンドウが全画面表示（プロセス名）
+    Fullscreen(Option<String>),
+    /// 前面のウィンドウが抑止対象のプロセス（プロセス名）
+    BlockedProcess(String),
+}
+
+impl fmt::Display for SuppressReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SuppressReason::MouseButtonDown => write!(f, "mouse button is held"),
+            SuppressReason::Fullscreen(Some(process)) => {
+                write!(f, "foreground window is fullscreen ({})", process)
+            }
+            SuppressReason::Fullscreen(None) => write!(f, "foreground window is fullscreen"),
+            SuppressReason::BlockedProcess(process) => {
+                write!(f, "blocked process is in the foreground ({})", process)
+            }
+        }
+    }
+}
+
+/// `EdgeDetector::poll` の結果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdgeEvent {
+    /// ゾーンに到達したため反応する
+    Triggered(EdgeHit),
+    /// ゾーンに到達したが、抑止条件に当てはまるため反応しない
+    Suppressed(EdgeHit, SuppressReason),
+}
+
+/// カーソル位置・モニターの配置・抑止条件の判定に使う状態の取得元。
+///
+/// 実際のカーソル（OS）の代わりに記録済みの軌跡を与えることで、
+/// `EdgeDetector` の判定を OS に依存せずに確認できます。
+pub trait CursorSource {
+    /// 現在のカーソル位置（物理ピクセル、仮想スクリーン座標）。取得できない場合は `None`。
+    fn cursor_position(&mut self) -> Option<(i32, i32)>;
+
+    /// 接続されているモニター。
+    fn monitors(&mut self) -> Vec<MonitorRect>;
+
+    /// マウスのボタン（左・右・中）のいずれかが押されているか。
+    fn mouse_button_down(&mut self) -> bool;
+
+    /// 前面のウィンドウ。取得できない場合は `None`。
+    fn foreground_window(&mut self) -> Option<ForegroundWindow>;
+}
+
+/// カーソルがどのモニターのどのゾーンにあるかを返します。
+///
+/// 四隅は、画面端にあってかつ角から `corner_size` 以内の位置です。
+/// 有効でないゾーン、無効なモニター、（`ignore_shared_edges` の場合）他のモニターと接している端は除きます。
+/// 四隅のゾーンが有効でない場合は、その位置の画面端のゾーンとして扱います。
+pub fn hit_test(
+    x: i32,
+    y: i32,
+    monitors: &[MonitorRect],
+    config: &MouseEdgeConfig,
+) -> Option<EdgeHit> {
+    let (index, monitor) = monitors
+        .iter()
+        .enumerate()
+        .find(|(_, monitor)| monitor.contains(x, y))?;
+    if monitor
+        .name
+        .as_ref()
+        .is_some_and(|name| config.disabled_monitors.contains(name))
+    {
+        return None;
+    }
+
+    let threshold = config.edge_threshold as i32;
+    let corner = config.corner_size as i32;
+    let (from_left, from_right) = (x - monitor.x, monitor.right() - 1 - x);
+    let (from_top, from_bottom) = (y - monitor.y, monitor.bottom() - 1 - y);
+    let (top, bottom) = (from_top <= threshold, from_bottom <= threshold);
+    let (left, right) = (from_left <= threshold, from_right <= threshold);
+
+    // 四隅を優先し、次に画面端の順で判定する
+    let candidates = [
+        (
+            HotZone::TopLeft,
+            (top && from_left < corner) || (left && from_top < corner),
+        ),
+        (
+            HotZone::TopRight,
+            (top && from_right < corner) || (right && from_top < corner),
+        ),
+        (
+            HotZone::BottomLeft,
+            (bottom && from_left < corner) || (left && from_bottom < corner),
+        ),
+        (
+            HotZone::BottomRight,
+            (bottom && from_right < corner) || (right && from_bottom < corner),
+        ),
+        (HotZone::Top, top),
+        (HotZone::Bottom, bottom),
+        (HotZone::Left, left),
+        (HotZone::Right, right),
+    ];
+    candidates
+        .into_iter()
+        .filter(|&(zone, hit)| hit && config.zones.contains(&zone))
+        .find(|&(zone, _)| !(config.ignore_shared_edges && is_shared(zone, x, y, index, monitors)))
+        .map(|(zone, _)| EdgeHit {
+            monitor: monitor.clone(),
+            zone,
+        })
+}
+
+/// ゾーンの外側に他のモニターがあるかどうか（カーソルがそのまま隣のモニターへ移動できるか）を返します。
+fn is_shared(zone: HotZone, x: i32, y: i32, index: usize, monitors: &[MonitorRect]) -> bool {
+    let monitor = &monitors[index];
+    zone.outward().iter().any(|&(dx, dy)| {
+        // 端の1ピクセル外側の点
+        let probe_x = match dx {
+            -1 => monitor.x - 1,
+            1 => monitor.right(),
+            _ => x,
+        };
+        let probe_y = match dy {
+            -1 => monitor.y - 1,
+            1 => monitor.bottom(),
+            _ => y,
+        };
+        monitors
+            .iter()
+            .enumerate()
+            .any(|(i, other)| i != index && other.contains(probe_x, probe_y))
+    })
+}
+
+/// カーソル位置の変化から、ゾーンへの到達（滞在時間・連続反応防止を考慮）を検知します。
+///
+/// ゾーンに `dwell_ms` 以上留まると反応し、以降はそのゾーンを離れるまで、
+/// また反応から `cooldown_ms` が経過するまで反応しません。
+/// 抑止条件により反応しなかった場合も、そのゾーンを離れるまで反応しません
+/// （ドラッグを画面端で終えた直後などに反応しないようにするため）。
+pub struct EdgeDetector {
+    config: MouseEdgeConfig,
+    /// 滞在中のゾーンと、そのゾーンに入った時刻
+    dwelling: Option<(EdgeHit, Instant)>,
+    /// 滞在中のゾーンで既に反応したか
+    fired: bool,
+    last_fired: Option<Instant>,
+}
+
+impl EdgeDetector {
+    pub fn new(config: MouseEdgeConfig) -> Self {
+        Self {
+            config,
+            dwelling: None,
+            fired: false,
+            last_fired: None,
+        }
+    }
+
+    pub fn config(&self) -> &MouseEdgeConfig {
+        &self.config
+    }
+
+    /// 設定を変更します。滞在中の状態は維持します。
+    pub fn set_config(&mut self, config: MouseEdgeConfig) {
+        self.config = config;
+    }
+
+    /// 取得元から現在のカーソル位置を取得して到達を判定し、反応する時点で抑止条件を確認します。
+    /// カーソル位置が取得できない場合は何もしません。
+    pub fn poll(&mut self, source: &mut impl CursorSource, now: Instant) -> Option<EdgeEvent> {
+        let (x, y) = source.cursor_position()?;
+        let monitors = source.monitors();
+        let hit = self.ready(x, y, &monitors, now)?;
+
+        // 前面のウィンドウの確認は負荷があるため、反応する時点で、ボタンにより抑止しない場合のみ行う
+        let button_down = source.mouse_button_down();
+        let foreground = if self.config.suppress_while_button_down && button_down {
+            None
+        } else {
+            source.foreground_window()
+        };
+        match self
+            .config
+            .suppression_reason(button_down, foreground.as_ref())
+        {
+            Some(reason) => {
+                // 連続反応防止の待機は始めず、ゾーンを離れるまで反応しない
+                self.fired = true;
+                Some(EdgeEvent::Suppressed(hit, reason))
+            }
+            None => {
+                self.fire(now);
+                Some(EdgeEvent::Triggered(hit))
+            }
+        }
+    }
+
+    /// 滞在中のゾーンを更新し、反応できる状態であればゾーンを返します。
+    fn ready(&mut self, x: i32, y: i32, monitors: &[MonitorRect], now: Instant) -> Option<EdgeHit> {
+        let Some(hit) = hit_test(x, y, monitors, &self.config) else {
+            self.dwelling = None;
+            self.fired = false;
+            return None;
+        };
+
+        let entered_at = match &self.dwelling {
+            Some((current, entered_at)) if *current == hit => *entered_at,
+            _ => {
+                self.dwelling = Some((hit.clone(), now));
+                self.fired = false;
+                now
+            }
+        };
+
+        if self.fired {
+            return None;
+        }
+        let cooldown = Duration::from_millis(self.config.cooldown_ms);
+        if self
+            .last_fired
+            .is_some_and(|last| now.duration_since(last) < cooldown)
+        {
+            return None;
+        }
+        if now.duration_since(entered_at) < Duration::from_millis(self.config.dwell_ms) {
+            return None;
+        }
+        Some(hit)
+    }
+
+    fn fire(&mut self, now: Instant) {
+        self.fired = true;
+        self.last_fired = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 軌跡の1点（カーソル位置とマウスのボタンの状態）
+    #[derive(Clone, Copy)]
+    struct Sample {
+        point: Option<(i32, i32)>,
+        button_down: bool,
+    }
+
+    /// 記録済みのカーソルの軌跡を1回の取得ごとに1点ずつ返す取得元
+    struct TraceSource {
+        trace: Vec<Sample>,
+        next: usize,
+        button_down: bool,
+        monitors: Vec<MonitorRect>,
+        foreground: Option<ForegroundWindow>,
+    }
+
+    impl CursorSource for TraceSource {
+        fn cursor_position(&mut self) -> Option<(i32, i32)> {
+            let sample = self.trace.get(self.next).copied()?;
+            self.next += 1;
+            self.button_down = sample.button_down;
+            sample.point
+        }
+
+        fn monitors(&mut self) -> Vec<MonitorRect> {
+            self.monitors.clone()
+        }
+
+        fn mouse_button_down(&mut self) -> bool {
+            self.button_down
+        }
+
+        fn foreground_window(&mut self) -> Option<ForegroundWindow> {
+            self.foreground.clone()
+        }
+    }
+
+    /// 取得間隔
+    const STEP: Duration = Duration::from_millis(100);
+
+    fn monitor(name: &str, x: i32, y: i32) -> MonitorRect {
+        MonitorRect {
+            name: Some(name.to_string()),
+            x,
+            y,
+            width: 1920,
+            height: 1080,
+        }
+    }
+
+    /// 左右に並んだ2台のモニター
+    fn dual() -> Vec<MonitorRect> {
+        vec![monitor("A", 0, 0), monitor("B", 1920, 0)]
+    }
+
+    fn config(zones: &[HotZone]) -> MouseEdgeConfig {
+        MouseEdgeConfig {
+            zones: zones.to_vec(),
+            ..MouseEdgeConfig::default()
+        }
+    }
+
+    fn zone_at(x: i32, y: i32, config: &MouseEdgeConfig) -> Option<HotZone> {
+        hit_test(x, y, &dual(), config).map(|hit| hit.zone)
+    }
+
+    /// 軌跡を `STEP` ごとに検知に与え、結果のあった時点（何点目か）と結果を返す
+    fn run_with(
+        config: MouseEdgeConfig,
+        trace: &[Sample],
+        foreground: Option<ForegroundWindow>,
+    ) -> Vec<(usize, EdgeEvent)> {
+        let mut source = TraceSource {
+            trace: trace.to_vec(),
+            next: 0,
+            button_down: false,
+            monitors: dual(),
+            foreground,
+        };
+        let mut detector = EdgeDetector::new(config);
+        let start = Instant::now();
+        (0..trace.len())
+            .filter_map(|i| {
+                let now = start + STEP * i as u32;
+                detector.poll(&mut source, now).map(|event| (i, event))
+            })
+            .collect()
+    }
+
+    /// 軌跡を検知に与え、反応した時点とゾーンを返す
+    fn run(config: MouseEdgeConfig, trace: &[Sample]) -> Vec<(usize, HotZone)> {
+        run_with(config, trace, None)
+            .into_iter()
+            .filter_map(|(i, event)| match event {
+                EdgeEvent::Triggered(hit) => Some((i, hit.zone)),
+                EdgeEvent::Suppressed(..) => None,
+            })
+            .collect()
+    }
+
+    /// `point` に `count` 回留まる軌跡
+    fn stay(point: (i32, i32), count: usize) -> Vec<Sample> {
+        vec![
+            Sample {
+                point: Some(point),
+                button_down: false,
+            };
+            count
+        ]
+    }
+
+    /// ボタンを押したまま `point` に `count` 回留まる軌跡
+    fn drag(point: (i32, i32), count: usize) -> Vec<Sample> {
+        vec![
+            Sample {
+                point: Some(point),
+                button_down: true,
+            };
+            count
+        ]
+    }
+
+    /// カーソル位置を取得できない `count` 回の軌跡
+    fn lost(count: usize) -> Vec<Sample> {
+        vec![
+            Sample {
+                point: None,
+                button_down: false,
+            };
+            count
+        ]
+    }
+
+    fn foreground(process: &str, fullscreen: bool) -> Option<ForegroundWindow> {
+        Some(ForegroundWindow {
+            process_name: Some(process.to_string()),
+            fullscreen,
+        })
+    }
+
+    const TOP: (i32, i32) = (800, 0);
+    const LEFT: (i32, i32) = (0, 500);
+    const CENTER: (i32, i32) = (800, 500);
+
+    #[test]
+    fn detects_edges_within_threshold() {
+        let config = config(&[HotZone::Top, HotZone::Left, HotZone::Bottom]);
+        assert_eq!(zone_at(800, 5, &config), Some(HotZone::Top));
+        assert_eq!(zone_at(800, 6, &config), None);
+        assert_eq!(zone_at(0, 500, &config), Some(HotZone::Left));
+        assert_eq!(zone_at(800, 1079, &config), Some(HotZone::Bottom));
+        assert_eq!(zone_at(800, 500, &config), None);
+        // 右端は有効でない
+        assert_eq!(zone_at(3839, 500, &config), None);
+    }
+
+    #[test]
+    fn corners_take_priority_and_fall_back_to_edges() {
+        let config = config(&[HotZone::Top, HotZone::TopLeft, HotZone::Left]);
+        assert_eq!(zone_at(0, 0, &config), Some(HotZone::TopLeft));
+        assert_eq!(zone_at(31, 0, &config), Some(HotZone::TopLeft));
+        assert_eq!(zone_at(0, 31, &config), Some(HotZone::TopLeft));
+        assert_eq!(zone_at(32, 0, &config), Some(HotZone::Top));
+        assert_eq!(zone_at(0, 32, &config), Some(HotZone::Left));
+
+        // 四隅が有効でなければ、その位置の画面端として扱う
+        let config = MouseEdgeConfig {
+            zones: vec![HotZone::Left],
+            ..config
+        };
+        assert_eq!(zone_at(0, 0, &config), Some(HotZone::Left));
+    }
+
+    #[test]
+    fn shared_edges_between_monitors_are_ignored() {
+        let zones = [HotZone::Left, HotZone::Right, HotZone::TopRight];
+        let config = config(&zones);
+        // A の右端と B の左端は接している
+        assert_eq!(zone_at(1919, 500, &config), None);
+        assert_eq!(zone_at(1920, 500, &config), None);
+        // 外側の端は反応する
+        assert_eq!(zone_at(0, 500, &config), Some(HotZone::Left));
+        assert_eq!(zone_at(3839, 500, &config), Some(HotZone::Right));
+        // A の右上は B と接している
+        assert_eq!(zone_at(1919, 0, &config), None);
+
+        let config = MouseEdgeConfig {
+            ignore_shared_edges: false,
+            ..config
+        };
+        assert_eq!(zone_at(1919, 500, &config), Some(HotZone::Right));
+        assert_eq!(zone_at(1920, 500, &config), Some(HotZone::Left));
+        assert_eq!(zone_at(1919, 0, &config), Some(HotZone::TopRight));
+    }
+
+    #[test]
+    fn disabled_monitors_are_skipped() {
+        let config = MouseEdgeConfig {
+            disabled_monitors: vec!["B".to_string()],
+            ..config(&[HotZone::Top])
+        };
+        assert_eq!(zone_at(800, 0, &config), Some(HotZone::Top));
+        assert_eq!(zone_at(2800, 0, &config), None);
+
+        let hit = hit_test(2800, 0, &dual(), &MouseEdgeConfig::default()).unwrap();
+        assert_eq!(hit.monitor.name.as_deref(), Some("B"));
+    }
+
+    #[test]
+    fn fires_immediately_without_dwell_and_once_per_visit() {
+        let trace = [stay(TOP, 30), stay(CENTER, 1), stay(TOP, 3)].concat();
+        let hits = run(config(&[HotZone::Top]), &trace);
+        // 留まり続けても再反応せず、離れて戻ると反応する
+        assert_eq!(hits, vec![(0, HotZone::Top), (31, HotZone::Top)]);
+    }
+
+    #[test]
+    fn dwell_requires_staying_in_zone() {
+        let config = MouseEdgeConfig {
+            dwell_ms: 300,
+            ..config(&[HotZone::Top, HotZone::Left])
+        };
+        // 通過しただけ（200ms）では反応しない
+        let trace = [stay(TOP, 3), stay(CENTER, 2), stay(TOP, 4)].concat();
+        assert_eq!(run(config.clone(), &trace), vec![(8, HotZone::Top)]);
+
+        // 別のゾーンに移ると滞在時間は数え直す
+        let trace = [stay(TOP, 3), stay(LEFT, 4)].concat();
+        assert_eq!(run(config, &trace), vec![(6, HotZone::Left)]);
+    }
+
+    #[test]
+    fn cooldown_blocks_quick_reentry() {
+        let config = MouseEdgeConfig {
+            cooldown_ms: 1000,
+            ..config(&[HotZone::Top, HotZone::Left])
+        };
+        // 反応から 1000ms 未満の再到達（別のゾーンを含む）には反応しない
+        let trace = [
+            stay(TOP, 1),
+            stay(CENTER, 2),
+            stay(LEFT, 2),
+            stay(CENTER, 2),
+            stay(TOP, 6),
+        ]
+        .concat();
+        assert_eq!(
+            run(config, &trace),
+            vec![(0, HotZone::Top), (10, HotZone::Top)]
+        );
+    }
+
+    #[test]
+    fn missing_cursor_positions_are_ignored() {
+        let trace = [lost(2), stay(TOP, 2)].concat();
+        assert_eq!(
+            run(config(&[HotZone::Top]), &trace),
+            vec![(2, HotZone::Top)]
+        );
+    }
+
+    #[test]
+    fn actions_default_to_showing_launcher() {
+        let config: MouseEdgeConfig = serde_json::from_str(
+            r#"{
+                "zones": ["topLeft", "right"],
+                "actions": {
+                    "topLeft": { "type": "openGroup", "groupId": "g1" },
+                    "right": { "type": "hide" }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.action_for(HotZone::TopLeft),
+            EdgeAction::OpenGroup {
+                group_id: "g1".to_string()
+            }
+        );
+        assert_eq!(config.action_for(HotZone::Right), EdgeAction::Hide);
+        assert_eq!(config.action_for(HotZone::Top), EdgeAction::ShowLauncher);
+        // 省略した項目は既定値
+        assert_eq!(config.cooldown_ms, 1000);
+
+        let json = serde_json::to_value(EdgeAction::LaunchCell {
+            cell_id: "c1".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "launchCell", "cellId": "c1" })
+        );
+    }
+
+    #[test]
+    fn suppression_rules() {
+        let config = MouseEdgeConfig {
+            blocked_processes: vec!["Game".to_string(), "slides.EXE".to_string()],
+            ..MouseEdgeConfig::default()
+        };
+        assert_eq!(config.suppression_reason(false, None), None);
+        assert_eq!(
+            config.suppression_reason(true, None),
+            Some(SuppressReason::MouseButtonDown)
+        );
+        assert_eq!(
+            config.suppression_reason(false, foreground("player.exe", true).as_ref()),
+            Some(SuppressReason::Fullscreen(Some("player.exe".to_string())))
+        );
+        assert_eq!(
+            config.suppression_reason(false, foreground("player.exe", false).as_ref()),
+            None
+        );
+        // 大文字小文字と `.exe` の有無は区別しない
+        for process in ["game.exe", "GAME.EXE", "Slides.exe", "slides"] {
+            assert_eq!(
+                config.suppression_reason(false, foreground(process, false).as_ref()),
+                Some(SuppressReason::BlockedProcess(process.to_string())),
+                "{}",
+                process
+            );
+        }
+
+        let config = MouseEdgeConfig {
+            suppress_while_button_down: false,
+            suppress_in_fullscreen: false,
+            ..config
+        };
+        assert_eq!(
+            config.suppression_reason(true, foreground("player.exe", true).as_ref()),
+            None
+        );
+        // 抑止対象のプロセスは全画面表示の設定に関係なく抑止する
+        assert_eq!(
+            config.suppression_reason(false, foreground("game.exe", true).as_ref()),
+            Some(SuppressReason::BlockedProcess("game.exe".to_string()))
+        );
+    }
+
+    #[test]
+    fn drag_ending_at_edge_does_not_trigger() {
+        // ボタンを押したまま画面端に到達し、そのまま離しても反応しない
+        let trace = [drag(TOP, 3), stay(TOP, 3), stay(CENTER, 1), stay(TOP, 1)].concat();
+        let events = run_with(config(&[HotZone::Top]), &trace, None);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0],
+            (0, EdgeEvent::Suppressed(hit, SuppressReason::MouseButtonDown)) if hit.zone == HotZone::Top
+        ));
+        // 抑止では連続反応防止の待機は始まらない
+        assert!(matches!(&events[1], (7, EdgeEvent::Triggered(hit)) if hit.zone == HotZone::Top));
+
+        let config = MouseEdgeConfig {
+            suppress_while_button_down: false,
+            ..config(&[HotZone::Top])
+        };
+        assert_eq!(run(config, &drag(TOP, 2)), vec![(0, HotZone::Top)]);
+    }
+
+    #[test]
+    fn fullscreen_foreground_suppresses_activation() {
+        let trace = [stay(TOP, 2), stay(CENTER, 1), stay(TOP, 1)].concat();
+        let events = run_with(
+            config(&[HotZone::Top]),
+            &trace,
+            foreground("game.exe", true),
+        );
+        let reasons: Vec<_> = events
+            .iter()
+            .map(|(i, event)| match event {
+                EdgeEvent::Suppressed(_, reason) => (*i, Some(reason.to_string())),
+                EdgeEvent::Triggered(_) => (*i, None),
+            })
+            .collect();
+        let reason = Some("foreground window is fullscreen (game.exe)".to_string());
+        assert_eq!(reasons, vec![(0, reason.clone()), (3, reason)]);
+
+        // 全画面表示でなければ反応する（2回目は連続反応防止の待機中）
+        let events = run_with(
+            config(&[HotZone::Top]),
+            &trace,
+            foreground("game.exe", false),
+        );
+        assert!(matches!(events[..], [(0, EdgeEvent::Triggered(_))]));
+    }
+}
@@ -0,0 +1,103 @@
+use std::path::Path;
+use windows::core::PWSTR;
+use windows::Win32::Foundation::{CloseHandle, HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+};
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, VK_LBUTTON, VK_MBUTTON, VK_RBUTTON,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetClassNameW, GetForegroundWindow, GetShellWindow, GetWindowRect, GetWindowThreadProcessId,
+};
+
+use crate::edge_detector::ForegroundWindow;
+
+/// モニター全体を覆っていても全画面表示とみなさない、デスクトップ（壁紙）のウィンドウクラス
+const DESKTOP_CLASSES: [&str; 2] = ["Progman", "WorkerW"];
+
+/// マウスのボタン（左・右・中）のいずれかが押されているかを返します。
+pub fn mouse_button_down() -> bool {
+    [VK_LBUTTON, VK_RBUTTON, VK_MBUTTON]
+        .iter()
+        // 最上位ビットが立っていれば現在押されている
+        .any(|key| unsafe { GetAsyncKeyState(key.0 as i32) } as u16 & 0x8000 != 0)
+}
+
+/// 前面のウィンドウの実行ファイル名と、全画面表示かどうかを返します。
+///
+/// 前面のウィンドウがない場合や、ランチャー自身のウィンドウの場合は `None` を返します。
+pub fn foreground_window() -> Option<ForegroundWindow> {
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_invalid() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == std::process::id() {
+            return None;
+        }
+        Some(ForegroundWindow {
+            process_name: process_name(pid),
+            fullscreen: is_fullscreen(hwnd),
+        })
+    }
+}
+
+/// ウィンドウがモニター全体（タスクバーを含む）を覆っているかを返します。
+/// 最大化したウィンドウは作業領域のみを覆うため、全画面表示とはみなしません。
+unsafe fn is_fullscreen(hwnd: HWND) -> bool {
+    if hwnd == GetShellWindow() || DESKTOP_CLASSES.contains(&class_name(hwnd).as_str()) {
+        return false;
+    }
+
+    let mut rect = RECT::default();
+    if GetWindowRect(hwnd, &mut rect).is_err() {
+        return false;
+    }
+    let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+        return false;
+    }
+
+    let screen = info.rcMonitor;
+    rect.left <= screen.left
+        && rect.top <= screen.top
+        && rect.right >= screen.right
+        && rect.bottom >= screen.bottom
+}
+
+unsafe fn class_name(hwnd: HWND) -> String {
+    let mut buffer = [0u16; 256];
+    let len = GetClassNameW(hwnd, &mut buffer).max(0) as usize;
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+/// プロセスの実行ファイル名（例: `game.exe`）を返します。
+unsafe fn process_name(pid: u32) -> Option<String> {
+    // 管理者権限のプロセスは開けないことがあるため、その場合は名前なしとして扱う
+    let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+    let mut buffer = [0u16; 1024];
+    let mut len = buffer.len() as u32;
+    let result = QueryFullProcessImageNameW(
+        process,
+        PROCESS_NAME_WIN32,
+        PWSTR(buffer.as_mut_ptr()),
+        &mut len,
+    );
+    let _ = CloseHandle(process);
+    result.ok()?;
+
+    let path = String::from_utf16_lossy(&buffer[..len as usize]);
+    Path::new(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
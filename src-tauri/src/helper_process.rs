@@ -0,0 +1,280 @@
+use serde::de::DeserializeOwned;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 終了を確認する間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// エラーメッセージに含める出力の最大文字数
+const MAX_OUTPUT_IN_ERROR: usize = 200;
+
+/// 外部のヘルパー（PowerShell など）を実行し、標準出力を返します。
+///
+/// `timeout` を過ぎても終了しない場合はプロセスを終了させてエラーを返します。
+/// 終了コードが 0 以外の場合は標準エラー出力の内容をエラーとして返します。
+/// ウィンドウを表示しないなどのプラットフォーム固有の設定は、呼び出し側で `command` に行います。
+pub fn run(mut command: Command, timeout: Duration) -> Result<Vec<u8>, String> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute {}: {}", program, e))?;
+
+    // パイプのバッファが一杯になってヘルパーが止まらないよう、終了を待つ間も読み続ける
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break status,
+            None if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                // 子プロセスがパイプを引き継いでいると読み取りが終わらないため、読み取りスレッドは待たない
+                return Err(format!(
+                    "{} timed out after {} seconds",
+                    program,
+                    timeout.as_secs_f32()
+                ));
+            }
+            None => thread::sleep(POLL_INTERVAL),
+        }
+    };
+
+    let collect = |reader: Option<thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    };
+    let stdout = collect(stdout);
+    let stderr = collect(stderr);
+
+    if !status.success() {
+        let message = decode_output(&stderr);
+        return Err(format!(
+            "{} exited with {}: {}",
+            program,
+            status,
+            truncate(message.trim())
+        ));
+    }
+    Ok(stdout)
+}
+
+/// ヘルパーを実行し、JSON の出力を一覧として返します（`run` と `parse_json_list` の組み合わせ）。
+pub fn run_json<T: DeserializeOwned>(
+    command: Command,
+    timeout: Duration,
+) -> Result<Vec<T>, String> {
+    parse_json_list(&run(command, timeout)?)
+}
+
+/// ヘルパーの出力をテキストに変換します。
+///
+/// BOM（UTF-8 / UTF-16LE / UTF-16BE）があればそれに従い、BOM がなくても
+/// 2バイト目が 0 の場合は UTF-16LE（PowerShell の `Out-File` などの既定）とみなします。
+pub fn decode_output(bytes: &[u8]) -> String {
+    let text = match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8_lossy(rest).to_string(),
+        [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes),
+        [first, 0, ..] if *first != 0 => decode_utf16(bytes, u16::from_le_bytes),
+        _ => String::from_utf8_lossy(bytes).to_string(),
+    };
+    // 出力の途中（複数回の書き込みの先頭）に BOM が含まれることがあるため取り除く
+    text.replace('\u{feff}', "")
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// ヘルパーの JSON の出力を一覧としてパースします。
+///
+/// PowerShell の `ConvertTo-Json` は、結果が複数の場合は配列、1件の場合はオブジェクト、
+/// 0件の場合は何も出力しない（または `null`）ため、いずれも一覧として扱います。
+pub fn parse_json_list<T: DeserializeOwned>(bytes: &[u8]) -> Result<Vec<T>, String> {
+    let text = decode_output(bytes);
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let value: serde_json::Value = serde_json::from_str(trimmed).map_err(|e| {
+        format!(
+            "Failed to parse JSON: {} | Output: {}",
+            e,
+            truncate(trimmed)
+        )
+    })?;
+    let items = match value {
+        serde_json::Value::Null => Vec::new(),
+        serde_json::Value::Array(items) => items,
+        single => vec![single],
+    };
+    items
+        .into_iter()
+        .map(|item| {
+            serde_json::from_value(item).map_err(|e| format!("Unexpected JSON item: {}", e))
+        })
+        .collect()
+}
+
+fn read_in_background<R: Read + Send + 'static>(mut reader: R) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = reader.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_OUTPUT_IN_ERROR) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct StartApp {
+        #[serde(rename = "Name")]
+        name: String,
+        #[serde(rename = "AppID")]
+        app_id: String,
+    }
+
+    fn app(name: &str, app_id: &str) -> StartApp {
+        StartApp {
+            name: name.to_string(),
+            app_id: app_id.to_string(),
+        }
+    }
+
+    fn utf16le(text: &str, bom: bool) -> Vec<u8> {
+        let mut bytes = if bom { vec![0xFF, 0xFE] } else { Vec::new() };
+        bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        bytes
+    }
+
+    /// `Get-StartApps | Select-Object Name, AppID | ConvertTo-Json -Compress` の出力（複数件）
+    const ARRAY_OUTPUT: &str = "[{\"Name\":\"電卓\",\"AppID\":\"Microsoft.WindowsCalculator_8wekyb3d8bbwe!App\"},\
+        {\"Name\":\"Paint \\\"Classic\\\" \\u0026 Co\",\"AppID\":\"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\\\mspaint.exe\"}]\r\n";
+    /// 同じコマンドの出力（1件のみ）
+    const SINGLE_OUTPUT: &str =
+        "{\"Name\":\"Windows Terminal\",\"AppID\":\"Microsoft.WindowsTerminal_8wekyb3d8bbwe!App\"}\r\n";
+
+    #[test]
+    fn parses_array_with_escaped_names() {
+        let apps: Vec<StartApp> = parse_json_list(ARRAY_OUTPUT.as_bytes()).unwrap();
+        assert_eq!(
+            apps,
+            vec![
+                app("電卓", "Microsoft.WindowsCalculator_8wekyb3d8bbwe!App"),
+                app(
+                    "Paint \"Classic\" & Co",
+                    r"{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\mspaint.exe"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_single_object_as_list() {
+        let apps: Vec<StartApp> = parse_json_list(SINGLE_OUTPUT.as_bytes()).unwrap();
+        assert_eq!(
+            apps,
+            vec![app(
+                "Windows Terminal",
+                "Microsoft.WindowsTerminal_8wekyb3d8bbwe!App"
+            )]
+        );
+    }
+
+    #[test]
+    fn empty_output_is_empty_list() {
+        for output in ["", "\r\n", "  \n", "null\r\n", "\u{feff}"] {
+            let apps: Vec<StartApp> = parse_json_list(output.as_bytes()).unwrap();
+            assert!(apps.is_empty(), "{:?}", output);
+        }
+        let apps: Vec<StartApp> = parse_json_list(&utf16le("\r\n", true)).unwrap();
+        assert!(apps.is_empty());
+    }
+
+    #[test]
+    fn handles_utf8_bom() {
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice(SINGLE_OUTPUT.as_bytes());
+        let apps: Vec<StartApp> = parse_json_list(&bytes).unwrap();
+        assert_eq!(apps.len(), 1);
+    }
+
+    #[test]
+    fn handles_utf16_output() {
+        for bom in [true, false] {
+            let apps: Vec<StartApp> = parse_json_list(&utf16le(ARRAY_OUTPUT, bom)).unwrap();
+            assert_eq!(apps.len(), 2);
+            assert_eq!(apps[0].name, "電卓");
+        }
+
+        let mut big_endian = vec![0xFE, 0xFF];
+        big_endian.extend(
+            SINGLE_OUTPUT
+                .encode_utf16()
+                .flat_map(|unit| unit.to_be_bytes()),
+        );
+        let apps: Vec<StartApp> = parse_json_list(&big_endian).unwrap();
+        assert_eq!(apps[0].name, "Windows Terminal");
+    }
+
+    #[test]
+    fn reports_invalid_output() {
+        let error =
+            parse_json_list::<StartApp>(b"Get-StartApps : The term is not recognized").unwrap_err();
+        assert!(error.starts_with("Failed to parse JSON"), "{}", error);
+
+        let error = parse_json_list::<StartApp>(b"[{\"Name\":\"x\"}]").unwrap_err();
+        assert!(error.contains("AppID"), "{}", error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn returns_output_of_helper() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "printf '[{\"Name\":\"a\",\"AppID\":\"b\"}]'"]);
+        let apps: Vec<StartApp> = run_json(command, Duration::from_secs(10)).unwrap();
+        assert_eq!(apps, vec![app("a", "b")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_failure_with_stderr() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo broken >&2; exit 3"]);
+        let error = run(command, Duration::from_secs(10)).unwrap_err();
+        assert!(error.contains("broken"), "{}", error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn kills_helper_after_timeout() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 10"]);
+        let started = Instant::now();
+        let error = run(command, Duration::from_millis(200)).unwrap_err();
+        assert!(error.contains("timed out"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,647 @@
+use image::{ImageOutputFormat, RgbaImage};
+use std::fs;
+
+use crate::icon_pixels;
+
+/// リソースの種類: アイコン画像
+const RT_ICON: u32 = 3;
+/// リソースの種類: アイコングループ（`.ico` のディレクトリに相当）
+const RT_GROUP_ICON: u32 = 14;
+
+/// リソースディレクトリで辿るサブディレクトリの最大数（循環した不正なファイルで無限ループしないため）
+const MAX_RESOURCE_DEPTH: usize = 3;
+
+/// PNG形式の画像データの先頭シグネチャ
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// アイコンに含まれる1枚の画像。
+#[derive(Debug, Clone)]
+pub struct IconEntry {
+    /// 幅（ピクセル）
+    pub width: u32,
+    /// 高さ（ピクセル）
+    pub height: u32,
+    /// 1ピクセルあたりのビット数（PNGの場合は32）
+    pub bit_count: u16,
+    /// 画像データ（PNG、または BITMAPINFOHEADER から始まる DIB）
+    pub data: Vec<u8>,
+}
+
+impl IconEntry {
+    /// 画像データが PNG 形式かどうか。
+    pub fn is_png(&self) -> bool {
+        self.data.starts_with(PNG_SIGNATURE)
+    }
+
+    /// 画像を RGBA にデコードします。
+    pub fn decode(&self) -> Result<RgbaImage, String> {
+        if self.is_png() {
+            image::load_from_memory(&self.data)
+                .map(|img| img.to_rgba8())
+                .map_err(|e| e.to_string())
+        } else {
+            decode_dib(&self.data)
+        }
+    }
+
+    /// 画像を PNG データとして返します。PNG 形式の場合はそのまま返します。
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        if self.is_png() {
+            return Ok(self.data.clone());
+        }
+
+        let mut png_buffer = Vec::new();
+        image::DynamicImage::ImageRgba8(self.decode()?)
+            .write_to(
+                &mut std::io::Cursor::new(&mut png_buffer),
+                ImageOutputFormat::Png,
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(png_buffer)
+    }
+}
+
+/// `.ico`、または PE ファイル（.exe / .dll）内のアイコンから、`size` に最適な画像を PNG データとして返します。
+///
+/// `index` の意味は `PrivateExtractIconsW` と同じです。
+/// - 0以上: ファイル内の `index` 番目のアイコングループ（`.ico` の場合は0のみ有効）
+/// - 負の値: リソースID が `-index` のアイコングループ
+pub fn extract_icon(path: &str, index: i32, size: u32) -> Result<Vec<u8>, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+
+    let entries = if data.starts_with(b"MZ") {
+        read_pe_icon_group(&data, index)?
+    } else if index == 0 {
+        read_ico(&data)?
+    } else {
+        return Err(format!("Icon index {} does not exist in {}", index, path));
+    };
+
+    best_entry(&entries, size)
+        .ok_or_else(|| format!("No icon images found in {}", path))?
+        .to_png()
+}
+
+/// `size` に最適な画像を選びます。
+///
+/// `size` 以上で最小のものを優先し（同じサイズならビット数が多いもの）、なければ最大のものを返します。
+pub fn best_entry(entries: &[IconEntry], size: u32) -> Option<&IconEntry> {
+    let key = |e: &IconEntry| (e.width.max(e.height), e.bit_count);
+    entries
+        .iter()
+        .filter(|e| e.width.max(e.height) >= size)
+        .min_by(|a, b| {
+            let (size_a, bits_a) = key(a);
+            let (size_b, bits_b) = key(b);
+            size_a.cmp(&size_b).then(bits_b.cmp(&bits_a))
+        })
+        .or_else(|| entries.iter().max_by_key(|e| key(e)))
+}
+
+/// `.ico` ファイルの内容を読み込み、含まれる画像の一覧を返します。
+pub fn read_ico(data: &[u8]) -> Result<Vec<IconEntry>, String> {
+    let reserved = read_u16(data, 0)?;
+    let kind = read_u16(data, 2)?;
+    if reserved != 0 || kind != 1 {
+        return Err("Not an ICO file".to_string());
+    }
+
+    let count = read_u16(data, 4)? as usize;
+    (0..count)
+        .map(|i| {
+            let entry = 6 + i * 16;
+            let size = read_u32(data, entry + 8)? as usize;
+            let offset = read_u32(data, entry + 12)? as usize;
+            Ok(IconEntry {
+                width: dimension(read_u8(data, entry)?),
+                height: dimension(read_u8(data, entry + 1)?),
+                bit_count: read_u16(data, entry + 6)?,
+                data: slice(data, offset, size)?.to_vec(),
+            })
+        })
+        .collect()
+}
+
+/// PE ファイル内の指定されたアイコングループ（`RT_GROUP_ICON`）の画像の一覧を返します。
+pub fn read_pe_icon_group(data: &[u8], index: i32) -> Result<Vec<IconEntry>, String> {
+    let pe = PeResources::parse(data)?;
+
+    let groups = pe.entries(pe.root, Some(RT_GROUP_ICON))?;
+    let group = if index >= 0 {
+        groups.get(index as usize)
+    } else {
+        let id = index.unsigned_abs();
+        groups.iter().find(|(entry_id, _)| *entry_id == Some(id))
+    }
+    .ok_or_else(|| format!("Icon group {} not found", index))?;
+
+    let group_data = pe.first_data(group.1)?;
+    let count = read_u16(group_data, 4)? as usize;
+    let icons = pe.entries(pe.root, Some(RT_ICON))?;
+
+    let mut entries = Vec::with_capacity(count);
+    for i in 0..count {
+        let entry = 6 + i * 14;
+        let id = read_u16(group_data, entry + 12)? as u32;
+        // グループが参照するアイコンが存在しない場合はスキップする
+        let Some((_, icon)) = icons.iter().find(|(icon_id, _)| *icon_id == Some(id)) else {
+            continue;
+        };
+        entries.push(IconEntry {
+            width: dimension(read_u8(group_data, entry)?),
+            height: dimension(read_u8(group_data, entry + 1)?),
+            bit_count: read_u16(group_data, entry + 6)?,
+            data: pe.first_data(*icon)?.to_vec(),
+        });
+    }
+    Ok(entries)
+}
+
+/// PE ファイルのリソースセクション。
+struct PeResources<'a> {
+    data: &'a [u8],
+    /// リソースディレクトリのファイル上のオフセット
+    root: usize,
+    /// (仮想アドレス, 仮想サイズ, ファイル上のオフセット) のセクション一覧
+    sections: Vec<(u32, u32, u32)>,
+}
+
+impl<'a> PeResources<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, String> {
+        let pe_offset = read_u32(data, 0x3C)? as usize;
+        if slice(data, pe_offset, 4)? != b"PE\0\0" {
+            return Err("Invalid PE signature".to_string());
+        }
+
+        let coff = pe_offset + 4;
+        let section_count = read_u16(data, coff + 2)? as usize;
+        let optional_size = read_u16(data, coff + 16)? as usize;
+        let optional = coff + 20;
+
+        // データディレクトリの位置は PE32 / PE32+ で異なる
+        let data_directories = match read_u16(data, optional)? {
+            0x10b => optional + 96,
+            0x20b => optional + 112,
+            magic => return Err(format!("Unknown optional header magic: {:#x}", magic)),
+        };
+        // リソースディレクトリはデータディレクトリの3番目
+        let resource_rva = read_u32(data, data_directories + 2 * 8)?;
+        if resource_rva == 0 {
+            return Err("No resources in PE file".to_string());
+        }
+
+        let section_table = optional + optional_size;
+        let sections = (0..section_count)
+            .map(|i| {
+                let section = section_table + i * 40;
+                Ok((
+                    read_u32(data, section + 12)?,
+                    read_u32(data, section + 8)?.max(read_u32(data, section + 16)?),
+                    read_u32(data, section + 20)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut resources = Self {
+            data,
+            root: 0,
+            sections,
+        };
+        resources.root = resources.rva_to_offset(resource_rva)?;
+        Ok(resources)
+    }
+
+    /// 仮想アドレスをファイル上のオフセットに変換します。
+    fn rva_to_offset(&self, rva: u32) -> Result<usize, String> {
+        self.sections
+            .iter()
+            .find(|(address, size, _)| {
+                rva >= *address && address.checked_add(*size).is_some_and(|end| rva < end)
+            })
+            .and_then(|(address, _, raw)| (rva - address).checked_add(*raw))
+            .map(|offset| offset as usize)
+            .ok_or_else(|| format!("RVA {:#x} is outside of all sections", rva))
+    }
+
+    /// リソースディレクトリの (ID, 子のオフセット) の一覧を返します。名前付きエントリの ID は `None` です。
+    ///
+    /// `id` を指定した場合は、その ID のエントリの子ディレクトリの一覧を返します。
+    fn entries(&self, dir: usize, id: Option<u32>) -> Result<Vec<(Option<u32>, usize)>, String> {
+        let dir = match id {
+            Some(id) => self
+                .list(dir)?
+                .into_iter()
+                .find(|(entry_id, _)| *entry_id == Some(id))
+                .map(|(_, child)| child)
+                .ok_or_else(|| format!("Resource type {} not found", id))?,
+            None => dir,
+        };
+        self.list(dir)
+    }
+
+    fn list(&self, dir: usize) -> Result<Vec<(Option<u32>, usize)>, String> {
+        let named = read_u16(self.data, dir + 12)? as usize;
+        let ids = read_u16(self.data, dir + 14)? as usize;
+        (0..named + ids)
+            .map(|i| {
+                let entry = dir + 16 + i * 8;
+                let name = read_u32(self.data, entry)?;
+                let offset = read_u32(self.data, entry + 4)?;
+                let id = (name & 0x8000_0000 == 0).then_some(name);
+                Ok((id, self.root + (offset & 0x7FFF_FFFF) as usize))
+            })
+            .collect()
+    }
+
+    /// リソース（名前/ID 階層）の最初の言語のデータを返します。
+    fn first_data(&self, node: usize) -> Result<&'a [u8], String> {
+        // 子がサブディレクトリ（最上位ビット）の間は最初のエントリを辿る
+        let mut node = node;
+        for _ in 0..=MAX_RESOURCE_DEPTH {
+            let offset = read_u32(self.data, node + 16 + 4)?;
+            let child = self.root + (offset & 0x7FFF_FFFF) as usize;
+            if offset & 0x8000_0000 == 0 {
+                let rva = read_u32(self.data, child)?;
+                let size = read_u32(self.data, child + 4)? as usize;
+                return slice(self.data, self.rva_to_offset(rva)?, size);
+            }
+            node = child;
+        }
+        Err("Resource directory is nested too deeply".to_string())
+    }
+}
+
+/// `BITMAPINFOHEADER` から始まる DIB 形式のアイコン画像（XORビットマップ + ANDマスク）をデコードします。
+///
+/// 1 / 4 / 8 / 16 / 24 / 32 ビットに対応します。
+/// 32ビットでアルファチャンネルがすべて0の場合、および32ビット未満の場合は ANDマスクで透過を決定します。
+pub fn decode_dib(data: &[u8]) -> Result<RgbaImage, String> {
+    let header_size = read_u32(data, 0)? as usize;
+    let width = read_i32(data, 4)?;
+    // アイコンの DIB の高さは XOR と AND の2枚分
+    let height = read_i32(data, 8)?
+        .checked_abs()
+        .ok_or("Invalid DIB height")?
+        / 2;
+    let bit_count = read_u16(data, 14)? as u32;
+    let compression = read_u32(data, 16)?;
+    let colors_used = read_u32(data, 32)? as usize;
+
+    if width <= 0 || height <= 0 || width > 1024 || height > 1024 {
+        return Err(format!("Invalid DIB size: {}x{}", width, height));
+    }
+    // BI_RGB (0) と BI_BITFIELDS (3, 16/32ビットの標準マスクのみ) に対応
+    if compression != 0 && compression != 3 {
+        return Err(format!("Unsupported DIB compression: {}", compression));
+    }
+    let (width, height) = (width as usize, height as usize);
+
+    let palette_size = if bit_count <= 8 {
+        if colors_used == 0 {
+            1 << bit_count
+        } else {
+            colors_used
+        }
+    } else {
+        0
+    };
+    let bitfields = if compression == 3 { 12 } else { 0 };
+    let palette = header_size + bitfields;
+    let pixels = palette + palette_size * 4;
+    let stride = (width * bit_count as usize).div_ceil(32) * 4;
+    let mask = pixels + stride * height;
+    let mask_stride = width.div_ceil(32) * 4;
+
+    let mut image = RgbaImage::new(width as u32, height as u32);
+    for y in 0..height {
+        // DIB は下から上の順に格納されている
+        let row = slice(data, pixels + (height - 1 - y) * stride, stride)?;
+        for x in 0..width {
+            // 32ビット未満の場合、アルファは後で ANDマスクから決定する
+            let rgba = match bit_count {
+                32 => [row[x * 4 + 2], row[x * 4 + 1], row[x * 4], row[x * 4 + 3]],
+                24 => [row[x * 3 + 2], row[x * 3 + 1], row[x * 3], 0],
+                16 => {
+                    let v = u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]);
+                    let expand = |c: u16| ((c << 3) | (c >> 2)) as u8;
+                    [
+                        expand((v >> 10) & 0x1F),
+                        expand((v >> 5) & 0x1F),
+                        expand(v & 0x1F),
+                        0,
+                    ]
+                }
+                1 | 4 | 8 => {
+                    let bits = bit_count as usize;
+                    let byte = row[x * bits / 8];
+                    let shift = 8 - bits - (x * bits % 8);
+                    let index = ((byte >> shift) as usize) & ((1 << bits) - 1);
+                    let color = slice(data, palette + index * 4, 4)?;
+                    [color[2], color[1], color[0], 0]
+                }
+                _ => return Err(format!("Unsupported bit count: {}", bit_count)),
+            };
+            image.put_pixel(x as u32, y as u32, image::Rgba(rgba));
+        }
+    }
+
+    // 32ビットでアルファが有効な場合は ANDマスクを使用しない。ANDマスクが欠けている場合はすべて不透明とする
+    let mask = slice(data, mask, mask_stride * height)
+        .and_then(|mask_data| icon_pixels::mask_from_bits(mask_data, width, height, true))
+        .ok();
+    icon_pixels::apply_and_mask(&mut image, mask.as_deref());
+
+    Ok(image)
+}
+
+/// ディレクトリエントリのサイズ（0 は 256 を意味する）
+fn dimension(value: u8) -> u32 {
+    if value == 0 {
+        256
+    } else {
+        value as u32
+    }
+}
+
+fn slice(data: &[u8], offset: usize, len: usize) -> Result<&[u8], String> {
+    offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset..end))
+        .ok_or_else(|| format!("Unexpected end of data at {:#x}", offset))
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8, String> {
+    Ok(slice(data, offset, 1)?[0])
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    let bytes = slice(data, offset, 2)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    let bytes = slice(data, offset, 4)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Result<i32, String> {
+    read_u32(data, offset).map(|v| v as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2x2 の 32ビット DIB（アルファなし）と ANDマスクを作成します。
+    fn dib_2x2(mask_bits: [u8; 2]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&40u32.to_le_bytes());
+        data.extend_from_slice(&2i32.to_le_bytes());
+        data.extend_from_slice(&4i32.to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&32u16.to_le_bytes());
+        data.extend_from_slice(&[0u8; 24]);
+        // 下の行 → 上の行 (BGRA)
+        data.extend_from_slice(&[0, 0, 255, 0, 0, 255, 0, 0]);
+        data.extend_from_slice(&[255, 0, 0, 0, 255, 255, 255, 0]);
+        // ANDマスク (下の行 → 上の行、4バイト境界)
+        data.extend_from_slice(&[mask_bits[1], 0, 0, 0]);
+        data.extend_from_slice(&[mask_bits[0], 0, 0, 0]);
+        data
+    }
+
+    fn ico(entries: &[(u8, u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0, 0, 1, 0];
+        data.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        let mut offset = 6 + entries.len() * 16;
+        for (size, bits, image) in entries {
+            data.extend_from_slice(&[*size, *size, 0, 0]);
+            data.extend_from_slice(&1u16.to_le_bytes());
+            data.extend_from_slice(&bits.to_le_bytes());
+            data.extend_from_slice(&(image.len() as u32).to_le_bytes());
+            data.extend_from_slice(&(offset as u32).to_le_bytes());
+            offset += image.len();
+        }
+        for (_, _, image) in entries {
+            data.extend_from_slice(image);
+        }
+        data
+    }
+
+    #[test]
+    fn decodes_dib_with_and_mask() {
+        // 上の行の右のピクセルだけを透過にする
+        let image = decode_dib(&dib_2x2([0b0100_0000, 0])).unwrap();
+        assert_eq!(image.dimensions(), (2, 2));
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(1, 0).0[3], 0);
+        assert_eq!(image.get_pixel(0, 1).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn reads_ico_and_picks_best_size() {
+        let data = ico(&[
+            (16, 32, dib_2x2([0, 0])),
+            (32, 32, dib_2x2([0, 0])),
+            (0, 32, dib_2x2([0, 0])),
+        ]);
+        let entries = read_ico(&data).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].width, 256);
+        assert_eq!(best_entry(&entries, 24).unwrap().width, 32);
+        assert_eq!(best_entry(&entries, 512).unwrap().width, 256);
+    }
+
+    /// リソースセクションの仮想アドレスとファイル上のオフセット
+    const SECTION_RVA: u32 = 0x1000;
+    const SECTION_OFFSET: usize = 0x200;
+    /// リソースディレクトリのエントリがサブディレクトリを指すことを示すフラグ
+    const SUBDIRECTORY: u32 = 0x8000_0000;
+    /// ルートディレクトリ（RT_ICON と RT_GROUP_ICON の2エントリ）のサイズ
+    const ROOT_SIZE: usize = 16 + 2 * 8;
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// ID のエントリのみのリソースディレクトリを書き込み、そのオフセットを返します。
+    fn res_dir(res: &mut Vec<u8>, entries: &[(u32, u32)]) -> u32 {
+        let offset = res.len() as u32;
+        res.extend_from_slice(&[0u8; 14]);
+        res.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (id, child) in entries {
+            res.extend_from_slice(&id.to_le_bytes());
+            res.extend_from_slice(&child.to_le_bytes());
+        }
+        offset
+    }
+
+    /// データと、それを指す言語ディレクトリを書き込み、言語ディレクトリを指すエントリの値を返します。
+    fn res_leaf(res: &mut Vec<u8>, data: &[u8]) -> u32 {
+        let data_offset = res.len() as u32;
+        res.extend_from_slice(data);
+        let entry = res.len() as u32;
+        res.extend_from_slice(&(SECTION_RVA + data_offset).to_le_bytes());
+        res.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        res.extend_from_slice(&[0u8; 8]);
+        res_dir(res, &[(0x0411, entry)]) | SUBDIRECTORY
+    }
+
+    /// 先頭に確保した領域にルートディレクトリを書き込みます。
+    fn res_root(res: &mut [u8], icon_dir: u32, group_dir: u32) {
+        let mut root = Vec::new();
+        res_dir(
+            &mut root,
+            &[
+                (RT_ICON, icon_dir | SUBDIRECTORY),
+                (RT_GROUP_ICON, group_dir | SUBDIRECTORY),
+            ],
+        );
+        put(res, 0, &root);
+    }
+
+    /// アイコングループ (リソースID, [(サイズ, アイコンのID)]) とアイコン (ID, 画像) のリソースを作成します。
+    fn icon_resources(groups: &[(u32, Vec<(u8, u16)>)], icons: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let mut res = vec![0u8; ROOT_SIZE];
+        let icon_entries: Vec<(u32, u32)> = icons
+            .iter()
+            .map(|(id, image)| (*id, res_leaf(&mut res, image)))
+            .collect();
+        let icon_dir = res_dir(&mut res, &icon_entries);
+
+        let group_entries: Vec<(u32, u32)> = groups
+            .iter()
+            .map(|(id, members)| {
+                let mut group = vec![0, 0, 1, 0];
+                group.extend_from_slice(&(members.len() as u16).to_le_bytes());
+                for (size, icon_id) in members {
+                    group.extend_from_slice(&[*size, *size, 0, 0]);
+                    group.extend_from_slice(&1u16.to_le_bytes());
+                    group.extend_from_slice(&32u16.to_le_bytes());
+                    group.extend_from_slice(&0u32.to_le_bytes());
+                    group.extend_from_slice(&icon_id.to_le_bytes());
+                }
+                (*id, res_leaf(&mut res, &group))
+            })
+            .collect();
+        let group_dir = res_dir(&mut res, &group_entries);
+
+        res_root(&mut res, icon_dir, group_dir);
+        res
+    }
+
+    /// リソースセクションを1つだけ持つ最小限の PE32 ファイルを作成します。
+    fn pe_file(resources: &[u8]) -> Vec<u8> {
+        let mut data = vec![0u8; SECTION_OFFSET];
+        put(&mut data, 0, b"MZ");
+        put(&mut data, 0x3C, &0x40u32.to_le_bytes());
+        put(&mut data, 0x40, b"PE\0\0");
+
+        let coff = 0x44;
+        let optional = coff + 20;
+        let optional_size = 96 + 16 * 8;
+        put(&mut data, coff + 2, &1u16.to_le_bytes());
+        put(&mut data, coff + 16, &(optional_size as u16).to_le_bytes());
+        put(&mut data, optional, &0x10bu16.to_le_bytes());
+        put(&mut data, optional + 96 + 2 * 8, &SECTION_RVA.to_le_bytes());
+        put(
+            &mut data,
+            optional + 96 + 2 * 8 + 4,
+            &(resources.len() as u32).to_le_bytes(),
+        );
+
+        let section = optional + optional_size;
+        put(&mut data, section, b".rsrc\0\0\0");
+        put(
+            &mut data,
+            section + 8,
+            &(resources.len() as u32).to_le_bytes(),
+        );
+        put(&mut data, section + 12, &SECTION_RVA.to_le_bytes());
+        put(
+            &mut data,
+            section + 16,
+            &(resources.len() as u32).to_le_bytes(),
+        );
+        put(
+            &mut data,
+            section + 20,
+            &(SECTION_OFFSET as u32).to_le_bytes(),
+        );
+
+        data.extend_from_slice(resources);
+        data
+    }
+
+    fn group_widths(data: &[u8], index: i32) -> Result<Vec<u32>, String> {
+        read_pe_icon_group(data, index).map(|entries| entries.iter().map(|e| e.width).collect())
+    }
+
+    #[test]
+    fn selects_pe_icon_group_by_index_or_resource_id() {
+        let pe = pe_file(&icon_resources(
+            &[
+                (100, vec![(16, 1)]),
+                (200, vec![(32, 2), (48, 3)]),
+                // 存在しないアイコン (ID 9) を参照するエントリは読み飛ばす
+                (300, vec![(24, 9), (16, 1)]),
+            ],
+            &[
+                (1, dib_2x2([0, 0])),
+                (2, dib_2x2([0, 0])),
+                (3, dib_2x2([0, 0])),
+            ],
+        ));
+
+        // 0以上はファイル内の順番
+        assert_eq!(group_widths(&pe, 0).unwrap(), vec![16]);
+        assert_eq!(group_widths(&pe, 1).unwrap(), vec![32, 48]);
+        assert_eq!(group_widths(&pe, 2).unwrap(), vec![16]);
+        assert!(group_widths(&pe, 3).is_err());
+        // 負の値はリソースID
+        assert_eq!(group_widths(&pe, -200).unwrap(), vec![32, 48]);
+        assert_eq!(group_widths(&pe, -100).unwrap(), vec![16]);
+        assert!(group_widths(&pe, -1).is_err());
+
+        let entries = read_pe_icon_group(&pe, -200).unwrap();
+        assert_eq!(entries[0].decode().unwrap().dimensions(), (2, 2));
+    }
+
+    #[test]
+    fn rejects_cyclic_resource_directories() {
+        let mut res = vec![0u8; ROOT_SIZE];
+        // 自分自身をサブディレクトリとして指すディレクトリ
+        let cyclic = res.len() as u32;
+        res_dir(&mut res, &[(0x0411, cyclic | SUBDIRECTORY)]);
+        let group_dir = res_dir(&mut res, &[(100, cyclic | SUBDIRECTORY)]);
+        let icon_dir = res_dir(&mut res, &[]);
+        res_root(&mut res, icon_dir, group_dir);
+
+        assert!(read_pe_icon_group(&pe_file(&res), 0).is_err());
+    }
+
+    #[test]
+    fn rejects_overflowing_section_ranges() {
+        let pe = PeResources {
+            data: &[],
+            root: 0,
+            sections: vec![(0xFFFF_F000, 0x2000, 0x100), (0x1000, 0x100, u32::MAX)],
+        };
+        assert!(pe.rva_to_offset(0xFFFF_FFFF).is_err());
+        assert!(pe.rva_to_offset(0x1080).is_err());
+    }
+
+    #[test]
+    fn rejects_minimum_dib_height() {
+        let mut data = dib_2x2([0, 0]);
+        put(&mut data, 8, &i32::MIN.to_le_bytes());
+        assert!(decode_dib(&data).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let mut data = ico(&[(16, 32, dib_2x2([0, 0]))]);
+        data.truncate(30);
+        assert!(read_ico(&data).is_err());
+    }
+}
@@ -1,17 +1,55 @@
 use base64::Engine; // Needed for .encode()
-use image::ImageOutputFormat;
+use image::imageops::FilterType;
+use image::{GenericImageView, ImageOutputFormat};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use tauri::Manager;
 use windows::Win32::Graphics::Gdi::{
-    CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC,
-    SelectObject, BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HBITMAP,
+    DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO, BITMAPINFOHEADER,
+    BI_RGB, DIB_RGB_COLORS, HBITMAP, HDC,
+};
+use windows::Win32::Storage::FileSystem::{
+    FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_NORMAL, FILE_FLAGS_AND_ATTRIBUTES,
+};
+use windows::Win32::UI::Controls::{IImageList, ILD_TRANSPARENT};
+use windows::Win32::UI::Shell::{
+    SHGetFileInfoW, SHGetImageList, SHFILEINFOW, SHGFI_FLAGS, SHGFI_SYSICONINDEX,
+    SHGFI_USEFILEATTRIBUTES, SHIL_EXTRALARGE, SHIL_JUMBO, SHIL_LARGE, SHIL_SMALL,
 };
-use windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NORMAL;
-use windows::Win32::UI::Shell::{SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON};
 use windows::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON};
 
+#[cfg(not(target_os = "windows"))]
+use crate::desktop_entry::DesktopEntry;
+use crate::ico_reader;
+use crate::icon_index::{IconIndex, IconIndexEntry};
+use crate::icon_memory_cache::IconMemoryCache;
+use crate::icon_palette::{self, IconPalette};
+use crate::icon_pixels;
+use crate::icon_protocol;
+#[cfg(not(target_os = "windows"))]
+use crate::icon_theme;
+use crate::icon_transform::IconTransform;
+use crate::mime_type;
+use crate::path_utils;
+use crate::shortcut_utils;
+use crate::svg_render;
+use crate::uwp_catalog::UwpCatalog;
+
+/// 抽出時に要求するネイティブサイズ（システムイメージリストのサイズに対応）
+const NATIVE_ICON_SIZES: [u32; 4] = [16, 32, 48, 256];
+/// 要求できる最大サイズ
+const MAX_ICON_SIZE: u32 = 256;
+/// サイズ指定がない場合のファイルアイコンのサイズ（従来の `SHGFI_LARGEICON` 相当）
+const DEFAULT_FILE_ICON_SIZE: u32 = 32;
+/// サイズ指定がない場合のリソースアイコンのサイズ
+const DEFAULT_RESOURCE_ICON_SIZE: u32 = 256;
+/// 画像そのものをアイコンとして読み込むリソースファイルの拡張子（UWPアプリのロゴなど）
+const IMAGE_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "webp", "svg", "svgz"];
+
 // GDIオブジェクトの確実なクリーンアップを保証するためのラッパー構造体
 struct GdiObject<T: Copy>(T, fn(T));
 impl<T: Copy> Drop for GdiObject<T> {
@@ -20,25 +58,157 @@ impl<T: Copy> Drop for GdiObject<T> {
     }
 }
 
+/// アイコンの抽出元。
+#[derive(Clone)]
+pub enum IconSource {
+    /// ファイル自体のアイコン（シェルが表示するアイコン）
+    File(String),
+    /// リソースファイル（.dll / .exe / .ico）内の指定インデックスのアイコン。
+    /// 画像ファイル（UWPアプリのロゴなど）の場合は画像そのもの
+    Resource {
+        /// キャッシュキー（例: "path/to/icon.dll:0", "uwp:<AUMID>"）
+        key: String,
+        path: String,
+        index: i32,
+    },
+    /// ファイルの種類（MIMEタイプ）ごとの汎用アイコン
+    MimeType(String),
+    /// 拡張子（小文字、`.` を含まない）ごとにシェルが表示するアイコン（Windows）
+    FileType(String),
+}
+
+/// セルのパスから、実際にアイコンを抽出する対象を決定します。
+///
+/// # 引数
+/// * `app_handle` - TauriのAppHandle（UWPアプリのカタログの参照用）
+/// * `path` - セルのターゲットパス（UWPアプリの場合は AUMID）
+/// * `resolve_shortcut` - ショートカット(.lnk)の場合、リンク先（またはアイコンリソース）のアイコンを使用するか
+/// * `resolve_links` - シンボリックリンク・ジャンクションを解決した実体パスを使用するか
+pub fn resolve_icon_source(
+    app_handle: &tauri::AppHandle,
+    path: String,
+    resolve_shortcut: bool,
+    resolve_links: bool,
+) -> IconSource {
+    // UWPアプリはカタログに記録されたパッケージのロゴを使用する
+    if let Some(logo) = app_handle
+        .try_state::<UwpCatalog>()
+        .and_then(|catalog| catalog.logo_for(&path))
+    {
+        return IconSource::Resource {
+            key: format!("uwp:{}", path),
+            path: logo,
+            index: 0,
+        };
+    }
+
+    let path = if resolve_links {
+        path_utils::real_path(&path)
+    } else {
+        path
+    };
+
+    if resolve_shortcut && path.to_lowercase().ends_with(".lnk") {
+        if let Ok(info) = shortcut_utils::resolve_lnk(&path) {
+            // ショートカットがアイコンリソース情報を指している場合はそれを使用
+            if !info.icon_path.is_empty() {
+                // カスタムハッシュキーを作成してキャッシュを分離する
+                // 例: "path/to/icon.dll:0"
+                return IconSource::Resource {
+                    key: format!("{}:{}", info.icon_path, info.icon_index),
+                    path: info.icon_path,
+                    index: info.icon_index,
+                };
+            } else if !info.target.is_empty() {
+                // アイコン指定がない場合はターゲットパスを使用 (従来のロジック)
+                let target = if resolve_links {
+                    path_utils::real_path(&info.target)
+                } else {
+                    info.target
+                };
+                return file_source(target);
+            }
+        }
+    }
+    // 通常のファイルパスまたは解決失敗時はそのまま
+    file_source(path)
+}
+
+/// ファイル固有のアイコンを持たないファイル（ドキュメント・フォルダーなど）は、
+/// パスごとではなく種類ごとのアイコンを使用します。
+///
+/// Windows ではシェルが拡張子ごとに登録されたアイコンを表示するため、MIME タイプの対応表にない拡張子
+/// （`.sln` や `.kdbx` など）も区別できるよう、フォルダー以外は拡張子ごとのアイコンを使用します。
+fn file_source(path: String) -> IconSource {
+    let file_path = Path::new(&path);
+    if mime_type::has_own_icon(file_path) {
+        return IconSource::File(path);
+    }
+    if cfg!(target_os = "windows") && !file_path.is_dir() {
+        let extension = file_path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        return IconSource::FileType(extension);
+    }
+    IconSource::MimeType(mime_type::detect(file_path))
+}
+
+/// ファイル固有のアイコンを持たないファイルの場合、共有する種類ごとのアイコンのキャッシュキーを返します。
+pub fn shared_cache_key(path: &str) -> Option<String> {
+    match file_source(path.to_string()) {
+        IconSource::MimeType(mime) => Some(mime_cache_key(&mime)),
+        IconSource::FileType(extension) => Some(file_type_cache_key(&extension)),
+        IconSource::File(_) | IconSource::Resource { .. } => None,
+    }
+}
+
 /// 指定されたパスのファイルのアイコンを取得し、Base64エンコードされたPNG画像として返します。
 ///
 /// パフォーマンスを最適化するため、以下のキャッシュ戦略を採用しています：
 /// 1. パスのSHA256ハッシュを計算し、キャッシュキーとします。
-/// 2. ディスク上のキャッシュ（`AppData/cache/icons/`）を確認します。
-/// 3. キャッシュが存在すれば、それを読み込んで返します。
-/// 4. キャッシュがない場合、Windows APIを使用してアイコンを抽出・PNG変換し、ディスクに保存してから返します。
+/// 2. サイドカーインデックス（`index.json`）で、抽出元の更新日時・サイズ・抽出パラメータが変わっていないか確認します。
+///    変わっている場合はメモリ・ディスクのキャッシュを破棄します。
+/// 3. メモリキャッシュ（`IconMemoryCache`）を確認します。
+/// 4. ディスク上のキャッシュ（`AppData/cache/icons/`）を確認します。
+/// 5. キャッシュが存在すれば、それを読み込んで返します。
+/// 6. キャッシュがない場合、Windows APIを使用してアイコンを抽出・PNG変換し、ディスクに保存してから返します。
 ///
 /// # 引数
 /// * `app_handle` - TauriのAppHandle（キャッシュディレクトリパス取得用）
 /// * `path` - アイコンを取得したいファイルのパス
-pub fn get_icon(app_handle: &tauri::AppHandle, path: String) -> Result<String, String> {
-    // 1. キャッシュキーの計算 (パスのハッシュ値)
+/// * `size` - 要求するピクセルサイズ（`None` の場合は従来どおり32px）
+/// * `transform` - 抽出したアイコンに適用する後処理（パラメータはキャッシュキーに含まれます）
+pub fn get_icon(
+    app_handle: &tauri::AppHandle,
+    path: String,
+    size: Option<u32>,
+    transform: &IconTransform,
+) -> Result<String, String> {
+    let (key, params) = transformed_key(sized_key(&path, "", size), transform)?;
+    get_or_extract(app_handle, &key, &path, &params, || {
+        extract_sized(
+            app_handle,
+            &path,
+            &path,
+            "",
+            size,
+            DEFAULT_FILE_ICON_SIZE,
+            |native| extract_icon_png(&path, native),
+        )
+        .and_then(|png_data| transform.apply(&png_data))
+    })
+}
+
+/// キャッシュキー（パスやリソースキー）のSHA256ハッシュを計算します。
+fn hash_key(cache_key: &str) -> String {
     let mut hasher = Sha256::new();
-    hasher.update(path.as_bytes());
-    let result = hasher.finalize();
-    let hash = hex::encode(result);
+    hasher.update(cache_key.as_bytes());
+    hex::encode(hasher.finalize())
+}
 
-    // 2. キャッシュディレクトリのセットアップ
+/// アイコンのディスクキャッシュディレクトリ（`AppData/cache/icons/`）を返します。存在しない場合は作成します。
+pub fn icon_cache_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     let app_dir = app_handle
         .path()
         .app_data_dir()
@@ -48,16 +218,253 @@ pub fn get_icon(app_handle: &tauri::AppHandle, path: String) -> Result<String, S
     if !cache_dir.exists() {
         fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
     }
+    Ok(cache_dir)
+}
+
+/// PNGデータをデータURIに変換します。
+fn to_data_uri(png_data: &[u8]) -> String {
+    let base64_string = base64::engine::general_purpose::STANDARD.encode(png_data);
+    format!("data:image/png;base64,{}", base64_string)
+}
+
+/// `to_data_uri` で作成したデータURIをPNGデータに戻します。
+fn from_data_uri(data_uri: &str) -> Option<Vec<u8>> {
+    let encoded = data_uri.strip_prefix("data:image/png;base64,")?;
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()
+}
+
+/// キャッシュディレクトリのサイドカーインデックスを読み込みます。
+///
+/// アプリ起動時に呼び出し、Tauriのステートとして管理します。
+pub fn load_index(app_handle: &tauri::AppHandle) -> Result<IconIndex, String> {
+    Ok(IconIndex::load(&icon_cache_dir(app_handle)?))
+}
+
+/// 要求サイズに応じたキャッシュキーと抽出パラメータを返します。
+///
+/// サイズ指定がない場合は、従来のキャッシュをそのまま使えるよう元のキーを返します。
+fn sized_key(base_key: &str, base_params: &str, size: Option<u32>) -> (String, String) {
+    match size {
+        Some(size) => (
+            format!("{}@{}", base_key, size),
+            format!("{};size={}", base_params, size),
+        ),
+        None => (base_key.to_string(), base_params.to_string()),
+    }
+}
+
+/// 後処理のパラメータをキャッシュキーと抽出パラメータに加えます。
+///
+/// 後処理がない場合は元のキーをそのまま返します。後処理のパラメータが不正な場合はエラーを返します。
+fn transformed_key(
+    (key, params): (String, String),
+    transform: &IconTransform,
+) -> Result<(String, String), String> {
+    let transform_params = transform.cache_params()?;
+    if transform_params.is_empty() {
+        return Ok((key, params));
+    }
+    Ok((
+        format!("{}|{}", key, transform_params),
+        format!("{};transform={}", params, transform_params),
+    ))
+}
+
+/// 要求サイズ以上で最小のネイティブサイズを返します（256px を超える場合は 256px）。
+fn native_size_for(size: u32) -> u32 {
+    NATIVE_ICON_SIZES
+        .iter()
+        .copied()
+        .find(|&native| native >= size)
+        .unwrap_or(MAX_ICON_SIZE)
+}
+
+/// 要求サイズのアイコンのPNGデータを生成します。
+///
+/// 1. 要求サイズ以上のネイティブサイズのうち、既にキャッシュされているものがあればそれを使用します。
+/// 2. なければ、要求サイズ以上で最小のネイティブサイズを `extract_native` で抽出してキャッシュします。
+/// 3. 要求サイズより大きい場合は Lanczos3 フィルタで縮小します（拡大はしません）。
+///
+/// `size` が `None` の場合は `default_size` をそのまま抽出します（従来の動作）。
+fn extract_sized<F>(
+    app_handle: &tauri::AppHandle,
+    base_key: &str,
+    source: &str,
+    base_params: &str,
+    size: Option<u32>,
+    default_size: u32,
+    extract_native: F,
+) -> Result<Vec<u8>, String>
+where
+    F: Fn(u32) -> Result<Vec<u8>, String>,
+{
+    let Some(size) = size else {
+        return extract_native(default_size);
+    };
+    let size = size.clamp(1, MAX_ICON_SIZE);
+    let native_slot = |native: u32| {
+        CacheSlot::prepare(
+            app_handle,
+            &format!("{}#{}", base_key, native),
+            source,
+            &format!("{};native={}", base_params, native),
+        )
+    };
+
+    // 1. キャッシュ済みのネイティブサイズから最適なものを探す
+    for native in NATIVE_ICON_SIZES.iter().copied().filter(|&n| n >= size) {
+        let slot = native_slot(native)?;
+        if let Ok(png_data) = fs::read(&slot.cache_file) {
+            return resize_png(&png_data, size);
+        }
+    }
+
+    // 2. 最適なネイティブサイズを抽出してキャッシュ
+    let native = native_size_for(size);
+    let png_data = extract_native(native)?;
+    native_slot(native)?.store(app_handle, &png_data);
+
+    // 3. 要求サイズへの縮小
+    resize_png(&png_data, size)
+}
+
+/// PNGデータを `size` ピクセルの正方形に収まるよう縮小します。既に収まっている場合はそのまま返します。
+fn resize_png(png_data: &[u8], size: u32) -> Result<Vec<u8>, String> {
+    let img = image::load_from_memory(png_data).map_err(|e| e.to_string())?;
+    let (width, height) = img.dimensions();
+    if width <= size && height <= size {
+        return Ok(png_data.to_vec());
+    }
+
+    let resized = img.resize(size, size, FilterType::Lanczos3);
+    let mut png_buffer = Vec::new();
+    resized
+        .write_to(
+            &mut std::io::Cursor::new(&mut png_buffer),
+            ImageOutputFormat::Png,
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(png_buffer)
+}
+
+/// 画像の不透明な部分が、左上の `inner` ピクセル四方に収まっているかどうか。
+///
+/// ジャンボサイズのイメージリストは、大きいアイコンを持たないファイルに対して
+/// 小さいアイコンを左上に配置した画像を返すため、その判定に使用します。
+fn fits_in_corner(png_data: &[u8], inner: u32) -> bool {
+    let Ok(img) = image::load_from_memory(png_data) else {
+        return false;
+    };
+    let rgba = img.to_rgba8();
+    if rgba.width() <= inner && rgba.height() <= inner {
+        return false;
+    }
+    rgba.enumerate_pixels()
+        .filter(|(x, y, _)| *x >= inner || *y >= inner)
+        .all(|(_, _, pixel)| pixel[3] == 0)
+}
+
+/// キャッシュキーに対応するディスクキャッシュの位置と、抽出元の現在の状態。
+struct CacheSlot {
+    hash: String,
+    cache_file: PathBuf,
+    current: IconIndexEntry,
+}
+
+impl CacheSlot {
+    /// キャッシュキーのハッシュを計算し、抽出元が変更されていればキャッシュ（メモリ・ディスク）を破棄します。
+    fn prepare(
+        app_handle: &tauri::AppHandle,
+        cache_key: &str,
+        source: &str,
+        params: &str,
+    ) -> Result<Self, String> {
+        let hash = hash_key(cache_key);
+        let cache_file = icon_cache_dir(app_handle)?.join(format!("{}.png", hash));
+        let current = IconIndexEntry::for_source(source, params);
+
+        if let Some(index) = app_handle.try_state::<IconIndex>() {
+            if !index.is_fresh(&hash, &current) {
+                if let Some(memory) = app_handle.try_state::<IconMemoryCache>() {
+                    memory.remove(&hash);
+                }
+                let _ = fs::remove_file(&cache_file);
+            }
+        }
+
+        Ok(Self {
+            hash,
+            cache_file,
+            current,
+        })
+    }
+
+    /// 抽出したPNGデータをディスクに保存し、インデックスに記録します。
+    ///
+    /// 上限サイズを超えた場合は古いキャッシュを破棄します。保存できたかどうかを返します。
+    fn store(&self, app_handle: &tauri::AppHandle, png_data: &[u8]) -> bool {
+        if fs::write(&self.cache_file, png_data).is_err() {
+            return false;
+        }
+        if let Some(index) = app_handle.try_state::<IconIndex>() {
+            let entry = IconIndexEntry {
+                bytes: png_data.len() as u64,
+                ..self.current.clone()
+            };
+            let evicted = index.record(self.hash.clone(), entry);
+            if let Some(cache_dir) = self.cache_file.parent() {
+                discard(app_handle, cache_dir, &evicted);
+            }
+        }
+        true
+    }
+
+    /// `hexicon` プロトコルのURLを返します。
+    ///
+    /// 抽出元の状態から作るバージョン文字列をクエリに含め、抽出元が変更された際にWebViewのキャッシュを無効化します。
+    fn url(&self) -> String {
+        let version = format!("{:x}-{:x}", self.current.mtime, self.current.size);
+        icon_protocol::icon_url(&self.hash, &version)
+    }
+}
+
+/// メモリ → ディスクの順にキャッシュを確認し、なければ `extract` でアイコンを抽出してキャッシュします。
+///
+/// # 引数
+/// * `cache_key` - キャッシュキー（ハッシュ化されてファイル名・メモリキャッシュのキーになります）
+/// * `source` - アイコンの抽出元ファイル（更新日時・サイズの変化を検出するために使用）
+/// * `params` - 抽出パラメータ（変化した場合はキャッシュを破棄します）
+/// * `extract` - キャッシュがない場合にPNGデータを生成する関数
+fn get_or_extract<F>(
+    app_handle: &tauri::AppHandle,
+    cache_key: &str,
+    source: &str,
+    params: &str,
+    extract: F,
+) -> Result<String, String>
+where
+    F: FnOnce() -> Result<Vec<u8>, String>,
+{
+    // 1. キャッシュキーの計算と抽出元の変更確認 (変更されていればキャッシュを破棄)
+    let slot = CacheSlot::prepare(app_handle, cache_key, source, params)?;
+    let memory = app_handle.try_state::<IconMemoryCache>();
 
-    let cache_file = cache_dir.join(format!("{}.png", hash));
+    // 2. メモリキャッシュの確認
+    if let Some(data_uri) = memory.as_ref().and_then(|m| m.get(&slot.hash)) {
+        return Ok(data_uri);
+    }
 
-    // 3. キャッシュの確認
-    if cache_file.exists() {
-        // キャッシュからの読み込み
-        match fs::read(&cache_file) {
+    // 3. ディスクキャッシュの確認
+    if slot.cache_file.exists() {
+        match fs::read(&slot.cache_file) {
             Ok(data) => {
-                let base64_string = base64::engine::general_purpose::STANDARD.encode(&data);
-                return Ok(format!("data:image/png;base64,{}", base64_string));
+                let data_uri = to_data_uri(&data);
+                if let Some(memory) = &memory {
+                    memory.insert(slot.hash, data_uri.clone());
+                }
+                return Ok(data_uri);
             }
             Err(e) => {
                 println!("Failed to read cache: {}", e);
@@ -66,22 +473,126 @@ pub fn get_icon(app_handle: &tauri::AppHandle, path: String) -> Result<String, S
         }
     }
 
-    // 4. アイコンの抽出 (Windows API使用)
-    let png_data = extract_icon_png(&path)?;
+    // 4. アイコンの抽出
+    let png_data = extract()?;
 
     // 5. キャッシュへの保存 (UIブロックを避けるため書き込みエラーは無視)
-    let _ = fs::write(&cache_file, &png_data);
+    slot.store(app_handle, &png_data);
 
-    // 6. Base64文字列として返却
-    let base64_string = base64::engine::general_purpose::STANDARD.encode(&png_data);
-    Ok(format!("data:image/png;base64,{}", base64_string))
+    // 6. データURIとして返却
+    let data_uri = to_data_uri(&png_data);
+    if let Some(memory) = &memory {
+        memory.insert(slot.hash, data_uri.clone());
+    }
+    Ok(data_uri)
+}
+
+/// ディスクキャッシュを確認し、なければ `extract` でアイコンを抽出して保存した上で、
+/// キャッシュファイルの位置を返します。
+///
+/// `hexicon` プロトコルのURLの生成や、キャッシュファイルを直接解析する処理（配色の抽出など）で使用します。
+/// ディスクにない場合はメモリキャッシュを確認し、残っていれば抽出しなおさずにディスクへ書き戻します。
+/// 抽出した場合はメモリキャッシュにも登録し、同じアイコンのデータURIの要求に備えます。
+fn ensure_cached<F>(
+    app_handle: &tauri::AppHandle,
+    cache_key: &str,
+    source: &str,
+    params: &str,
+    extract: F,
+) -> Result<CacheSlot, String>
+where
+    F: FnOnce() -> Result<Vec<u8>, String>,
+{
+    let slot = CacheSlot::prepare(app_handle, cache_key, source, params)?;
+
+    if !slot.cache_file.exists() {
+        let memory = app_handle.try_state::<IconMemoryCache>();
+        let cached = memory
+            .as_ref()
+            .and_then(|m| m.get(&slot.hash))
+            .and_then(|data_uri| from_data_uri(&data_uri));
+
+        let png_data = match cached {
+            Some(png_data) => png_data,
+            None => {
+                let png_data = extract()?;
+                if let Some(memory) = &memory {
+                    memory.insert(slot.hash.clone(), to_data_uri(&png_data));
+                }
+                png_data
+            }
+        };
+
+        if !slot.store(app_handle, &png_data) {
+            return Err(format!(
+                "Failed to write icon cache: {}",
+                slot.cache_file.display()
+            ));
+        }
+    }
+
+    Ok(slot)
 }
 
 #[cfg(target_os = "windows")]
 /// Windows APIを使用してファイルからアイコンを抽出し、PNGデータに変換します。
 ///
-/// `SHGetFileInfoW` でアイコンハンドルを取得し、`icon_to_png` で画像データに変換します。
-fn extract_icon_png(path: &str) -> Result<Vec<u8>, String> {
+/// `SHGetFileInfoW` でシステムイメージリストのインデックスを取得し、
+/// `size` に対応するイメージリスト（16 / 32 / 48 / 256px）からアイコンハンドルを取得して、
+/// `icon_to_png` で画像データに変換します。
+fn extract_icon_png(path: &str, size: u32) -> Result<Vec<u8>, String> {
+    extract_shell_icon_png(path, FILE_ATTRIBUTE_NORMAL, SHGFI_SYSICONINDEX, size)
+}
+
+#[cfg(target_os = "windows")]
+/// MIME タイプの汎用アイコンを、シェルに種類のアイコンとして問い合わせて抽出します。
+///
+/// `SHGFI_USEFILEATTRIBUTES` を指定し、代表する拡張子を持つ仮のファイル名（フォルダーの場合はディレクトリ属性）
+/// から取得するため、実在するファイルにはアクセスしません。
+fn extract_mime_icon_png(mime: &str, size: u32) -> Result<Vec<u8>, String> {
+    let (name, attributes) = if mime == mime_type::DIRECTORY {
+        ("folder".to_string(), FILE_ATTRIBUTE_DIRECTORY)
+    } else {
+        let name = match mime_type::extension_for(mime) {
+            Some(extension) => format!("file.{}", extension),
+            None => "file".to_string(),
+        };
+        (name, FILE_ATTRIBUTE_NORMAL)
+    };
+    extract_shell_icon_png(
+        &name,
+        attributes,
+        SHGFI_SYSICONINDEX | SHGFI_USEFILEATTRIBUTES,
+        size,
+    )
+}
+
+#[cfg(target_os = "windows")]
+/// 拡張子ごとのアイコンを、シェルに種類のアイコンとして問い合わせて抽出します。
+///
+/// `SHGFI_USEFILEATTRIBUTES` を指定し、その拡張子を持つ仮のファイル名から取得するため、
+/// 実在するファイルにはアクセスしません。
+fn extract_file_type_icon_png(extension: &str, size: u32) -> Result<Vec<u8>, String> {
+    let name = if extension.is_empty() {
+        "file".to_string()
+    } else {
+        format!("file.{}", extension)
+    };
+    extract_shell_icon_png(
+        &name,
+        FILE_ATTRIBUTE_NORMAL,
+        SHGFI_SYSICONINDEX | SHGFI_USEFILEATTRIBUTES,
+        size,
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn extract_shell_icon_png(
+    path: &str,
+    attributes: FILE_FLAGS_AND_ATTRIBUTES,
+    flags: SHGFI_FLAGS,
+    size: u32,
+) -> Result<Vec<u8>, String> {
     unsafe {
         use std::ffi::OsStr;
         use std::os::windows::ffi::OsStrExt;
@@ -94,17 +605,27 @@ fn extract_icon_png(path: &str) -> Result<Vec<u8>, String> {
         let mut shfi: SHFILEINFOW = std::mem::zeroed();
         let result = SHGetFileInfoW(
             windows::core::PCWSTR(wide_path.as_ptr()),
-            FILE_ATTRIBUTE_NORMAL,
+            attributes,
             Some(&mut shfi),
             std::mem::size_of::<SHFILEINFOW>() as u32,
-            SHGFI_ICON | SHGFI_LARGEICON,
+            flags,
         );
 
         if result == 0 {
             return Err(format!("Failed to get file info for {}", path));
         }
 
-        let hicon = shfi.hIcon;
+        let list = match size {
+            0..=16 => SHIL_SMALL,
+            17..=32 => SHIL_LARGE,
+            33..=48 => SHIL_EXTRALARGE,
+            _ => SHIL_JUMBO,
+        };
+        let image_list: IImageList =
+            SHGetImageList(list as i32).map_err(|e| format!("Failed to get image list: {}", e))?;
+        let hicon = image_list
+            .GetIcon(shfi.iIcon, ILD_TRANSPARENT.0)
+            .map_err(|e| format!("Failed to get icon for {}: {}", path, e))?;
         if hicon.is_invalid() {
             return Err("Invalid icon handle".to_string());
         }
@@ -115,49 +636,136 @@ fn extract_icon_png(path: &str) -> Result<Vec<u8>, String> {
         })?;
 
         let _ = DestroyIcon(hicon);
+
+        // 256px のアイコンを持たないファイルは、48px のアイコンが左上に配置された画像になるため 48px を使用する
+        if list == SHIL_JUMBO && fits_in_corner(&png_data, 48) {
+            return extract_shell_icon_png(path, attributes, flags, 48);
+        }
         Ok(png_data)
     }
 }
 
 #[cfg(not(target_os = "windows"))]
-/// Windows以外のOSではアイコン抽出は未サポートです。
-fn extract_icon_png(_path: &str) -> Result<Vec<u8>, String> {
-    Err("Icon extraction is only supported on Windows".to_string())
+/// Windows以外のOSでは、ファイルの種類に応じて以下の方法で抽出します。
+///
+/// - `.ico` と PE ファイル（.exe / .dll）: `ico_reader` で直接解析
+/// - `.desktop` ファイル: `Icon=` の絶対パス、またはアイコンテーマから解決
+/// - PNG / SVG / XPM 画像: 画像そのものを読み込み
+/// - その他のファイル・ディレクトリ: MIME タイプの汎用アイコン
+fn extract_icon_png(path: &str, size: u32) -> Result<Vec<u8>, String> {
+    let file_path = Path::new(path);
+    let extension = file_path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "ico" | "exe" | "dll" => ico_reader::extract_icon(path, 0, size),
+        "desktop" => {
+            let entry = DesktopEntry::load(file_path)?;
+            let icon = entry
+                .get("Desktop Entry", "Icon")
+                .filter(|icon| !icon.is_empty())
+                .ok_or("Desktop entry has no icon")?;
+            if Path::new(icon).is_absolute() {
+                icon_theme::load_icon_png(Path::new(icon), size)
+            } else {
+                icon_theme::icon_png(icon, size)
+            }
+        }
+        "png" | "svg" | "svgz" | "xpm" => icon_theme::load_icon_png(file_path, size),
+        _ => extract_mime_icon_png(&mime_type::detect(file_path), size),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+/// MIME タイプの汎用アイコンを、アイコンテーマから `mime_type::icon_names` の順に探して抽出します。
+fn extract_mime_icon_png(mime: &str, size: u32) -> Result<Vec<u8>, String> {
+    mime_type::icon_names(mime)
+        .iter()
+        .find_map(|name| icon_theme::icon_png(name, size).ok())
+        .ok_or_else(|| format!("No icon found for MIME type {}", mime))
+}
+
+#[cfg(not(target_os = "windows"))]
+/// Windows以外のOSでは、拡張子から判定した MIME タイプの汎用アイコンを使用します。
+fn extract_file_type_icon_png(extension: &str, size: u32) -> Result<Vec<u8>, String> {
+    let name = format!("file.{}", extension);
+    extract_mime_icon_png(&mime_type::detect(Path::new(&name)), size)
 }
 
 #[cfg(target_os = "windows")]
+/// アイコンハンドルを PNG データに変換します。
+///
+/// アルファチャンネルを持たない古い形式のアイコンは ANDマスクから透過を決定し、
+/// カラービットマップを持たないモノクロアイコンはマスクの AND / XOR から白黒の画像を作ります。
 unsafe fn icon_to_png(hicon: HICON) -> Result<Vec<u8>, String> {
     let mut icon_info = std::mem::zeroed();
     if GetIconInfo(hicon, &mut icon_info).is_err() {
         return Err("Failed to get icon info".to_string());
     }
 
-    let hbitmap = icon_info.hbmColor;
-    let _hbm_mask = icon_info.hbmMask;
+    let hbm_color = icon_info.hbmColor;
+    let hbm_mask = icon_info.hbmMask;
 
     fn delete_bitmap(h: HBITMAP) {
         unsafe {
             let _ = DeleteObject(h);
         }
     }
-    let _cleanup_color = GdiObject(hbitmap, delete_bitmap);
-    let _cleanup_mask = GdiObject(icon_info.hbmMask, delete_bitmap);
-
-    if hbitmap.is_invalid() {
-        return Err("Invalid color bitmap".to_string());
-    }
+    let _cleanup_color = GdiObject(hbm_color, delete_bitmap);
+    let _cleanup_mask = GdiObject(hbm_mask, delete_bitmap);
 
     let hdc_screen = GetDC(None);
-    let hdc_mem = CreateCompatibleDC(hdc_screen);
-    let _cleanup_dc = GdiObject(hdc_mem, |h| unsafe {
-        let _ = DeleteDC(h);
-    });
     let _cleanup_screen_dc = GdiObject(hdc_screen, |h| unsafe {
         let _ = ReleaseDC(None, h);
     });
 
-    let old_obj = SelectObject(hdc_mem, hbitmap);
+    let (width, height, pixels) = if hbm_color.is_invalid() {
+        // モノクロアイコン: マスクの上半分が AND、下半分が XOR
+        if hbm_mask.is_invalid() {
+            return Err("Invalid icon bitmaps".to_string());
+        }
+        let (width, mask_height, mask) = read_bitmap_bgra(hdc_screen, hbm_mask)?;
+        let height = mask_height / 2;
+        let pixels = icon_pixels::monochrome_to_rgba(&mask, width, height)?;
+        (width, height, pixels)
+    } else {
+        let (width, height, mut pixels) = read_bitmap_bgra(hdc_screen, hbm_color)?;
+        icon_pixels::bgra_to_rgba(&mut pixels);
+
+        let and_mask = if hbm_mask.is_invalid() {
+            None
+        } else {
+            read_bitmap_bgra(hdc_screen, hbm_mask)
+                .ok()
+                .filter(|(w, h, _)| *w == width && *h == height)
+                .and_then(|(_, _, mask)| icon_pixels::mask_from_bgra(&mask, width, height).ok())
+        };
+        icon_pixels::apply_and_mask(&mut pixels, and_mask.as_deref());
+        (width, height, pixels)
+    };
+
+    let mut png_buffer = Vec::new();
+    let img_buffer: image::ImageBuffer<image::Rgba<u8>, _> =
+        image::ImageBuffer::from_raw(width as u32, height as u32, pixels)
+            .ok_or("Failed to create image buffer")?;
+
+    img_buffer
+        .write_to(
+            &mut std::io::Cursor::new(&mut png_buffer),
+            ImageOutputFormat::Png,
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(png_buffer)
+}
 
+#[cfg(target_os = "windows")]
+/// ビットマップを上から下の順の 32ビット BGRA として読み出し、(幅, 高さ, ピクセル) を返します。
+///
+/// 24ビット以下のビットマップのアルファは0になります。
+unsafe fn read_bitmap_bgra(hdc: HDC, hbitmap: HBITMAP) -> Result<(usize, usize, Vec<u8>), String> {
     let mut bitmap: BITMAP = std::mem::zeroed();
     if GetObjectW(
         windows::Win32::Graphics::Gdi::HGDIOBJ(hbitmap.0),
@@ -170,8 +778,10 @@ unsafe fn icon_to_png(hicon: HICON) -> Result<Vec<u8>, String> {
 
     let width = bitmap.bmWidth;
     let height = bitmap.bmHeight;
-    let size = (width * height * 4) as usize;
-    let mut pixels = vec![0u8; size];
+    if width <= 0 || height <= 0 {
+        return Err("Invalid bitmap size".to_string());
+    }
+    let mut pixels = vec![0u8; (width * height * 4) as usize];
 
     let mut bi: BITMAPINFO = std::mem::zeroed();
     bi.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
@@ -182,7 +792,7 @@ unsafe fn icon_to_png(hicon: HICON) -> Result<Vec<u8>, String> {
     bi.bmiHeader.biCompression = BI_RGB.0;
 
     if GetDIBits(
-        hdc_mem,
+        hdc,
         hbitmap,
         0,
         height as u32,
@@ -194,86 +804,354 @@ unsafe fn icon_to_png(hicon: HICON) -> Result<Vec<u8>, String> {
         return Err("Failed to get DIB bits".to_string());
     }
 
-    SelectObject(hdc_mem, old_obj);
+    Ok((width as usize, height as usize, pixels))
+}
 
-    // Convert BGRA to RGBA
-    for chunk in pixels.chunks_mut(4) {
-        let b = chunk[0];
-        let r = chunk[2];
-        chunk[0] = r;
-        chunk[2] = b;
-    }
+/// 指定されたリソースキー（キャッシュ用）、ファイルパス、インデックスを使用してアイコンを取得します。
+/// キャッシュロジックは get_icon と同様です（`size` が `None` の場合は従来どおり256px）。
+pub fn get_icon_by_resource(
+    app_handle: &tauri::AppHandle,
+    cache_key: &str,
+    path: &str,
+    index: i32,
+    size: Option<u32>,
+    transform: &IconTransform,
+) -> Result<String, String> {
+    let base_params = index.to_string();
+    let (key, params) = transformed_key(sized_key(cache_key, &base_params, size), transform)?;
+    get_or_extract(app_handle, &key, path, &params, || {
+        extract_sized(
+            app_handle,
+            cache_key,
+            path,
+            &base_params,
+            size,
+            DEFAULT_RESOURCE_ICON_SIZE,
+            |native| extract_icon_from_resource(path, index, native),
+        )
+        .and_then(|png_data| transform.apply(&png_data))
+    })
+}
 
-    let mut png_buffer = Vec::new();
-    let img_buffer: image::ImageBuffer<image::Rgba<u8>, _> =
-        image::ImageBuffer::from_raw(width as u32, height as u32, pixels)
-            .ok_or("Failed to create image buffer")?;
+/// MIME タイプのキャッシュキー（インデックスの抽出元としても記録されます）を返します。
+pub fn mime_cache_key(mime: &str) -> String {
+    format!("mime:{}", mime)
+}
 
-    img_buffer
-        .write_to(
-            &mut std::io::Cursor::new(&mut png_buffer),
-            ImageOutputFormat::Png,
+/// 拡張子のキャッシュキー（インデックスの抽出元としても記録されます）を返します。
+pub fn file_type_cache_key(extension: &str) -> String {
+    format!("ext:.{}", extension)
+}
+
+/// 指定された MIME タイプの汎用アイコンを取得します。
+///
+/// キャッシュは MIME タイプごとに1つで、同じ種類のファイルを指すすべてのセルで共有されます。
+/// キャッシュロジックは get_icon と同様です（`size` が `None` の場合は従来どおり32px）。
+pub fn get_icon_by_mime(
+    app_handle: &tauri::AppHandle,
+    mime: &str,
+    size: Option<u32>,
+    transform: &IconTransform,
+) -> Result<String, String> {
+    get_shared_icon(
+        app_handle,
+        &mime_cache_key(mime),
+        size,
+        transform,
+        |native| extract_mime_icon_png(mime, native),
+    )
+}
+
+/// 指定された拡張子のファイルにシェルが表示するアイコンを取得します。
+///
+/// キャッシュは拡張子ごとに1つで、同じ拡張子のファイルを指すすべてのセルで共有されます。
+/// キャッシュロジックは get_icon_by_mime と同様です。
+pub fn get_icon_by_file_type(
+    app_handle: &tauri::AppHandle,
+    extension: &str,
+    size: Option<u32>,
+    transform: &IconTransform,
+) -> Result<String, String> {
+    get_shared_icon(
+        app_handle,
+        &file_type_cache_key(extension),
+        size,
+        transform,
+        |native| extract_file_type_icon_png(extension, native),
+    )
+}
+
+/// 種類ごとに共有するアイコンを、`cache_key` をキー・抽出元としてキャッシュから取得、または抽出します。
+fn get_shared_icon<F>(
+    app_handle: &tauri::AppHandle,
+    cache_key: &str,
+    size: Option<u32>,
+    transform: &IconTransform,
+    extract_native: F,
+) -> Result<String, String>
+where
+    F: Fn(u32) -> Result<Vec<u8>, String>,
+{
+    let (key, params) = transformed_key(sized_key(cache_key, "", size), transform)?;
+    get_or_extract(app_handle, &key, cache_key, &params, || {
+        extract_sized(
+            app_handle,
+            cache_key,
+            cache_key,
+            "",
+            size,
+            DEFAULT_FILE_ICON_SIZE,
+            extract_native,
         )
-        .map_err(|e| e.to_string())?;
+        .and_then(|png_data| transform.apply(&png_data))
+    })
+}
 
-    Ok(png_buffer)
+/// `hexicon` プロトコルのURLとして渡したアイコンの抽出方法。
+#[derive(Clone)]
+struct IconRecipe {
+    source: IconSource,
+    size: Option<u32>,
+    transform: IconTransform,
 }
 
-/// 指定されたリソースキー（キャッシュ用）、ファイルパス、インデックスを使用してアイコンを取得します。
-/// キャッシュロジックは get_icon と同様です。
-pub fn get_icon_by_resource(
+/// URLとして渡したアイコンの、キャッシュファイル名（ハッシュ）ごとの抽出方法。
+///
+/// キャッシュファイルが上限サイズによる削除などで失われた後にURLが要求された場合に、
+/// `hexicon` プロトコルのハンドラーが再抽出するために使用します。Tauriのステートとして管理されます。
+#[derive(Default)]
+pub struct IconUrlRegistry {
+    recipes: Mutex<HashMap<String, IconRecipe>>,
+}
+
+/// 抽出元に応じてアイコンをディスクキャッシュに用意し、`hexicon` プロトコルのURLを返します。
+///
+/// URLはディスク上のキャッシュファイルを指すため、メモリキャッシュはディスクにない場合の復元元としてのみ使用します。
+pub fn get_source_url(
+    app_handle: &tauri::AppHandle,
+    source: IconSource,
+    size: Option<u32>,
+    transform: &IconTransform,
+) -> Result<String, String> {
+    let slot = source_slot(app_handle, &source, size, transform)?;
+    if let Some(registry) = app_handle.try_state::<IconUrlRegistry>() {
+        if let Ok(mut recipes) = registry.recipes.lock() {
+            recipes.insert(
+                slot.hash.clone(),
+                IconRecipe {
+                    source,
+                    size,
+                    transform: transform.clone(),
+                },
+            );
+        }
+    }
+    Ok(slot.url())
+}
+
+/// URLとして渡したアイコンのキャッシュファイルを、失われていれば再抽出した上で返します。
+///
+/// このセッションで `get_source_url` が返したURLでない場合は `None` を返します。
+pub fn regenerate(app_handle: &tauri::AppHandle, hash: &str) -> Option<PathBuf> {
+    let recipe = app_handle
+        .try_state::<IconUrlRegistry>()?
+        .recipes
+        .lock()
+        .ok()?
+        .get(hash)
+        .cloned()?;
+    let slot = source_slot(app_handle, &recipe.source, recipe.size, &recipe.transform).ok()?;
+    // 抽出元が変更されてハッシュが変わった場合でも、要求されたファイル名で応答することはない
+    (slot.hash == hash).then_some(slot.cache_file)
+}
+
+/// 抽出元に応じてアイコンをディスクキャッシュに用意し、キャッシュファイルの位置を返します。
+fn source_slot(
+    app_handle: &tauri::AppHandle,
+    source: &IconSource,
+    size: Option<u32>,
+    transform: &IconTransform,
+) -> Result<CacheSlot, String> {
+    match source {
+        IconSource::File(path) => file_slot(app_handle, path.clone(), size, transform),
+        IconSource::Resource { key, path, index } => {
+            resource_slot(app_handle, key, path, *index, size, transform)
+        }
+        IconSource::MimeType(mime) => shared_slot(
+            app_handle,
+            &mime_cache_key(mime),
+            size,
+            transform,
+            |native| extract_mime_icon_png(mime, native),
+        ),
+        IconSource::FileType(extension) => shared_slot(
+            app_handle,
+            &file_type_cache_key(extension),
+            size,
+            transform,
+            |native| extract_file_type_icon_png(extension, native),
+        ),
+    }
+}
+
+/// ファイルのアイコンをディスクキャッシュに用意し、キャッシュファイルの位置を返します。
+fn file_slot(
+    app_handle: &tauri::AppHandle,
+    path: String,
+    size: Option<u32>,
+    transform: &IconTransform,
+) -> Result<CacheSlot, String> {
+    let (key, params) = transformed_key(sized_key(&path, "", size), transform)?;
+    ensure_cached(app_handle, &key, &path, &params, || {
+        extract_sized(
+            app_handle,
+            &path,
+            &path,
+            "",
+            size,
+            DEFAULT_FILE_ICON_SIZE,
+            |native| extract_icon_png(&path, native),
+        )
+        .and_then(|png_data| transform.apply(&png_data))
+    })
+}
+
+/// リソースファイル・インデックスのアイコンをディスクキャッシュに用意し、キャッシュファイルの位置を返します。
+fn resource_slot(
     app_handle: &tauri::AppHandle,
     cache_key: &str,
     path: &str,
     index: i32,
-) -> Result<String, String> {
-    // 1. キャッシュキーのハッシュ化
-    let mut hasher = Sha256::new();
-    hasher.update(cache_key.as_bytes());
-    let result = hasher.finalize();
-    let hash = hex::encode(result);
+    size: Option<u32>,
+    transform: &IconTransform,
+) -> Result<CacheSlot, String> {
+    let base_params = index.to_string();
+    let (key, params) = transformed_key(sized_key(cache_key, &base_params, size), transform)?;
+    ensure_cached(app_handle, &key, path, &params, || {
+        extract_sized(
+            app_handle,
+            cache_key,
+            path,
+            &base_params,
+            size,
+            DEFAULT_RESOURCE_ICON_SIZE,
+            |native| extract_icon_from_resource(path, index, native),
+        )
+        .and_then(|png_data| transform.apply(&png_data))
+    })
+}
 
-    // 2. キャッシュディレクトリのセットアップ
-    let app_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| e.to_string())?;
+/// 種類ごとに共有するアイコン（MIME タイプ・拡張子）をディスクキャッシュに用意し、キャッシュファイルの位置を返します。
+fn shared_slot<F>(
+    app_handle: &tauri::AppHandle,
+    cache_key: &str,
+    size: Option<u32>,
+    transform: &IconTransform,
+    extract_native: F,
+) -> Result<CacheSlot, String>
+where
+    F: Fn(u32) -> Result<Vec<u8>, String>,
+{
+    let (key, params) = transformed_key(sized_key(cache_key, "", size), transform)?;
+    ensure_cached(app_handle, &key, cache_key, &params, || {
+        extract_sized(
+            app_handle,
+            cache_key,
+            cache_key,
+            "",
+            size,
+            DEFAULT_FILE_ICON_SIZE,
+            extract_native,
+        )
+        .and_then(|png_data| transform.apply(&png_data))
+    })
+}
 
-    let cache_dir = app_dir.join("cache").join("icons");
-    if !cache_dir.exists() {
-        fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
-    }
+/// セルのアイコンの配色（アクセントカラーと文字色）を返します。
+///
+/// 既定サイズのアイコンをディスクキャッシュに用意し、その PNG を解析します。
+/// 解析結果はキャッシュファイルの隣（`<hash>.palette.json`）に保存され、アイコンが再抽出されるまで再利用されます。
+pub fn get_icon_palette(
+    app_handle: &tauri::AppHandle,
+    source: IconSource,
+) -> Result<IconPalette, String> {
+    let slot = source_slot(app_handle, &source, None, &IconTransform::default())?;
+    icon_palette::load_or_extract(&slot.cache_file)
+}
 
-    let cache_file = cache_dir.join(format!("{}.png", hash));
+/// 指定されたパスを抽出元とするアイコンのキャッシュ（メモリ・ディスク）を破棄します。
+///
+/// パス自体をキーとするキャッシュに加え、同じファイルをリソースとして参照するキャッシュも対象です。
+/// 破棄したキャッシュファイルの数を返します。
+pub fn invalidate(app_handle: &tauri::AppHandle, path: &str) -> Result<usize, String> {
+    let cache_dir = icon_cache_dir(app_handle)?;
 
-    // 3. キャッシュの確認
-    if cache_file.exists() {
-        match fs::read(&cache_file) {
-            Ok(data) => {
-                let base64_string = base64::engine::general_purpose::STANDARD.encode(&data);
-                return Ok(format!("data:image/png;base64,{}", base64_string));
-            }
-            Err(e) => {
-                println!("Failed to read cache: {}", e);
+    let mut hashes = vec![hash_key(path)];
+    if let Some(index) = app_handle.try_state::<IconIndex>() {
+        for hash in index.remove_by_source(path) {
+            if !hashes.contains(&hash) {
+                hashes.push(hash);
             }
         }
+        index.remove(&hashes[0]);
+    }
+
+    Ok(discard(app_handle, &cache_dir, &hashes))
+}
+
+/// 指定されたハッシュのキャッシュファイルとメモリキャッシュを削除し、削除できたファイルの数を返します。
+///
+/// インデックスからの削除は呼び出し側で行います。
+pub fn discard(app_handle: &tauri::AppHandle, cache_dir: &Path, hashes: &[String]) -> usize {
+    let memory = app_handle.try_state::<IconMemoryCache>();
+    let mut removed = 0;
+    for hash in hashes {
+        if let Some(memory) = &memory {
+            memory.remove(hash);
+        }
+        if fs::remove_file(cache_dir.join(format!("{}.png", hash))).is_ok() {
+            removed += 1;
+        }
+        let _ = fs::remove_file(cache_dir.join(icon_palette::sidecar_name(hash)));
     }
+    removed
+}
 
-    // 4. アイコンの抽出 (Resource Index指定)
-    let png_data = extract_icon_from_resource(path, index)?;
+/// すべてのアイコンのキャッシュ（メモリ・ディスク・インデックス）を破棄します。
+///
+/// 破棄したキャッシュファイルの数を返します。
+pub fn invalidate_all(app_handle: &tauri::AppHandle) -> Result<usize, String> {
+    let cache_dir = icon_cache_dir(app_handle)?;
 
-    // 5. キャッシュへの保存
-    let _ = fs::write(&cache_file, &png_data);
+    if let Some(memory) = app_handle.try_state::<IconMemoryCache>() {
+        memory.clear();
+    }
+    if let Some(index) = app_handle.try_state::<IconIndex>() {
+        index.clear();
+    }
 
-    // 6. Base64文字列として返却
-    let base64_string = base64::engine::general_purpose::STANDARD.encode(&png_data);
-    Ok(format!("data:image/png;base64,{}", base64_string))
+    let mut removed = 0;
+    for entry in fs::read_dir(&cache_dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+    {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "png") && fs::remove_file(&path).is_ok() {
+            removed += 1;
+        } else if icon_palette::is_sidecar(&path) {
+            let _ = fs::remove_file(&path);
+        }
+    }
+    Ok(removed)
 }
 
 #[cfg(target_os = "windows")]
-/// 指定されたリソースパスとインデックスからアイコンを抽出します。
-/// PrivateExtractIconsWを使用しています。
-pub fn extract_icon_from_resource(path: &str, index: i32) -> Result<Vec<u8>, String> {
+/// 指定されたリソースパスとインデックスから、`size` ピクセルのアイコンを抽出します。
+/// PrivateExtractIconsWを使用しています。画像ファイルの場合は画像そのものを読み込みます。
+pub fn extract_icon_from_resource(path: &str, index: i32, size: u32) -> Result<Vec<u8>, String> {
+    if is_image_file(path) {
+        return load_image_png(path, size);
+    }
     unsafe {
         use std::ffi::OsStr;
         use std::os::windows::ffi::OsStrExt;
@@ -295,24 +1173,27 @@ pub fn extract_icon_from_resource(path: &str, index: i32) -> Result<Vec<u8>, Str
         let mut hicon_out = [HICON(std::ptr::null_mut())];
         let mut id_out = [0u32];
 
-        // 256x256のアイコン取得を試みる
+        // 指定サイズのアイコン取得を試みる (該当サイズがない場合はWindowsが拡大縮小する)
         // Expects &[u16; 260] for filename according to previous error.
 
         let extracted_count = PrivateExtractIconsW(
             &path_buf,
             index,
-            256,
-            256,
+            size as i32,
+            size as i32,
             Some(&mut hicon_out),
             Some(id_out.as_mut_ptr()),
             1,
         );
 
         if extracted_count == 0 || hicon_out[0].is_invalid() {
-            return Err(format!(
-                "Failed to extract icon from {} at index {}",
-                path, index
-            ));
+            // シェルが読めないリソースは PE / ICO を直接解析して再試行する
+            return ico_reader::extract_icon(path, index, size).map_err(|e| {
+                format!(
+                    "Failed to extract icon from {} at index {}: {}",
+                    path, index, e
+                )
+            });
         }
 
         let hicon = hicon_out[0];
@@ -329,6 +1210,42 @@ pub fn extract_icon_from_resource(path: &str, index: i32) -> Result<Vec<u8>, Str
 }
 
 #[cfg(not(target_os = "windows"))]
-pub fn extract_icon_from_resource(_path: &str, _index: i32) -> Result<Vec<u8>, String> {
-    Err("Icon extraction is only supported on Windows".to_string())
+/// Windows以外のOSでは、`ico_reader` でリソースファイルを直接解析して抽出します。
+/// 画像ファイルの場合は画像そのものを読み込みます。
+pub fn extract_icon_from_resource(path: &str, index: i32, size: u32) -> Result<Vec<u8>, String> {
+    if is_image_file(path) {
+        return load_image_png(path, size);
+    }
+    ico_reader::extract_icon(path, index, size)
+}
+
+/// 画像そのものをアイコンとして読み込むファイルかどうかを返します。
+fn is_image_file(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// 画像ファイルを読み込み、`size` ピクセルの正方形に収まるPNGとして返します。
+///
+/// SVG は `size` ピクセル四方にラスタライズします。
+fn load_image_png(path: &str, size: u32) -> Result<Vec<u8>, String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    if Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg") || ext.eq_ignore_ascii_case("svgz"))
+    {
+        return svg_render::render_svg(&data, size);
+    }
+
+    let mut png_buffer = Vec::new();
+    image::load_from_memory(&data)
+        .map_err(|e| e.to_string())?
+        .write_to(
+            &mut std::io::Cursor::new(&mut png_buffer),
+            ImageOutputFormat::Png,
+        )
+        .map_err(|e| e.to_string())?;
+    resize_png(&png_buffer, size)
 }
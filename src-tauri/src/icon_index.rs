@@ -0,0 +1,387 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// サイドカーインデックスのファイル名（`AppData/cache/icons/` 直下）
+const INDEX_FILE_NAME: &str = "index.json";
+
+/// ディスクキャッシュの既定の上限サイズ（バイト）
+pub const DEFAULT_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// アクセス日時の更新だけでインデックスを書き出す最短間隔
+const TOUCH_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+/// 抽出したキャッシュの記録でインデックスを書き出す最短間隔（大量の抽出をまとめて書き出すため）
+const RECORD_SAVE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// キャッシュされたアイコン1件の抽出元情報。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IconIndexEntry {
+    /// アイコンの抽出元ファイルのパス
+    pub source: String,
+    /// 抽出時の抽出元ファイルの更新日時（UNIXエポックからのミリ秒）
+    pub mtime: u64,
+    /// 抽出時の抽出元ファイルのサイズ（バイト）
+    pub size: u64,
+    /// 抽出パラメータ（リソースインデックスなど）
+    #[serde(default)]
+    pub params: String,
+    /// キャッシュファイルのサイズ（バイト）
+    #[serde(default)]
+    pub bytes: u64,
+    /// 最終アクセス日時（UNIXエポックからのミリ秒）
+    #[serde(default)]
+    pub last_access: u64,
+}
+
+impl IconIndexEntry {
+    /// 抽出元ファイルの現在の状態からエントリを作成します。
+    ///
+    /// ファイルの情報が取得できない場合（存在しない、仮想パスなど）は更新日時・サイズを0とします。
+    pub fn for_source(source: &str, params: &str) -> Self {
+        let (mtime, size) = fs::metadata(source)
+            .map(|m| {
+                let mtime = m
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0);
+                (mtime, m.len())
+            })
+            .unwrap_or((0, 0));
+
+        Self {
+            source: source.to_string(),
+            mtime,
+            size,
+            params: params.to_string(),
+            bytes: 0,
+            last_access: 0,
+        }
+    }
+
+    /// 抽出元・更新日時・サイズ・抽出パラメータがすべて一致するかどうか。
+    fn matches(&self, other: &Self) -> bool {
+        self.source == other.source
+            && self.mtime == other.mtime
+            && self.size == other.size
+            && self.params == other.params
+    }
+}
+
+/// `index.json` の内容。
+#[derive(Serialize, Deserialize)]
+struct IndexFile {
+    /// ディスクキャッシュの上限サイズ（バイト）
+    #[serde(default = "default_max_bytes")]
+    max_bytes: u64,
+    /// キャッシュファイル名（ハッシュ）ごとのエントリ
+    #[serde(default)]
+    entries: HashMap<String, IconIndexEntry>,
+}
+
+fn default_max_bytes() -> u64 {
+    DEFAULT_MAX_BYTES
+}
+
+struct IndexState {
+    file: IndexFile,
+    last_saved: Instant,
+    /// 書き出していない変更があるかどうか
+    dirty: bool,
+}
+
+/// ディスクキャッシュのサイドカーインデックス。
+///
+/// キャッシュファイル名（キャッシュキーのハッシュ）ごとに抽出元の情報と最終アクセス日時を記録し、
+/// 抽出元の更新日時・サイズ・抽出パラメータが変わったキャッシュを古いものとして扱います。
+/// また、キャッシュの合計サイズが上限を超えた場合は、最も長く使われていないものから破棄対象とします。
+/// Tauriのステートとして管理され、`index.json` へ書き出されます。
+/// 抽出の記録やアクセス日時の更新は一定の間隔でまとめて書き出すため、終了時には `flush` を呼び出してください。
+pub struct IconIndex {
+    path: PathBuf,
+    inner: Mutex<IndexState>,
+}
+
+impl IconIndex {
+    /// キャッシュディレクトリの `index.json` を読み込みます。読み込めない場合は空のインデックスになります。
+    pub fn load(cache_dir: &Path) -> Self {
+        let path = cache_dir.join(INDEX_FILE_NAME);
+        let file = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_else(|| IndexFile {
+                max_bytes: DEFAULT_MAX_BYTES,
+                entries: HashMap::new(),
+            });
+
+        Self {
+            path,
+            inner: Mutex::new(IndexState {
+                file,
+                last_saved: Instant::now(),
+                dirty: false,
+            }),
+        }
+    }
+
+    /// キャッシュが `current` に対して最新かどうかを返します。最新の場合は最終アクセス日時を更新します。
+    ///
+    /// インデックスに記録がないキャッシュ（インデックス導入前のものなど）は古いものとして扱います。
+    pub fn is_fresh(&self, hash: &str, current: &IconIndexEntry) -> bool {
+        let Ok(mut state) = self.inner.lock() else {
+            return false;
+        };
+
+        match state.file.entries.get_mut(hash) {
+            Some(entry) if entry.matches(current) => {
+                entry.last_access = now_millis();
+                state.dirty = true;
+                // アクセス日時の更新だけで毎回書き出さないよう間隔を空ける
+                self.save_if_due(&mut state, TOUCH_SAVE_INTERVAL);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 抽出したキャッシュの情報を記録します。
+    ///
+    /// 上限サイズを超えた場合は古いエントリをインデックスから削除し、そのハッシュを返します。
+    /// 対応するキャッシュファイルの削除は呼び出し側で行います。
+    ///
+    /// 書き出しは `RECORD_SAVE_INTERVAL` ごとにまとめて行います。
+    /// 書き出す前に終了した場合も、記録のないキャッシュが次回に再抽出されるだけです。
+    pub fn record(&self, hash: String, mut entry: IconIndexEntry) -> Vec<String> {
+        let Ok(mut state) = self.inner.lock() else {
+            return Vec::new();
+        };
+
+        entry.last_access = now_millis();
+        state.file.entries.insert(hash.clone(), entry);
+        let evicted = evict(&mut state.file, Some(&hash));
+        state.dirty = true;
+        self.save_if_due(&mut state, RECORD_SAVE_INTERVAL);
+        evicted
+    }
+
+    /// 上限サイズ（バイト）を返します。
+    pub fn max_bytes(&self) -> u64 {
+        self.inner
+            .lock()
+            .map(|state| state.file.max_bytes)
+            .unwrap_or(DEFAULT_MAX_BYTES)
+    }
+
+    /// 上限サイズ（バイト）を変更します。
+    ///
+    /// 新しい上限を超えている場合は古いエントリをインデックスから削除し、そのハッシュを返します。
+    pub fn set_max_bytes(&self, max_bytes: u64) -> Vec<String> {
+        let Ok(mut state) = self.inner.lock() else {
+            return Vec::new();
+        };
+
+        state.file.max_bytes = max_bytes;
+        let evicted = evict(&mut state.file, None);
+        self.save(&mut state);
+        evicted
+    }
+
+    /// すべてのエントリの (ハッシュ, エントリ) の一覧を返します。
+    pub fn entries(&self) -> Vec<(String, IconIndexEntry)> {
+        self.inner
+            .lock()
+            .map(|state| {
+                state
+                    .file
+                    .entries
+                    .iter()
+                    .map(|(hash, e)| (hash.clone(), e.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 指定された抽出元から作られたキャッシュのハッシュを、インデックスから削除して返します。
+    pub fn remove_by_source(&self, source: &str) -> Vec<String> {
+        let Ok(mut state) = self.inner.lock() else {
+            return Vec::new();
+        };
+
+        let removed: Vec<String> = state
+            .file
+            .entries
+            .iter()
+            .filter(|(_, e)| same_path(&e.source, source))
+            .map(|(hash, _)| hash.clone())
+            .collect();
+        if !removed.is_empty() {
+            for hash in &removed {
+                state.file.entries.remove(hash);
+            }
+            self.save(&mut state);
+        }
+        removed
+    }
+
+    /// 指定されたハッシュのエントリを削除します。
+    pub fn remove(&self, hash: &str) {
+        self.remove_many(&[hash.to_string()]);
+    }
+
+    /// 指定されたハッシュのエントリをまとめて削除します。
+    pub fn remove_many(&self, hashes: &[String]) {
+        if let Ok(mut state) = self.inner.lock() {
+            let before = state.file.entries.len();
+            for hash in hashes {
+                state.file.entries.remove(hash);
+            }
+            if state.file.entries.len() != before {
+                self.save(&mut state);
+            }
+        }
+    }
+
+    /// すべてのエントリを削除します（上限サイズの設定は保持します）。
+    pub fn clear(&self) {
+        if let Ok(mut state) = self.inner.lock() {
+            state.file.entries.clear();
+            self.save(&mut state);
+        }
+    }
+
+    /// 書き出していない変更があれば、すぐに書き出します。
+    pub fn flush(&self) {
+        if let Ok(mut state) = self.inner.lock() {
+            if state.dirty {
+                self.save(&mut state);
+            }
+        }
+    }
+
+    /// 前回の書き出しから `interval` 以上経過していれば書き出します。
+    fn save_if_due(&self, state: &mut IndexState, interval: Duration) {
+        if state.last_saved.elapsed() >= interval {
+            self.save(state);
+        }
+    }
+
+    /// インデックスを書き出します。書き込みエラーは無視します（次回は再抽出されるだけのため）。
+    ///
+    /// 書き込み中に終了しても壊れたインデックスが残らないよう、一時ファイルに書き込んでからリネームします。
+    fn save(&self, state: &mut IndexState) {
+        if let Ok(json) = serde_json::to_string(&state.file) {
+            let temp_path = self.path.with_extension("json.tmp");
+            if fs::write(&temp_path, json).is_ok() && fs::rename(&temp_path, &self.path).is_err() {
+                let _ = fs::remove_file(&temp_path);
+            }
+        }
+        state.last_saved = Instant::now();
+        state.dirty = false;
+    }
+}
+
+/// 合計サイズが上限以下になるまで、最終アクセス日時の古いエントリから削除し、そのハッシュを返します。
+///
+/// `keep` に指定されたエントリ（記録した直後のものなど）は削除しません。
+fn evict(file: &mut IndexFile, keep: Option<&str>) -> Vec<String> {
+    let mut total: u64 = file.entries.values().map(|e| e.bytes).sum();
+    if total <= file.max_bytes {
+        return Vec::new();
+    }
+
+    let mut candidates: Vec<(u64, u64, String)> = file
+        .entries
+        .iter()
+        .filter(|(hash, _)| Some(hash.as_str()) != keep)
+        .map(|(hash, e)| (e.last_access, e.bytes, hash.clone()))
+        .collect();
+    candidates.sort();
+
+    let mut evicted = Vec::new();
+    for (_, bytes, hash) in candidates {
+        if total <= file.max_bytes {
+            break;
+        }
+        file.entries.remove(&hash);
+        total = total.saturating_sub(bytes);
+        evicted.push(hash);
+    }
+    evicted
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// パスが同じファイルを指すかどうか（Windowsでは大文字・小文字と区切り文字の違いを無視します）。
+pub fn same_path(a: &str, b: &str) -> bool {
+    path_key(a) == path_key(b)
+}
+
+/// パスの比較用のキーを返します（Windowsでは小文字化し、区切り文字を `\` に統一します）。
+pub fn path_key(path: &str) -> String {
+    if cfg!(target_os = "windows") {
+        path.replace('/', "\\").to_lowercase()
+    } else {
+        path.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// テストごとに空の一時ディレクトリを作成します。
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("hexa-icon-index-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entry(bytes: u64) -> IconIndexEntry {
+        IconIndexEntry {
+            bytes,
+            ..IconIndexEntry::for_source("/nonexistent/source", "")
+        }
+    }
+
+    #[test]
+    fn batches_records_until_flush() {
+        let dir = scratch_dir("batch");
+        let index = IconIndex::load(&dir);
+        // 読み込み直後は書き出し間隔が経過していないため、記録はまとめて書き出される
+        index.record("a".into(), entry(10));
+        index.record("b".into(), entry(10));
+        assert!(IconIndex::load(&dir).entries().is_empty());
+
+        index.flush();
+        let reloaded = IconIndex::load(&dir);
+        assert_eq!(reloaded.entries().len(), 2);
+        assert!(reloaded.is_fresh("a", &entry(0)));
+        assert!(!dir.join("index.json.tmp").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let dir = scratch_dir("evict");
+        let index = IconIndex::load(&dir);
+        assert!(index.set_max_bytes(100).is_empty());
+        index.record("old".into(), entry(60));
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(
+            index.record("new".into(), entry(60)),
+            vec!["old".to_string()]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
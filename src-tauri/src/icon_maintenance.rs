@@ -0,0 +1,206 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tauri::Manager;
+
+use crate::cell_health::{self, CellRef};
+use crate::custom_icon;
+use crate::icon_cache;
+use crate::icon_index::{self, IconIndex};
+use crate::icon_memory_cache::{IconMemoryCache, IconMemoryStats};
+use crate::path_utils;
+use crate::shortcut_utils;
+use crate::uwp_catalog::UwpCatalog;
+
+/// アイコンキャッシュの統計情報。
+#[derive(Debug, Serialize)]
+pub struct IconCacheStats {
+    /// ディスク上のキャッシュファイル数
+    pub entries: usize,
+    /// ディスク上のキャッシュファイルの合計サイズ（バイト）
+    pub total_bytes: u64,
+    /// ディスクキャッシュの上限サイズ（バイト）
+    pub max_bytes: u64,
+    /// どのセルからも参照されていないキャッシュファイル数
+    pub orphaned_entries: usize,
+    /// どのセルからも参照されていないキャッシュファイルの合計サイズ（バイト）
+    pub orphaned_bytes: u64,
+    /// メモリキャッシュの統計情報
+    pub memory: IconMemoryStats,
+}
+
+/// コンパクションの結果。
+#[derive(Debug, Serialize)]
+pub struct CompactionResult {
+    /// 削除したキャッシュファイル数
+    pub removed_entries: usize,
+    /// 解放したサイズ（バイト）
+    pub freed_bytes: u64,
+}
+
+/// ディスク・メモリのキャッシュの統計情報を取得します。
+///
+/// 孤立したキャッシュ（どのセルからも参照されていないもの）を判定するため、
+/// 保存済みの `settings.json` を読み込み、ショートカット(.lnk)のリンク先を解決します。
+pub fn stats(app_handle: &tauri::AppHandle) -> Result<IconCacheStats, String> {
+    let cache_dir = icon_cache::icon_cache_dir(app_handle)?;
+    let files = cache_files(&cache_dir);
+    let orphans = find_orphans(app_handle, &files)?;
+
+    let memory = app_handle
+        .try_state::<IconMemoryCache>()
+        .map(|m| m.stats())
+        .unwrap_or_default();
+
+    Ok(IconCacheStats {
+        entries: files.len(),
+        total_bytes: files.values().sum(),
+        max_bytes: app_handle
+            .try_state::<IconIndex>()
+            .map(|index| index.max_bytes())
+            .unwrap_or(icon_index::DEFAULT_MAX_BYTES),
+        orphaned_entries: orphans.len(),
+        orphaned_bytes: orphans.iter().map(|hash| files[hash]).sum(),
+        memory,
+    })
+}
+
+/// 孤立したキャッシュ（どのセルからも参照されていないもの）を削除します。
+///
+/// キャッシュファイルが存在しないインデックスのエントリも合わせて削除します。
+pub fn compact(app_handle: &tauri::AppHandle) -> Result<CompactionResult, String> {
+    let cache_dir = icon_cache::icon_cache_dir(app_handle)?;
+    let files = cache_files(&cache_dir);
+    let orphans = find_orphans(app_handle, &files)?;
+    let freed_bytes = orphans.iter().map(|hash| files[hash]).sum();
+
+    if let Some(index) = app_handle.try_state::<IconIndex>() {
+        let missing: Vec<String> = index
+            .entries()
+            .into_iter()
+            .map(|(hash, _)| hash)
+            .filter(|hash| !files.contains_key(hash))
+            .chain(orphans.iter().cloned())
+            .collect();
+        index.remove_many(&missing);
+    }
+
+    Ok(CompactionResult {
+        removed_entries: icon_cache::discard(app_handle, &cache_dir, &orphans),
+        freed_bytes,
+    })
+}
+
+/// ディスクキャッシュの上限サイズを変更し、超過分を古いものから削除します。
+///
+/// 削除したキャッシュファイル数を返します。
+pub fn set_limit(app_handle: &tauri::AppHandle, max_bytes: u64) -> Result<usize, String> {
+    let cache_dir = icon_cache::icon_cache_dir(app_handle)?;
+    let index = app_handle
+        .try_state::<IconIndex>()
+        .ok_or("Icon cache index is not loaded")?;
+    let evicted = index.set_max_bytes(max_bytes);
+    Ok(icon_cache::discard(app_handle, &cache_dir, &evicted))
+}
+
+/// キャッシュディレクトリの PNG ファイルの (ハッシュ, サイズ) を返します。
+fn cache_files(cache_dir: &Path) -> HashMap<String, u64> {
+    fs::read_dir(cache_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            if !path.extension().is_some_and(|ext| ext == "png") {
+                return None;
+            }
+            let hash = path.file_stem()?.to_string_lossy().to_string();
+            Some((hash, e.metadata().ok()?.len()))
+        })
+        .collect()
+}
+
+/// 孤立したキャッシュファイルのハッシュを返します。
+///
+/// インデックスに記録がないもの、または抽出元がどのセルからも参照されていないものが対象です。
+fn find_orphans(
+    app_handle: &tauri::AppHandle,
+    files: &HashMap<String, u64>,
+) -> Result<Vec<String>, String> {
+    let cells = cell_health::load_cells_from_settings(app_handle)?;
+    let referenced = referenced_sources(&cells, app_handle.try_state::<UwpCatalog>().as_deref());
+
+    let sources: HashMap<String, String> = app_handle
+        .try_state::<IconIndex>()
+        .map(|index| {
+            index
+                .entries()
+                .into_iter()
+                .map(|(hash, e)| (hash, e.source))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(files
+        .keys()
+        .filter(|hash| match sources.get(*hash) {
+            Some(source) => !referenced.contains(&icon_index::path_key(source)),
+            None => true,
+        })
+        .cloned()
+        .collect())
+}
+
+/// セルがアイコンの抽出元として参照しうるパスの集合（比較用キー）を返します。
+///
+/// ターゲット、その実体パス、カスタムアイコン、ショートカットのリンク先とアイコンリソース、
+/// UWPアプリのロゴ、それらの MIME タイプのアイコンを含みます。
+fn referenced_sources(cells: &[CellRef], uwp_catalog: Option<&UwpCatalog>) -> HashSet<String> {
+    let mut sources = HashSet::new();
+    let mut links = Vec::new();
+
+    for cell in cells {
+        let target = cell
+            .shortcut
+            .as_ref()
+            .and_then(|s| s.target_path.clone())
+            .or_else(|| cell.target.clone())
+            .unwrap_or_default();
+        add_source(&mut sources, &target);
+        if let Some(logo) = uwp_catalog.and_then(|catalog| catalog.logo_for(&target)) {
+            add_source(&mut sources, &logo);
+        }
+        if let Some(custom_icon) = &cell.custom_icon {
+            // インポート済みのアイコンはアイコンキャッシュを使用しない
+            if !custom_icon::is_reference(custom_icon) {
+                add_source(&mut sources, custom_icon);
+            }
+        }
+        if target.to_lowercase().ends_with(".lnk") {
+            links.push(target);
+        }
+    }
+
+    for item in shortcut_utils::resolve_lnk_batch(links, |_, _| {}) {
+        if let Some(info) = item.resolved {
+            add_source(&mut sources, &info.target);
+            add_source(&mut sources, &info.icon_path);
+        }
+    }
+
+    sources
+}
+
+/// パスとその実体パス、種類ごとのアイコンを使うファイルはその種類（MIME タイプ・拡張子）を参照済みとして追加します。
+fn add_source(sources: &mut HashSet<String>, path: &str) {
+    if path.is_empty() {
+        return;
+    }
+    let real_path = path_utils::real_path(path);
+    if let Some(key) = icon_cache::shared_cache_key(&real_path) {
+        sources.insert(icon_index::path_key(&key));
+    }
+    sources.insert(icon_index::path_key(path));
+    sources.insert(icon_index::path_key(&real_path));
+}
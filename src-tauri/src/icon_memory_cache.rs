@@ -0,0 +1,225 @@
+use lru::LruCache;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// メモリキャッシュの既定の上限サイズ（バイト）
+const DEFAULT_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// アイコンキャッシュのメモリ層。
+///
+/// キャッシュキー（SHA-256ハッシュ）ごとに、エンコード済みのデータURIを保持します。
+/// 保持しているデータURIの合計サイズが上限を超えた場合、最も長く使われていないものから破棄します。
+/// Tauriのステートとして管理され、`icon_cache` のディスク層より先に参照されます。
+pub struct IconMemoryCache {
+    inner: Mutex<Inner>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+struct Inner {
+    entries: LruCache<String, String>,
+    bytes: usize,
+    max_bytes: usize,
+}
+
+impl Default for IconMemoryCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// メモリキャッシュの統計情報。
+#[derive(Debug, Default, Serialize)]
+pub struct IconMemoryStats {
+    /// 保持しているエントリ数
+    pub entries: usize,
+    /// 保持しているデータURIの合計サイズ（バイト）
+    pub bytes: usize,
+    /// 上限サイズ（バイト）
+    pub max_bytes: usize,
+    /// キャッシュヒット数
+    pub hits: u64,
+    /// キャッシュミス数
+    pub misses: u64,
+}
+
+impl IconMemoryCache {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_MAX_BYTES)
+    }
+
+    /// 上限サイズ（バイト）を指定して作成します。
+    pub fn with_capacity(max_bytes: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                entries: LruCache::unbounded(),
+                bytes: 0,
+                max_bytes,
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// キャッシュキーに対応するデータURIを取得します。ヒット・ミスの回数を記録します。
+    pub fn get(&self, key: &str) -> Option<String> {
+        let found = match self.inner.lock() {
+            Ok(mut guard) => guard.entries.get(key).cloned(),
+            Err(_) => None,
+        };
+
+        if found.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        found
+    }
+
+    /// データURIを登録し、上限を超えた分を古いものから破棄します。
+    ///
+    /// 単体で上限を超えるデータは登録しません。
+    pub fn insert(&self, key: String, data_uri: String) {
+        let Ok(mut guard) = self.inner.lock() else {
+            return;
+        };
+
+        let size = data_uri.len();
+        if size > guard.max_bytes {
+            return;
+        }
+
+        if let Some(old) = guard.entries.put(key, data_uri) {
+            guard.bytes -= old.len();
+        }
+        guard.bytes += size;
+
+        while guard.bytes > guard.max_bytes {
+            match guard.entries.pop_lru() {
+                Some((_, evicted)) => guard.bytes -= evicted.len(),
+                None => break,
+            }
+        }
+    }
+
+    /// キャッシュキーに対応するエントリを破棄します。
+    pub fn remove(&self, key: &str) {
+        if let Ok(mut guard) = self.inner.lock() {
+            if let Some(old) = guard.entries.pop(key) {
+                guard.bytes -= old.len();
+            }
+        }
+    }
+
+    /// すべてのエントリを破棄します。ヒット・ミスの回数は保持します。
+    pub fn clear(&self) {
+        if let Ok(mut guard) = self.inner.lock() {
+            guard.entries.clear();
+            guard.bytes = 0;
+        }
+    }
+
+    /// 現在の統計情報を返します。
+    pub fn stats(&self) -> IconMemoryStats {
+        let (entries, bytes, max_bytes) = match self.inner.lock() {
+            Ok(guard) => (guard.entries.len(), guard.bytes, guard.max_bytes),
+            Err(_) => (0, 0, 0),
+        };
+        IconMemoryStats {
+            entries,
+            bytes,
+            max_bytes,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(len: usize) -> String {
+        "x".repeat(len)
+    }
+
+    #[test]
+    fn tracks_bytes_on_insert_replace_and_remove() {
+        let cache = IconMemoryCache::with_capacity(100);
+        cache.insert("a".to_string(), entry(10));
+        cache.insert("b".to_string(), entry(20));
+        assert_eq!(cache.stats().bytes, 30);
+
+        cache.insert("a".to_string(), entry(5));
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.bytes, 25);
+
+        cache.remove("b");
+        cache.remove("missing");
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.bytes, 5);
+        assert_eq!(stats.max_bytes, 100);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_when_over_capacity() {
+        let cache = IconMemoryCache::with_capacity(30);
+        cache.insert("a".to_string(), entry(10));
+        cache.insert("b".to_string(), entry(10));
+        cache.insert("c".to_string(), entry(10));
+        // "a" を参照して "b" を最も古いエントリにする
+        assert!(cache.get("a").is_some());
+
+        cache.insert("d".to_string(), entry(15));
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_none());
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("d").is_some());
+        assert_eq!(cache.stats().bytes, 25);
+    }
+
+    #[test]
+    fn rejects_entries_larger_than_capacity() {
+        let cache = IconMemoryCache::with_capacity(10);
+        cache.insert("small".to_string(), entry(10));
+        cache.insert("large".to_string(), entry(11));
+
+        assert!(cache.get("large").is_none());
+        assert!(cache.get("small").is_some());
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.bytes, 10);
+    }
+
+    #[test]
+    fn counts_hits_and_misses() {
+        let cache = IconMemoryCache::with_capacity(100);
+        cache.insert("a".to_string(), entry(1));
+        assert_eq!(cache.get("a").as_deref(), Some("x"));
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 1);
+    }
+
+    #[test]
+    fn clear_drops_entries_but_keeps_counters() {
+        let cache = IconMemoryCache::with_capacity(100);
+        cache.insert("a".to_string(), entry(10));
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+
+        cache.clear();
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 0);
+        assert_eq!(stats.bytes, 0);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+        assert!(cache.get("a").is_none());
+    }
+}
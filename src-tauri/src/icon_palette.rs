@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 配色の解析結果を保存するファイルの接尾辞（キャッシュファイルの `.png` を置き換えます）
+const SIDECAR_SUFFIX: &str = ".palette.json";
+/// 抽出する代表色の数（メディアンカットの分割数）
+const PALETTE_SIZE: usize = 8;
+/// 解析の対象とするピクセルのアルファ値の下限
+const MIN_ALPHA: u8 = 128;
+/// 暗すぎる・明るすぎる色をアクセントカラーの候補から外すための明度の範囲
+const ACCENT_LIGHTNESS_RANGE: (f32, f32) = (0.12, 0.92);
+
+/// アイコンから抽出した配色。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IconPalette {
+    /// セルの背景などに使うアクセントカラー（`#rrggbb`）
+    pub accent: String,
+    /// アクセントカラーの上に載せる文字色（`#000000` または `#ffffff`）
+    pub text: String,
+    /// 代表色（`#rrggbb`）。占める面積の大きい順に並びます
+    pub palette: Vec<String>,
+}
+
+/// 書き込み中の一時ファイルの名前を、同時に保存するスレッド間で重複させないための連番
+static TEMP_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// キャッシュファイル名のハッシュから、配色を保存するファイル名を返します。
+pub fn sidecar_name(hash: &str) -> String {
+    format!("{}{}", hash, SIDECAR_SUFFIX)
+}
+
+/// 配色を保存したファイルかどうかを返します。
+pub fn is_sidecar(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(SIDECAR_SUFFIX))
+}
+
+/// アイコンのキャッシュファイル（PNG）の配色を返します。
+///
+/// キャッシュファイルの隣に保存された解析結果がキャッシュファイルより新しければそれを使い、
+/// なければ解析して保存します。保存に失敗しても解析結果は返します。
+/// 書き込み途中のファイルを読まないよう、一時ファイルに書き込んでから置き換えます。
+pub fn load_or_extract(cache_file: &Path) -> Result<IconPalette, String> {
+    let hash = cache_file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| format!("Invalid icon cache file: {}", cache_file.display()))?;
+    let sidecar = cache_file.with_file_name(sidecar_name(hash));
+    // 一時ファイルも配色のファイルとして扱われる名前にし、残った場合もキャッシュの削除で消えるようにする
+    let temp = cache_file.with_file_name(sidecar_name(&format!(
+        "{}.tmp{}",
+        hash,
+        TEMP_SEQ.fetch_add(1, Ordering::Relaxed)
+    )));
+
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    if let (Some(png_time), Some(sidecar_time)) = (modified(cache_file), modified(&sidecar)) {
+        if sidecar_time >= png_time {
+            if let Some(palette) = fs::read(&sidecar)
+                .ok()
+                .and_then(|data| serde_json::from_slice(&data).ok())
+            {
+                return Ok(palette);
+            }
+        }
+    }
+
+    let png_data = fs::read(cache_file).map_err(|e| e.to_string())?;
+    let palette = extract(&png_data)?;
+    if let Ok(json) = serde_json::to_vec(&palette) {
+        if let Err(e) = fs::write(&temp, json).and_then(|_| fs::rename(&temp, &sidecar)) {
+            let _ = fs::remove_file(&temp);
+            eprintln!("Failed to write icon palette {}: {}", sidecar.display(), e);
+        }
+    }
+    Ok(palette)
+}
+
+/// PNGデータをメディアンカットで減色し、アクセントカラーと文字色を決定します。
+///
+/// アクセントカラーは面積と彩度から選び、ほぼ黒・ほぼ白の色は背景や輪郭であることが多いため優先度を下げます。
+/// 文字色はアクセントカラーとのコントラスト比（WCAG）が大きい方の黒または白です。
+pub fn extract(png_data: &[u8]) -> Result<IconPalette, String> {
+    let image = image::load_from_memory(png_data)
+        .map_err(|e| e.to_string())?
+        .to_rgba8();
+    let pixels: Vec<[u8; 3]> = image
+        .pixels()
+        .filter(|p| p[3] >= MIN_ALPHA)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect();
+    if pixels.is_empty() {
+        return Err("Icon has no opaque pixels".to_string());
+    }
+
+    let total = pixels.len() as f32;
+    let mut swatches: Vec<([u8; 3], usize)> = median_cut(pixels, PALETTE_SIZE)
+        .into_iter()
+        .map(|bucket| (average(&bucket), bucket.len()))
+        .collect();
+    swatches.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+    let score = |&(color, count): &([u8; 3], usize)| {
+        let (saturation, lightness) = saturation_lightness(color);
+        let (min, max) = ACCENT_LIGHTNESS_RANGE;
+        let weight = if lightness < min || lightness > max {
+            0.2
+        } else {
+            1.0
+        };
+        count as f32 / total * (0.25 + saturation) * weight
+    };
+    let accent = swatches
+        .iter()
+        .max_by(|a, b| score(a).total_cmp(&score(b)))
+        .map(|&(color, _)| color)
+        .unwrap_or([0, 0, 0]);
+
+    let luminance = relative_luminance(accent);
+    // 白・黒それぞれとのコントラスト比 (L1 + 0.05) / (L2 + 0.05) を比較
+    let text = if (luminance + 0.05) / 0.05 >= 1.05 / (luminance + 0.05) {
+        "#000000"
+    } else {
+        "#ffffff"
+    };
+
+    Ok(IconPalette {
+        accent: to_hex(accent),
+        text: text.to_string(),
+        palette: swatches.iter().map(|&(color, _)| to_hex(color)).collect(),
+    })
+}
+
+/// 色の範囲が最も広いチャンネルの中央値で分割を繰り返し、最大 `count` 個のグループに分けます。
+fn median_cut(pixels: Vec<[u8; 3]>, count: usize) -> Vec<Vec<[u8; 3]>> {
+    let mut buckets = vec![pixels];
+    while buckets.len() < count {
+        // 分割できる（2色以上を含む）グループのうち、範囲が最も広いもの
+        let Some((index, channel, _)) = buckets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, bucket)| {
+                let (channel, range) = widest_channel(bucket);
+                (range > 0).then_some((i, channel, range))
+            })
+            .max_by_key(|&(_, _, range)| range)
+        else {
+            break;
+        };
+
+        let mut bucket = buckets.swap_remove(index);
+        bucket.sort_unstable_by_key(|p| p[channel]);
+        let upper = bucket.split_off(bucket.len() / 2);
+        buckets.push(bucket);
+        buckets.push(upper);
+    }
+    buckets
+}
+
+/// 値の範囲が最も広いチャンネルとその範囲を返します。
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let (min, max) = pixels.iter().fold((u8::MAX, u8::MIN), |(min, max), p| {
+                (min.min(p[channel]), max.max(p[channel]))
+            });
+            (channel, max.saturating_sub(min))
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+fn average(pixels: &[[u8; 3]]) -> [u8; 3] {
+    let mut sum = [0u64; 3];
+    for p in pixels {
+        for channel in 0..3 {
+            sum[channel] += p[channel] as u64;
+        }
+    }
+    let len = pixels.len().max(1) as u64;
+    [
+        (sum[0] / len) as u8,
+        (sum[1] / len) as u8,
+        (sum[2] / len) as u8,
+    ]
+}
+
+/// HSL の彩度と明度（いずれも 0.0〜1.0）を返します。
+fn saturation_lightness(color: [u8; 3]) -> (f32, f32) {
+    let [r, g, b] = color.map(|c| c as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let saturation = if max == min {
+        0.0
+    } else {
+        (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+    };
+    (saturation, lightness)
+}
+
+/// WCAG の相対輝度を返します。
+fn relative_luminance(color: [u8; 3]) -> f32 {
+    let [r, g, b] = color.map(|c| {
+        let c = c as f32 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    });
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+fn to_hex(color: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageOutputFormat, Rgba, RgbaImage};
+
+    fn png(image: RgbaImage) -> Vec<u8> {
+        let mut data = Vec::new();
+        image::DynamicImage::ImageRgba8(image)
+            .write_to(&mut std::io::Cursor::new(&mut data), ImageOutputFormat::Png)
+            .unwrap();
+        data
+    }
+
+    fn solid(color: [u8; 4]) -> Vec<u8> {
+        png(RgbaImage::from_pixel(8, 8, Rgba(color)))
+    }
+
+    #[test]
+    fn solid_color_becomes_the_accent() {
+        let palette = extract(&solid([0x20, 0x80, 0xc0, 255])).unwrap();
+        assert_eq!(palette.accent, "#2080c0");
+        assert_eq!(palette.palette, ["#2080c0"]);
+    }
+
+    #[test]
+    fn picks_readable_text_color() {
+        assert_eq!(
+            extract(&solid([0xff, 0xe0, 0x60, 255])).unwrap().text,
+            "#000000"
+        );
+        assert_eq!(
+            extract(&solid([0x20, 0x20, 0x80, 255])).unwrap().text,
+            "#ffffff"
+        );
+        assert!(relative_luminance([255, 255, 255]) > 0.99);
+        assert!(relative_luminance([0, 0, 0]) < 0.01);
+    }
+
+    #[test]
+    fn prefers_saturated_colors_over_near_black() {
+        // 大部分がほぼ黒の輪郭でも、彩度の高い色をアクセントにする
+        let mut image = RgbaImage::from_pixel(10, 10, Rgba([5, 5, 5, 255]));
+        for x in 0..10 {
+            for y in 0..3 {
+                image.put_pixel(x, y, Rgba([230, 40, 40, 255]));
+            }
+        }
+        let palette = extract(&png(image)).unwrap();
+        assert_eq!(palette.accent, "#e62828");
+        assert_eq!(palette.palette[0], "#050505");
+    }
+
+    #[test]
+    fn caps_the_palette_size() {
+        let image = RgbaImage::from_fn(16, 16, |x, y| {
+            Rgba([(x * 16) as u8, (y * 16) as u8, ((x + y) * 8) as u8, 255])
+        });
+        let palette = extract(&png(image)).unwrap();
+        assert_eq!(palette.palette.len(), PALETTE_SIZE);
+
+        let pixels: Vec<[u8; 3]> = (0..=255).map(|v| [v, 0, 0]).collect();
+        assert_eq!(widest_channel(&pixels), (0, 255));
+        assert_eq!(median_cut(pixels, 4).len(), 4);
+        // 同じ色だけのグループは分割しない
+        assert_eq!(median_cut(vec![[1, 2, 3]; 10], 4).len(), 1);
+    }
+
+    #[test]
+    fn rejects_fully_transparent_images() {
+        assert!(extract(&solid([255, 0, 0, 0])).is_err());
+        assert!(extract(b"not a png").is_err());
+    }
+
+    #[test]
+    fn measures_saturation_and_lightness() {
+        assert_eq!(saturation_lightness([255, 0, 0]), (1.0, 0.5));
+        assert_eq!(saturation_lightness([255, 255, 255]), (0.0, 1.0));
+        assert_eq!(saturation_lightness([0, 0, 0]), (0.0, 0.0));
+    }
+
+    #[test]
+    fn recognizes_sidecar_names() {
+        let name = sidecar_name("abc123");
+        assert!(is_sidecar(Path::new(&name)));
+        assert!(is_sidecar(&Path::new("/cache/icons").join(&name)));
+        assert!(!is_sidecar(Path::new("abc123.png")));
+        assert!(!is_sidecar(Path::new("index.json")));
+    }
+
+    #[test]
+    fn saves_and_reuses_the_sidecar() {
+        let dir = std::env::temp_dir().join(format!("icon-palette-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let cache_file = dir.join("abc123.png");
+        fs::write(&cache_file, solid([0x20, 0x80, 0xc0, 255])).unwrap();
+
+        let palette = load_or_extract(&cache_file).unwrap();
+        let sidecar = dir.join(sidecar_name("abc123"));
+        assert!(sidecar.is_file());
+        // 一時ファイルは残らない
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        assert_eq!(load_or_extract(&cache_file).unwrap().accent, palette.accent);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
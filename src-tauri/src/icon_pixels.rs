@@ -0,0 +1,164 @@
+/// BGRA のバッファを RGBA に並べ替えます。
+pub fn bgra_to_rgba(pixels: &mut [u8]) {
+    for chunk in pixels.chunks_exact_mut(4) {
+        chunk.swap(0, 2);
+    }
+}
+
+/// アルファチャンネルに意味のある値が含まれるかどうかを返します。
+///
+/// アルファを持たない古い形式のアイコン（24ビット以下、またはアルファがすべて0の32ビット）では
+/// `false` になり、透過は ANDマスクで決定する必要があります。
+pub fn has_alpha(rgba: &[u8]) -> bool {
+    rgba.chunks_exact(4).any(|p| p[3] != 0)
+}
+
+/// ANDマスクで透過を決定します。
+///
+/// アルファチャンネルが有効な場合は何もしません。
+/// `mask` はピクセルごとの透過フラグ（`true` が透過）で、`None` の場合はすべて不透明とします。
+pub fn apply_and_mask(rgba: &mut [u8], mask: Option<&[bool]>) {
+    if has_alpha(rgba) {
+        return;
+    }
+    for (i, pixel) in rgba.chunks_exact_mut(4).enumerate() {
+        let transparent = mask.is_some_and(|m| m.get(i).copied().unwrap_or(false));
+        pixel[3] = if transparent { 0 } else { 255 };
+    }
+}
+
+/// 1ビット/ピクセルのマスク（各行は4バイト境界に揃えられています）から透過フラグを作ります。
+///
+/// `bottom_up` が `true` の場合、DIB と同様に下の行から格納されているものとして扱います。
+pub fn mask_from_bits(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    bottom_up: bool,
+) -> Result<Vec<bool>, String> {
+    let stride = width.div_ceil(32) * 4;
+    if data.len() < stride * height {
+        return Err("AND mask is truncated".to_string());
+    }
+
+    let mut mask = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let row_index = if bottom_up { height - 1 - y } else { y };
+        let row = &data[row_index * stride..][..stride];
+        mask.extend((0..width).map(|x| row[x / 8] & (0x80 >> (x % 8)) != 0));
+    }
+    Ok(mask)
+}
+
+/// 32ビットで取得したマスクビットマップ（白が透過、黒が不透明）から透過フラグを作ります。
+pub fn mask_from_bgra(data: &[u8], width: usize, height: usize) -> Result<Vec<bool>, String> {
+    let len = width * height * 4;
+    if data.len() < len {
+        return Err("AND mask is truncated".to_string());
+    }
+    Ok(data[..len]
+        .chunks_exact(4)
+        .map(|p| p[0] | p[1] | p[2] != 0)
+        .collect())
+}
+
+/// モノクロアイコン（カラービットマップを持たず、マスクの上半分が AND、下半分が XOR のもの）を RGBA に変換します。
+///
+/// `mask` は高さ `height * 2` のマスクビットマップを32ビットで取得したものです。
+/// AND=0 のピクセルは XOR に応じて黒か白、AND=1 のピクセルは透過とします。
+/// 背景を反転するピクセル（AND=1, XOR=1）は背景に依存しない表示にするため黒とします。
+pub fn monochrome_to_rgba(mask: &[u8], width: usize, height: usize) -> Result<Vec<u8>, String> {
+    let and_mask = mask_from_bgra(mask, width, height)?;
+    let xor_mask = mask_from_bgra(&mask[width * height * 4..], width, height)?;
+
+    Ok(and_mask
+        .iter()
+        .zip(&xor_mask)
+        .flat_map(|(&and, &xor)| match (and, xor) {
+            (false, false) => [0, 0, 0, 255],
+            (false, true) => [255, 255, 255, 255],
+            (true, false) => [0, 0, 0, 0],
+            (true, true) => [0, 0, 0, 255],
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swaps_blue_and_red() {
+        let mut pixels = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        bgra_to_rgba(&mut pixels);
+        assert_eq!(pixels, vec![3, 2, 1, 4, 7, 6, 5, 8]);
+    }
+
+    #[test]
+    fn keeps_existing_alpha() {
+        let mut rgba = vec![10, 20, 30, 128, 40, 50, 60, 0];
+        apply_and_mask(&mut rgba, Some(&[true, true]));
+        assert_eq!(rgba, vec![10, 20, 30, 128, 40, 50, 60, 0]);
+    }
+
+    #[test]
+    fn builds_alpha_from_mask_when_alpha_is_empty() {
+        let mut rgba = vec![10, 20, 30, 0, 40, 50, 60, 0];
+        apply_and_mask(&mut rgba, Some(&[false, true]));
+        assert_eq!(rgba, vec![10, 20, 30, 255, 40, 50, 60, 0]);
+    }
+
+    #[test]
+    fn treats_missing_mask_as_opaque() {
+        let mut rgba = vec![10, 20, 30, 0];
+        apply_and_mask(&mut rgba, None);
+        assert_eq!(rgba[3], 255);
+    }
+
+    #[test]
+    fn reads_packed_mask_rows() {
+        // 幅2・高さ2、各行4バイト。上の行は左のみ透過、下の行は右のみ透過
+        let top = [0b1000_0000, 0, 0, 0];
+        let bottom = [0b0100_0000, 0, 0, 0];
+
+        let data = [top, bottom].concat();
+        assert_eq!(
+            mask_from_bits(&data, 2, 2, false).unwrap(),
+            vec![true, false, false, true]
+        );
+
+        let data = [bottom, top].concat();
+        assert_eq!(
+            mask_from_bits(&data, 2, 2, true).unwrap(),
+            vec![true, false, false, true]
+        );
+
+        assert!(mask_from_bits(&top, 2, 2, false).is_err());
+    }
+
+    #[test]
+    fn reads_mask_bitmap() {
+        let data = [0, 0, 0, 0, 255, 255, 255, 0];
+        assert_eq!(mask_from_bgra(&data, 2, 1).unwrap(), vec![false, true]);
+    }
+
+    #[test]
+    fn converts_monochrome_icons() {
+        let black = [0, 0, 0, 0];
+        let white = [255, 255, 255, 0];
+        // 上半分が AND マスク、下半分が XOR マスク
+        let mask = [black, black, white, white, black, white, black, white].concat();
+
+        assert_eq!(
+            monochrome_to_rgba(&mask, 4, 1).unwrap(),
+            [
+                [0, 0, 0, 255],
+                [255, 255, 255, 255],
+                [0, 0, 0, 0],
+                [0, 0, 0, 255],
+            ]
+            .concat()
+        );
+        assert!(monochrome_to_rgba(&mask[..16], 4, 1).is_err());
+    }
+}
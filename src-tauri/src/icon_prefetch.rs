@@ -0,0 +1,438 @@
+use crate::background_service::{self, lock};
+use crate::icon_cache;
+use crate::icon_transform::IconTransform;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use tauri::{AppHandle, Emitter};
+
+/// ワーカースレッド数の上限
+const MAX_PREFETCH_WORKERS: usize = 4;
+/// 抽出が完了するごとに発行するイベント名
+const ICON_READY_EVENT: &str = "icon-ready";
+
+/// 先読みの優先度。
+///
+/// 画面に表示されているセルを最優先に、次に隣接するセル（同じグループの画面外のセルや子グループ）、
+/// 最後にその他のセルの順で処理します。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IconPriority {
+    Rest,
+    Neighbor,
+    Visible,
+}
+
+/// 先読みするアイコンの要求。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IconRequest {
+    /// 呼び出し側が指定する識別子。同じキーの要求は重複して処理せず、`icon-ready` イベントでそのまま返します
+    pub key: String,
+    /// セルのターゲットパス
+    pub path: String,
+    /// ショートカット(.lnk)の場合、リンク先のアイコンを使用するか
+    #[serde(default)]
+    pub resolve_shortcut: bool,
+    /// シンボリックリンク・ジャンクションを解決した実体パスのアイコンを使用するか
+    #[serde(default)]
+    pub resolve_links: bool,
+    /// 要求するピクセルサイズ（省略時は既定サイズ）
+    #[serde(default)]
+    pub size: Option<u32>,
+    /// アイコンに適用する後処理
+    #[serde(default)]
+    pub transform: Option<IconTransform>,
+    pub priority: IconPriority,
+}
+
+/// `icon-ready` イベントのペイロード。
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IconReady {
+    /// 要求時に指定された識別子
+    pub key: String,
+    pub path: String,
+    /// アイコンのURL（`hexicon` プロトコル）。抽出に失敗した場合は `None`
+    pub url: Option<String>,
+    /// 抽出に失敗した場合のエラーメッセージ
+    pub error: Option<String>,
+}
+
+/// アイコンの先読みキュー。
+///
+/// 要求を優先度付きキューに積み、ワーカースレッドがディスクキャッシュへの抽出を行って、
+/// 1件完了するごとに `icon-ready` イベントを発行します。
+/// 待機中・処理中の要求と同じキーの要求は重複して処理しません（待機中の場合は優先度のみ引き上げます）。
+/// 要求はグループ（画面）単位で登録し、グループを離れた際にまとめて取り消せます。
+/// ワーカースレッドは最初の要求時に起動し、以降はキューが空の間待機します。
+pub struct IconPrefetcher {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    state: Mutex<QueueState>,
+    available: Condvar,
+}
+
+#[derive(Default)]
+struct QueueState {
+    /// 優先度順のキュー。優先度の変更や取り消しで古くなったエントリは取り出し時に読み飛ばします
+    queue: BinaryHeap<QueuedJob>,
+    /// 待機中の要求（キー → 要求）
+    pending: HashMap<String, PendingJob>,
+    /// 処理中の要求のキー
+    in_flight: HashSet<String>,
+    next_seq: u64,
+    workers: usize,
+}
+
+struct PendingJob {
+    request: IconRequest,
+    /// この要求を登録したグループ。すべてのグループから取り消されると破棄します
+    groups: HashSet<String>,
+    /// キュー内の有効なエントリの登録順
+    seq: u64,
+}
+
+#[derive(PartialEq, Eq)]
+struct QueuedJob {
+    priority: IconPriority,
+    seq: u64,
+    key: String,
+}
+
+impl Ord for QueuedJob {
+    /// 優先度が高いもの、同じ優先度では先に登録されたものを先に取り出します。
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for QueuedJob {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl QueueState {
+    fn push(&mut self, key: String, priority: IconPriority) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.queue.push(QueuedJob { priority, seq, key });
+        seq
+    }
+
+    /// 要求を待機中として登録し、新たに追加した場合は `true` を返します。
+    ///
+    /// 処理中の要求と同じキーは無視し、待機中の要求と同じキーはグループを追加した上で、
+    /// 優先度が高い場合のみキューに積み直します（古いエントリは `pop` で読み飛ばします）。
+    fn add(&mut self, request: IconRequest, group: &str) -> bool {
+        if self.in_flight.contains(&request.key) {
+            return false;
+        }
+        let key = request.key.clone();
+        let priority = request.priority;
+
+        if let Some(pending) = self.pending.get_mut(&key) {
+            pending.groups.insert(group.to_string());
+            if priority > pending.request.priority {
+                pending.request.priority = priority;
+                let seq = self.push(key.clone(), priority);
+                if let Some(pending) = self.pending.get_mut(&key) {
+                    pending.seq = seq;
+                }
+            }
+            return false;
+        }
+
+        let seq = self.push(key.clone(), priority);
+        self.pending.insert(
+            key,
+            PendingJob {
+                request,
+                groups: HashSet::from([group.to_string()]),
+                seq,
+            },
+        );
+        true
+    }
+
+    /// グループに登録された待機中の要求を取り消し、破棄した要求のキーを返します。
+    fn cancel(&mut self, group: Option<&str>) -> Vec<String> {
+        let cancelled: Vec<String> = match group {
+            Some(group) => self
+                .pending
+                .iter_mut()
+                .filter_map(|(key, pending)| {
+                    (pending.groups.remove(group) && pending.groups.is_empty()).then(|| key.clone())
+                })
+                .collect(),
+            None => self.pending.keys().cloned().collect(),
+        };
+        for key in &cancelled {
+            self.pending.remove(key);
+        }
+        if self.pending.is_empty() {
+            self.queue.clear();
+        }
+        cancelled
+    }
+
+    /// 次に処理する要求を取り出し、処理中として記録します。
+    fn pop(&mut self) -> Option<IconRequest> {
+        while let Some(job) = self.queue.pop() {
+            let is_current = self
+                .pending
+                .get(&job.key)
+                .is_some_and(|pending| pending.seq == job.seq);
+            if !is_current {
+                continue;
+            }
+            let pending = self.pending.remove(&job.key)?;
+            self.in_flight.insert(job.key);
+            return Some(pending.request);
+        }
+        None
+    }
+}
+
+impl IconPrefetcher {
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(QueueState::default()),
+                available: Condvar::new(),
+            }),
+        }
+    }
+
+    /// 要求をキューに追加し、新たに追加した件数を返します。
+    ///
+    /// # 引数
+    /// * `app_handle` - イベント発行に使用するAppHandle
+    /// * `requests` - 先読みするアイコンの要求
+    /// * `group` - 要求を登録するグループ（`cancel` で取り消す単位）
+    pub fn enqueue(
+        &self,
+        app_handle: &AppHandle,
+        requests: Vec<IconRequest>,
+        group: &str,
+    ) -> usize {
+        let mut state = lock(&self.shared.state);
+        let mut added = 0;
+        for request in requests {
+            if state.add(request, group) {
+                added += 1;
+            }
+        }
+
+        let wanted = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(MAX_PREFETCH_WORKERS)
+            .min(state.pending.len());
+        while state.workers < wanted {
+            state.workers += 1;
+            let shared = self.shared.clone();
+            let app_handle = app_handle.clone();
+            thread::spawn(move || worker(shared, app_handle));
+        }
+        drop(state);
+
+        self.shared.available.notify_all();
+        added
+    }
+
+    /// グループに登録された待機中の要求を取り消し、破棄した要求のキーを返します。
+    ///
+    /// 他のグループからも登録されている要求は残します。処理中の要求は完了まで実行されます。
+    /// `group` が `None` の場合は、待機中のすべての要求を破棄します。
+    pub fn cancel(&self, group: Option<&str>) -> Vec<String> {
+        lock(&self.shared.state).cancel(group)
+    }
+}
+
+impl Default for IconPrefetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 起動中のワーカースレッドの数を、スレッドの終了時（パニックを含む）に減らすためのガード。
+struct WorkerSlot(Arc<Shared>);
+
+impl Drop for WorkerSlot {
+    fn drop(&mut self) {
+        let mut state = lock(&self.0.state);
+        state.workers = state.workers.saturating_sub(1);
+    }
+}
+
+/// キューから要求を取り出してアイコンを抽出し、結果をイベントで通知するワーカースレッドの本体。
+///
+/// 抽出中のパニックはエラーとして通知し、処理中のキーを解放した上で次の要求の処理を続けます。
+fn worker(shared: Arc<Shared>, app_handle: AppHandle) {
+    let _slot = WorkerSlot(shared.clone());
+    #[cfg(target_os = "windows")]
+    let _com = crate::shortcut_utils::ComGuard::init();
+
+    loop {
+        let request = {
+            let mut state = lock(&shared.state);
+            loop {
+                if let Some(request) = state.pop() {
+                    break request;
+                }
+                state = shared
+                    .available
+                    .wait(state)
+                    .unwrap_or_else(|e| e.into_inner());
+            }
+        };
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let source = icon_cache::resolve_icon_source(
+                &app_handle,
+                request.path.clone(),
+                request.resolve_shortcut,
+                request.resolve_links,
+            );
+            let transform = request.transform.clone().unwrap_or_default();
+            icon_cache::get_source_url(&app_handle, source, request.size, &transform)
+        }))
+        .unwrap_or_else(|payload| Err(background_service::panic_message(payload.as_ref())));
+
+        lock(&shared.state).in_flight.remove(&request.key);
+
+        let (url, error) = match result {
+            Ok(url) => (Some(url), None),
+            Err(e) => (None, Some(e)),
+        };
+        let _ = app_handle.emit(
+            ICON_READY_EVENT,
+            IconReady {
+                key: request.key,
+                path: request.path,
+                url,
+                error,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(key: &str, priority: IconPriority) -> IconRequest {
+        IconRequest {
+            key: key.to_string(),
+            path: format!("/apps/{}", key),
+            resolve_shortcut: false,
+            resolve_links: false,
+            size: None,
+            transform: None,
+            priority,
+        }
+    }
+
+    fn drain(state: &mut QueueState) -> Vec<String> {
+        std::iter::from_fn(|| state.pop().map(|request| request.key)).collect()
+    }
+
+    #[test]
+    fn deduplicates_pending_keys() {
+        let mut state = QueueState::default();
+        assert!(state.add(request("a", IconPriority::Rest), "root"));
+        assert!(!state.add(request("a", IconPriority::Rest), "root"));
+        assert!(!state.add(request("a", IconPriority::Rest), "other"));
+
+        assert_eq!(drain(&mut state), ["a"]);
+    }
+
+    #[test]
+    fn orders_by_priority_then_registration() {
+        let mut state = QueueState::default();
+        state.add(request("rest-1", IconPriority::Rest), "root");
+        state.add(request("neighbor-1", IconPriority::Neighbor), "root");
+        state.add(request("visible-1", IconPriority::Visible), "root");
+        state.add(request("rest-2", IconPriority::Rest), "root");
+        state.add(request("visible-2", IconPriority::Visible), "root");
+        state.add(request("neighbor-2", IconPriority::Neighbor), "root");
+
+        assert_eq!(
+            drain(&mut state),
+            [
+                "visible-1",
+                "visible-2",
+                "neighbor-1",
+                "neighbor-2",
+                "rest-1",
+                "rest-2"
+            ]
+        );
+    }
+
+    #[test]
+    fn priority_bump_replaces_the_stale_entry() {
+        let mut state = QueueState::default();
+        state.add(request("a", IconPriority::Rest), "root");
+        state.add(request("b", IconPriority::Neighbor), "root");
+        state.add(request("a", IconPriority::Visible), "root");
+        // 優先度を下げる要求は無視する
+        state.add(request("b", IconPriority::Rest), "root");
+
+        // 古い Rest のエントリは読み飛ばされ、"a" は一度だけ取り出される
+        assert_eq!(state.queue.len(), 3);
+        let first = state.pop().unwrap();
+        assert_eq!(first.key, "a");
+        assert_eq!(first.priority, IconPriority::Visible);
+        assert_eq!(drain(&mut state), ["b"]);
+        assert!(state.queue.is_empty());
+    }
+
+    #[test]
+    fn skips_keys_in_flight() {
+        let mut state = QueueState::default();
+        state.add(request("a", IconPriority::Visible), "root");
+        assert_eq!(state.pop().unwrap().key, "a");
+        assert!(state.in_flight.contains("a"));
+
+        assert!(!state.add(request("a", IconPriority::Visible), "root"));
+        assert!(state.pop().is_none());
+
+        state.in_flight.remove("a");
+        assert!(state.add(request("a", IconPriority::Visible), "root"));
+    }
+
+    #[test]
+    fn group_cancel_keeps_keys_of_other_groups() {
+        let mut state = QueueState::default();
+        state.add(request("shared", IconPriority::Rest), "group-1");
+        state.add(request("shared", IconPriority::Rest), "group-2");
+        state.add(request("only-1", IconPriority::Rest), "group-1");
+
+        assert_eq!(state.cancel(Some("group-1")), ["only-1"]);
+        assert_eq!(drain(&mut state), ["shared"]);
+    }
+
+    #[test]
+    fn cancel_all_clears_the_queue() {
+        let mut state = QueueState::default();
+        state.add(request("a", IconPriority::Visible), "group-1");
+        state.add(request("b", IconPriority::Rest), "group-2");
+
+        let mut cancelled = state.cancel(None);
+        cancelled.sort();
+        assert_eq!(cancelled, ["a", "b"]);
+        assert!(state.pending.is_empty());
+        assert!(state.queue.is_empty());
+        assert!(state.pop().is_none());
+    }
+}
@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+use tauri::http::{header, Request, Response, StatusCode};
+
+use crate::custom_icon;
+use crate::icon_cache;
+
+/// アイコン配信用のカスタムプロトコルのスキーム名
+pub const SCHEME: &str = "hexicon";
+
+/// インポート済みのカスタムアイコンを配信するパスの接頭辞（`custom-<hash>-<size>.png`）
+const CUSTOM_ICON_PREFIX: &str = "custom-";
+
+/// キャッシュ済みアイコンのURLを返します。
+///
+/// Windows (WebView2) ではカスタムプロトコルが `http://<scheme>.localhost/` 形式で公開されるため、
+/// プラットフォームに応じて形式を切り替えます。
+/// `version` は抽出元が変更された際にWebViewのキャッシュを無効化するためのクエリです。
+pub fn icon_url(hash: &str, version: &str) -> String {
+    if cfg!(any(target_os = "windows", target_os = "android")) {
+        format!("http://{}.localhost/{}.png?v={}", SCHEME, hash, version)
+    } else {
+        format!("{}://localhost/{}.png?v={}", SCHEME, hash, version)
+    }
+}
+
+/// `hexicon://localhost/<hash>.png` へのリクエストに、ディスクキャッシュのPNGを返します。
+///
+/// `hexicon://localhost/custom-<hash>-<size>.png` へのリクエストには、インポート済みのカスタムアイコンを返します。
+///
+/// ディスクキャッシュは上限サイズによって削除されることがあるため、キャッシュファイルが失われている場合は
+/// URLを渡した際の抽出方法（`icon_cache::regenerate`）で再抽出してから返します。
+/// 同じ理由でWebViewには長期間のキャッシュを許可せず、`ETag` による再検証を求めます。
+/// 再抽出できない場合（このセッションで渡したURLでない場合など）は 404 を返します。
+///
+/// 再抽出はアイコンの抽出を伴うため、メインスレッド以外から呼び出してください。
+pub fn handle(app_handle: &tauri::AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let file_name = request.uri().path().trim_start_matches('/');
+    let mut cache_file = match resolve_file(app_handle, file_name) {
+        Ok(path) => path,
+        Err(status) => return status_response(status),
+    };
+    let name = file_name.strip_suffix(".png").unwrap_or(file_name);
+
+    if !cache_file.exists() && !name.starts_with(CUSTOM_ICON_PREFIX) {
+        match icon_cache::regenerate(app_handle, name) {
+            Some(path) => cache_file = path,
+            None => return status_response(StatusCode::NOT_FOUND),
+        }
+    }
+
+    let Ok(metadata) = fs::metadata(&cache_file) else {
+        return status_response(StatusCode::NOT_FOUND);
+    };
+
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let etag = format!("\"{}-{:x}-{:x}\"", name, metadata.len(), modified);
+
+    let not_modified = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| tag.trim() == etag));
+
+    let builder = Response::builder()
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::ETAG, &etag)
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+
+    if not_modified {
+        return builder
+            .status(StatusCode::NOT_MODIFIED)
+            .body(Vec::new())
+            .unwrap_or_else(|_| status_response(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+
+    match fs::read(&cache_file) {
+        Ok(data) => builder
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "image/png")
+            .body(data)
+            .unwrap_or_else(|_| status_response(StatusCode::INTERNAL_SERVER_ERROR)),
+        Err(_) => status_response(StatusCode::NOT_FOUND),
+    }
+}
+
+/// リクエストされたファイル名から、配信するファイルのパスを返します。
+fn resolve_file(app_handle: &tauri::AppHandle, file_name: &str) -> Result<PathBuf, StatusCode> {
+    let name = file_name.strip_suffix(".png").unwrap_or(file_name);
+
+    if let Some(custom) = name.strip_prefix(CUSTOM_ICON_PREFIX) {
+        let (hash, size) = custom.rsplit_once('-').ok_or(StatusCode::BAD_REQUEST)?;
+        // パストラバーサル防止のため、ハッシュ(16進数)とサイズ(数値)以外は受け付けない
+        let size: u32 = size.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        let dir = custom_icon::custom_icon_dir(app_handle)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        return Ok(dir.join(custom_icon::icon_file_name(hash, size)));
+    }
+
+    // パストラバーサル防止のため、ハッシュ(16進数)以外は受け付けない
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let cache_dir =
+        icon_cache::icon_cache_dir(app_handle).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(cache_dir.join(format!("{}.png", name)))
+}
+
+fn status_response(status: StatusCode) -> Response<Vec<u8>> {
+    let mut response = Response::new(Vec::new());
+    *response.status_mut() = status;
+    response
+}
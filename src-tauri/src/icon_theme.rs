@@ -0,0 +1,595 @@
+use image::{ImageOutputFormat, RgbaImage};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::desktop_entry::{self, DesktopEntry};
+use crate::svg_render;
+
+/// アイコンファイルとして探索する拡張子（優先順）
+const ICON_EXTENSIONS: [&str; 3] = ["png", "svg", "xpm"];
+
+/// XPM 画像として受け付ける最大の幅・高さ（不正なヘッダーで巨大な画像を確保しないため）
+const MAX_XPM_SIZE: usize = 1024;
+/// XPM の色テーブルとして受け付ける最大の色数
+const MAX_XPM_COLORS: usize = 65536;
+/// XPM の1ピクセルあたりの最大の文字数
+const MAX_XPM_CHARS_PER_PIXEL: usize = 8;
+
+/// すべてのテーマの最後にフォールバックとして探索するテーマ
+const FALLBACK_THEME: &str = "hicolor";
+
+/// 現在のテーマと、その `Inherits` を辿ったテーマの一覧（探索順）。初回の探索時に読み込みます。
+static THEME_CHAIN: OnceLock<Vec<Theme>> = OnceLock::new();
+
+/// `index.theme` のディレクトリの種類
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DirType {
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+/// テーマのサブディレクトリ（`48x48/apps` など）の情報。
+#[derive(Debug)]
+struct ThemeDir {
+    /// テーマディレクトリからの相対パス
+    path: String,
+    size: u32,
+    scale: u32,
+    kind: DirType,
+    min_size: u32,
+    max_size: u32,
+    threshold: u32,
+    /// このサブディレクトリが実在するテーマディレクトリの一覧
+    roots: Vec<PathBuf>,
+}
+
+impl ThemeDir {
+    /// Icon Theme Specification の `DirectoryMatchesSize` に相当します。
+    fn matches_size(&self, size: u32, scale: u32) -> bool {
+        if self.scale != scale {
+            return false;
+        }
+        match self.kind {
+            DirType::Fixed => self.size == size,
+            DirType::Scalable => self.min_size <= size && size <= self.max_size,
+            DirType::Threshold => {
+                self.size.saturating_sub(self.threshold) <= size
+                    && size <= self.size.saturating_add(self.threshold)
+            }
+        }
+    }
+
+    /// Icon Theme Specification の `DirectorySizeDistance` に相当します。
+    fn size_distance(&self, size: u32, scale: u32) -> u32 {
+        let requested = size.saturating_mul(scale);
+        let (min, max) = match self.kind {
+            DirType::Fixed => (self.size, self.size),
+            DirType::Scalable => (self.min_size, self.max_size),
+            DirType::Threshold => (
+                self.size.saturating_sub(self.threshold),
+                self.size.saturating_add(self.threshold),
+            ),
+        };
+        let (min, max) = (
+            min.saturating_mul(self.scale),
+            max.saturating_mul(self.scale),
+        );
+        if requested < min {
+            min - requested
+        } else {
+            requested.saturating_sub(max)
+        }
+    }
+}
+
+/// 読み込んだアイコンテーマ。
+#[derive(Debug)]
+struct Theme {
+    inherits: Vec<String>,
+    dirs: Vec<ThemeDir>,
+}
+
+impl Theme {
+    /// 各ベースディレクトリから `<name>/index.theme` を探して読み込みます。
+    ///
+    /// 同名のテーマが複数のベースディレクトリにある場合、`index.theme` は最初に見つかったものを使い、
+    /// アイコンはすべてのベースディレクトリから探索します。
+    fn load(name: &str, base_dirs: &[PathBuf]) -> Option<Self> {
+        let roots: Vec<PathBuf> = base_dirs
+            .iter()
+            .map(|base| base.join(name))
+            .filter(|root| root.is_dir())
+            .collect();
+        let entry = roots
+            .iter()
+            .find_map(|root| DesktopEntry::load(&root.join("index.theme")).ok())?;
+
+        let inherits = entry
+            .get("Icon Theme", "Inherits")
+            .map(split_comma_list)
+            .unwrap_or_default();
+
+        let mut dir_names = entry
+            .get("Icon Theme", "Directories")
+            .map(split_comma_list)
+            .unwrap_or_default();
+        dir_names.extend(
+            entry
+                .get("Icon Theme", "ScaledDirectories")
+                .map(split_comma_list)
+                .unwrap_or_default(),
+        );
+
+        let mut seen = HashSet::new();
+        let dirs = dir_names
+            .into_iter()
+            .filter(|dir| seen.insert(dir.clone()))
+            .filter_map(|dir| {
+                let number = |key: &str| entry.get(&dir, key).and_then(|v| v.parse::<u32>().ok());
+                let size = number("Size")?;
+                let kind = match entry.get(&dir, "Type") {
+                    Some("Fixed") => DirType::Fixed,
+                    Some("Scalable") => DirType::Scalable,
+                    _ => DirType::Threshold,
+                };
+                let dir_roots: Vec<PathBuf> = roots
+                    .iter()
+                    .filter(|root| root.join(&dir).is_dir())
+                    .cloned()
+                    .collect();
+                if dir_roots.is_empty() {
+                    return None;
+                }
+
+                Some(ThemeDir {
+                    size,
+                    scale: number("Scale").unwrap_or(1),
+                    kind,
+                    min_size: number("MinSize").unwrap_or(size),
+                    max_size: number("MaxSize").unwrap_or(size),
+                    threshold: number("Threshold").unwrap_or(2),
+                    roots: dir_roots,
+                    path: dir,
+                })
+            })
+            .collect();
+
+        Some(Self { inherits, dirs })
+    }
+
+    /// Icon Theme Specification の `LookupIcon` に相当します。
+    ///
+    /// サイズが一致するディレクトリを優先し、なければサイズが最も近いディレクトリのアイコンを返します。
+    fn lookup(&self, name: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        for dir in self.dirs.iter().filter(|d| d.matches_size(size, scale)) {
+            if let Some(path) = find_icon_file(&dir.roots, &dir.path, name) {
+                return Some(path);
+            }
+        }
+
+        let mut best: Option<(u32, PathBuf)> = None;
+        for dir in &self.dirs {
+            let distance = dir.size_distance(size, scale);
+            if best.as_ref().is_some_and(|(d, _)| distance >= *d) {
+                continue;
+            }
+            if let Some(path) = find_icon_file(&dir.roots, &dir.path, name) {
+                best = Some((distance, path));
+            }
+        }
+        best.map(|(_, path)| path)
+    }
+}
+
+/// テーマ名からアイコンファイルを探します。
+///
+/// 現在のテーマ、その `Inherits` の連鎖、`hicolor` の順に探索し、見つからなければ
+/// ベースディレクトリ直下（`/usr/share/pixmaps` など）を探します。
+/// それでも見つからない場合は、`-` で区切られた末尾を取り除いた名前
+/// （`text-x-python` → `text-x` → `text`）で再度探索します。
+pub fn lookup_icon(name: &str, size: u32) -> Option<PathBuf> {
+    let themes = THEME_CHAIN.get_or_init(load_theme_chain);
+    let base_dirs = base_dirs();
+
+    let mut candidate = name;
+    loop {
+        let found = themes
+            .iter()
+            .find_map(|theme| theme.lookup(candidate, size, 1))
+            .or_else(|| {
+                base_dirs
+                    .iter()
+                    .find_map(|base| find_icon_file(std::slice::from_ref(base), "", candidate))
+            });
+        if found.is_some() {
+            return found;
+        }
+
+        match candidate.rsplit_once('-') {
+            Some((rest, _)) if !rest.is_empty() => candidate = rest,
+            _ => return None,
+        }
+    }
+}
+
+/// テーマ名からアイコンを探し、PNGとして読み込みます。
+pub fn icon_png(name: &str, size: u32) -> Result<Vec<u8>, String> {
+    // `Icon=firefox.png` のように拡張子付きで指定されている場合も受け付ける
+    let name = ICON_EXTENSIONS
+        .iter()
+        .find_map(|ext| name.strip_suffix(&format!(".{}", ext)))
+        .unwrap_or(name);
+
+    let path = lookup_icon(name, size)
+        .ok_or_else(|| format!("Icon '{}' was not found in the icon theme", name))?;
+    load_icon_png(&path, size)
+}
+
+/// アイコンファイル（PNG / XPM / SVG）を読み込み、PNGとして返します。
+///
+/// SVGは `size` ピクセル四方にCPUでラスタライズします。PNG・XPMは元のサイズのまま返します
+/// （要求サイズへの縮小は呼び出し側で行います）。
+pub fn load_icon_png(path: &Path, size: u32) -> Result<Vec<u8>, String> {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+
+    match extension.as_str() {
+        "svg" | "svgz" => svg_render::render_svg(&data, size),
+        "xpm" => encode_png(parse_xpm(&String::from_utf8_lossy(&data))?),
+        _ => {
+            let image = image::load_from_memory(&data).map_err(|e| e.to_string())?;
+            encode_png(image.to_rgba8())
+        }
+    }
+}
+
+/// 現在のテーマから `Inherits` を深さ優先で辿ったテーマの一覧を返します（`hicolor` は常に最後）。
+fn load_theme_chain() -> Vec<Theme> {
+    let base_dirs = base_dirs();
+    let mut chain = Vec::new();
+    let mut visited = HashSet::new();
+    // hicolor は連鎖の途中で Inherits に現れても最後に探索する
+    visited.insert(FALLBACK_THEME.to_string());
+
+    let mut stack = vec![current_theme_name()];
+    while let Some(name) = stack.pop() {
+        if !visited.insert(name.clone()) {
+            continue;
+        }
+        if let Some(theme) = Theme::load(&name, &base_dirs) {
+            stack.extend(theme.inherits.iter().rev().cloned());
+            chain.push(theme);
+        }
+    }
+
+    if let Some(hicolor) = Theme::load(FALLBACK_THEME, &base_dirs) {
+        chain.push(hicolor);
+    }
+    chain
+}
+
+/// 現在のアイコンテーマ名を返します。
+///
+/// GTK（`gtk-3.0` / `gtk-4.0` の `settings.ini`）、KDE（`kdeglobals`）の設定を順に確認し、
+/// どれにもなければ `hicolor` とします。
+fn current_theme_name() -> String {
+    let Some(config_dir) = config_dir() else {
+        return FALLBACK_THEME.to_string();
+    };
+
+    let candidates = [
+        ("gtk-3.0/settings.ini", "Settings", "gtk-icon-theme-name"),
+        ("gtk-4.0/settings.ini", "Settings", "gtk-icon-theme-name"),
+        ("kdeglobals", "Icons", "Theme"),
+    ];
+    candidates
+        .iter()
+        .find_map(|(file, group, key)| {
+            let entry = DesktopEntry::load(&config_dir.join(file)).ok()?;
+            let name = entry.get(group, key)?.trim_matches('"').trim();
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .unwrap_or_else(|| FALLBACK_THEME.to_string())
+}
+
+/// `$XDG_CONFIG_HOME`（未設定の場合は `~/.config`）を返します。
+fn config_dir() -> Option<PathBuf> {
+    match std::env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => std::env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".config")),
+    }
+}
+
+/// アイコンを探索するベースディレクトリ（`~/.icons`, `$XDG_DATA_DIRS/icons`, `/usr/share/pixmaps`）を
+/// 優先順に返します。
+fn base_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(home) = std::env::var("HOME") {
+        dirs.push(PathBuf::from(home).join(".icons"));
+    }
+    // application_dirs() は $XDG_DATA_HOME と $XDG_DATA_DIRS の `applications` を返すため、親を差し替える
+    dirs.extend(
+        desktop_entry::application_dirs()
+            .iter()
+            .filter_map(|dir| dir.parent())
+            .map(|dir| dir.join("icons")),
+    );
+    dirs.push(PathBuf::from("/usr/share/pixmaps"));
+    dirs
+}
+
+/// `<root>/<subdir>/<name>.{png,svg,xpm}` のうち最初に見つかったファイルを返します。
+fn find_icon_file(roots: &[PathBuf], subdir: &str, name: &str) -> Option<PathBuf> {
+    roots.iter().find_map(|root| {
+        let dir = root.join(subdir);
+        ICON_EXTENSIONS
+            .iter()
+            .map(|ext| dir.join(format!("{}.{}", name, ext)))
+            .find(|path| path.is_file())
+    })
+}
+
+/// `index.theme` のカンマ区切りのリストを分割します。
+fn split_comma_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// XPM 形式の画像をパースします。
+///
+/// 色の指定は `c`（カラー）を優先し、`None` は透明として扱います。
+/// 色名は主要なものにのみ対応し、未知の色名は黒とします。
+fn parse_xpm(content: &str) -> Result<RgbaImage, String> {
+    let mut lines = quoted_strings(content).into_iter();
+    let header = lines.next().ok_or("XPM header is missing")?;
+    let values: Vec<usize> = header
+        .split_whitespace()
+        .take(4)
+        .map(|v| v.parse().map_err(|_| "Invalid XPM header".to_string()))
+        .collect::<Result<_, _>>()?;
+    let [width, height, colors, chars_per_pixel] = values[..] else {
+        return Err("Invalid XPM header".to_string());
+    };
+    if width == 0 || height == 0 || chars_per_pixel == 0 {
+        return Err("Invalid XPM header".to_string());
+    }
+    if width > MAX_XPM_SIZE
+        || height > MAX_XPM_SIZE
+        || colors > MAX_XPM_COLORS
+        || chars_per_pixel > MAX_XPM_CHARS_PER_PIXEL
+    {
+        return Err(format!(
+            "XPM image is too large: {}x{}, {} colors, {} chars per pixel",
+            width, height, colors, chars_per_pixel
+        ));
+    }
+
+    let mut palette = std::collections::HashMap::new();
+    for _ in 0..colors {
+        let line = lines.next().ok_or("XPM color table is truncated")?;
+        let key = line
+            .get(..chars_per_pixel)
+            .ok_or("Invalid XPM color entry")?;
+        palette.insert(key.to_string(), parse_xpm_color(&line[chars_per_pixel..]));
+    }
+
+    let mut image = RgbaImage::new(width as u32, height as u32);
+    for y in 0..height {
+        let row = lines.next().ok_or("XPM pixel data is truncated")?;
+        for x in 0..width {
+            let key = row
+                .get(x * chars_per_pixel..(x + 1) * chars_per_pixel)
+                .ok_or("XPM pixel row is too short")?;
+            let color = palette.get(key).copied().unwrap_or([0, 0, 0, 0]);
+            image.put_pixel(x as u32, y as u32, image::Rgba(color));
+        }
+    }
+    Ok(image)
+}
+
+/// XPM ファイル中のダブルクォートで囲まれた文字列を順に取り出します。
+fn quoted_strings(content: &str) -> Vec<&str> {
+    let mut strings = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find('"') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('"') else {
+            break;
+        };
+        strings.push(&after[..end]);
+        rest = &after[end + 1..];
+    }
+    strings
+}
+
+/// XPM の色指定（`c #RRGGBB m black` など）から RGBA を求めます。
+fn parse_xpm_color(spec: &str) -> [u8; 4] {
+    let tokens: Vec<&str> = spec.split_whitespace().collect();
+    // コンテキスト（c, m, g, g4, s）と色の組。色名に空白を含む場合は次のコンテキストまでを連結する
+    let mut contexts: Vec<(&str, String)> = Vec::new();
+    for token in tokens {
+        match (token, contexts.last_mut()) {
+            ("c" | "m" | "g" | "g4" | "s", _) => contexts.push((token, String::new())),
+            (_, Some((_, color))) => {
+                if !color.is_empty() {
+                    color.push(' ');
+                }
+                color.push_str(token);
+            }
+            (_, None) => {}
+        }
+    }
+
+    let color = ["c", "g", "g4", "m"]
+        .iter()
+        .find_map(|key| contexts.iter().find(|(k, _)| k == key))
+        .map(|(_, color)| color.as_str())
+        .unwrap_or("black");
+    parse_color(color).unwrap_or([0, 0, 0, 255])
+}
+
+/// `#RGB` / `#RRGGBB` / `#RRRGGGBBB` / `#RRRRGGGGBBBB` 形式、または主要な色名を RGBA に変換します。
+fn parse_color(color: &str) -> Option<[u8; 4]> {
+    if color.eq_ignore_ascii_case("none") {
+        return Some([0, 0, 0, 0]);
+    }
+
+    if let Some(hex) = color.strip_prefix('#') {
+        // X11 の色指定は1チャンネルあたり最大4桁
+        let digits = hex.len() / 3;
+        if !(1..=4).contains(&digits) || hex.len() % 3 != 0 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| {
+            let value = u32::from_str_radix(&hex[i * digits..(i + 1) * digits], 16).ok()?;
+            let max = (1u32 << (digits * 4)) - 1;
+            Some((value * 255 / max) as u8)
+        };
+        return Some([channel(0)?, channel(1)?, channel(2)?, 255]);
+    }
+
+    let rgb = match color.to_lowercase().replace(' ', "").as_str() {
+        "black" => [0, 0, 0],
+        "white" => [255, 255, 255],
+        "red" => [255, 0, 0],
+        "green" => [0, 255, 0],
+        "blue" => [0, 0, 255],
+        "yellow" => [255, 255, 0],
+        "cyan" => [0, 255, 255],
+        "magenta" => [255, 0, 255],
+        "gray" | "grey" => [190, 190, 190],
+        "darkgray" | "darkgrey" => [169, 169, 169],
+        "lightgray" | "lightgrey" => [211, 211, 211],
+        _ => return None,
+    };
+    Some([rgb[0], rgb[1], rgb[2], 255])
+}
+
+fn encode_png(image: RgbaImage) -> Result<Vec<u8>, String> {
+    let mut png_data = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(&mut Cursor::new(&mut png_data), ImageOutputFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(png_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dir(kind: DirType, size: u32, min_size: u32, max_size: u32, threshold: u32) -> ThemeDir {
+        ThemeDir {
+            path: format!("{}x{}/apps", size, size),
+            size,
+            scale: 1,
+            kind,
+            min_size,
+            max_size,
+            threshold,
+            roots: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn matches_size_by_directory_type() {
+        let fixed = dir(DirType::Fixed, 48, 48, 48, 2);
+        assert!(fixed.matches_size(48, 1));
+        assert!(!fixed.matches_size(47, 1));
+        assert!(!fixed.matches_size(48, 2));
+
+        let scalable = dir(DirType::Scalable, 48, 16, 256, 2);
+        assert!(scalable.matches_size(16, 1));
+        assert!(scalable.matches_size(256, 1));
+        assert!(!scalable.matches_size(512, 1));
+
+        let threshold = dir(DirType::Threshold, 48, 48, 48, 2);
+        assert!(threshold.matches_size(46, 1));
+        assert!(threshold.matches_size(50, 1));
+        assert!(!threshold.matches_size(51, 1));
+
+        let huge = dir(DirType::Threshold, u32::MAX, 0, 0, 2);
+        assert!(huge.matches_size(u32::MAX, 1));
+    }
+
+    #[test]
+    fn measures_size_distance() {
+        let fixed = dir(DirType::Fixed, 48, 48, 48, 2);
+        assert_eq!(fixed.size_distance(48, 1), 0);
+        assert_eq!(fixed.size_distance(32, 1), 16);
+        assert_eq!(fixed.size_distance(64, 1), 16);
+
+        let scalable = dir(DirType::Scalable, 48, 16, 256, 2);
+        assert_eq!(scalable.size_distance(8, 1), 8);
+        assert_eq!(scalable.size_distance(128, 1), 0);
+        assert_eq!(scalable.size_distance(300, 1), 44);
+
+        let mut hidpi = dir(DirType::Fixed, 32, 32, 32, 2);
+        hidpi.scale = 2;
+        assert_eq!(hidpi.size_distance(32, 2), 0);
+        assert_eq!(hidpi.size_distance(48, 1), 16);
+
+        // 不正な値でもオーバーフローしない
+        let huge = dir(DirType::Threshold, u32::MAX, 0, 0, u32::MAX);
+        assert_eq!(huge.size_distance(u32::MAX, u32::MAX), 0);
+    }
+
+    #[test]
+    fn parses_color_formats() {
+        assert_eq!(parse_color("#f00"), Some([255, 0, 0, 255]));
+        assert_eq!(parse_color("#00FF80"), Some([0, 255, 128, 255]));
+        assert_eq!(parse_color("#fff000000"), Some([255, 0, 0, 255]));
+        assert_eq!(parse_color("#ffff00000000"), Some([255, 0, 0, 255]));
+        assert_eq!(parse_color("None"), Some([0, 0, 0, 0]));
+        assert_eq!(parse_color("light grey"), Some([211, 211, 211, 255]));
+        assert_eq!(parse_color("unknown"), None);
+        assert_eq!(parse_color("#ff"), None);
+        assert_eq!(parse_color("#ggg"), None);
+        // 1チャンネル5桁以上は受け付けない
+        assert_eq!(parse_color("#fffffffffffffff"), None);
+        assert_eq!(parse_color("#ffffffffffffffffffffffff"), None);
+    }
+
+    #[test]
+    fn parses_xpm_image() {
+        let xpm = r#"/* XPM */
+static char *icon[] = {
+"2 2 3 1",
+"  c None",
+"r c #ff0000",
+"b c blue m black",
+"r ",
+" b"
+};"#;
+        let image = parse_xpm(xpm).unwrap();
+        assert_eq!(image.dimensions(), (2, 2));
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(1, 0).0, [0, 0, 0, 0]);
+        assert_eq!(image.get_pixel(1, 1).0, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn rejects_invalid_xpm() {
+        assert!(parse_xpm("").is_err());
+        assert!(parse_xpm(r#""0 2 1 1" "a c red""#).is_err());
+        // 画像データが不足している
+        assert!(parse_xpm(r#""2 2 1 1" "a c red" "aa""#).is_err());
+        assert!(parse_xpm(r#""2 1 1 1" "a c red" "a""#).is_err());
+        // 確保する前に大きすぎるサイズを拒否する
+        assert!(parse_xpm(r#""100000 100000 1 1" "a c red""#).is_err());
+        assert!(parse_xpm(r#""1 1 1000000000 1" "a c red""#).is_err());
+        assert!(parse_xpm(r#""1 1 1 100" "a c red""#).is_err());
+    }
+}
@@ -0,0 +1,376 @@
+use image::imageops::{self, FilterType};
+use image::{ImageOutputFormat, Rgba, RgbaImage};
+use serde::Deserialize;
+
+/// 透明な余白とみなすアルファ値の上限
+const TRIM_ALPHA_THRESHOLD: u8 = 8;
+/// 六角形マスクの縁のアンチエイリアスに使う1辺あたりのサンプル数
+const MASK_SAMPLES: u32 = 4;
+/// ドロップシャドウの不透明度
+const SHADOW_OPACITY: f32 = 0.5;
+
+/// アイコンに適用する後処理。
+///
+/// フロントエンドから `get_file_icon` / `get_file_icon_url` に渡され、
+/// 抽出したアイコンに対して一度だけ適用された結果がキャッシュされます。
+/// 適用順は 余白の除去 → シルエット化・着色 → 六角形マスク → ドロップシャドウ です。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IconTransform {
+    /// 不透明な部分を単色（`tint` の色、未指定の場合は白）で塗りつぶしたシルエットにする
+    #[serde(default)]
+    pub silhouette: bool,
+    /// 着色する色（`#RRGGBB`）。シルエットでない場合は輝度を保ったままこの色に染めます
+    #[serde(default)]
+    pub tint: Option<String>,
+    /// 六角形（セルと同じ、頂点が上下にある向き）のマスクで切り抜く
+    #[serde(default)]
+    pub hex_mask: bool,
+    /// ドロップシャドウを付ける
+    #[serde(default)]
+    pub shadow: bool,
+    /// 透明な余白を取り除き、画像いっぱいに拡大して中央に配置する
+    #[serde(default)]
+    pub trim: bool,
+}
+
+impl IconTransform {
+    /// 何も変更しない（すべての後処理が無効な）場合に `true` を返します。
+    pub fn is_identity(&self) -> bool {
+        !self.silhouette && self.tint.is_none() && !self.hex_mask && !self.shadow && !self.trim
+    }
+
+    /// 着色する色を返します。色の指定が不正な場合はエラーを返します。
+    fn tint_color(&self) -> Result<Option<[u8; 3]>, String> {
+        self.tint
+            .as_ref()
+            .map(|color| parse_hex_color(color).ok_or(format!("Invalid tint color: {}", color)))
+            .transpose()
+    }
+
+    /// キャッシュキー・抽出パラメータに含める、後処理の正規化された表現を返します。
+    ///
+    /// 後処理がない場合は空文字列を返します。`apply` と同じく、色の指定が不正な場合はエラーを返します。
+    pub fn cache_params(&self) -> Result<String, String> {
+        let mut params = Vec::new();
+        if self.trim {
+            params.push("trim".to_string());
+        }
+        if self.silhouette {
+            params.push("silhouette".to_string());
+        }
+        if let Some(color) = self.tint_color()? {
+            params.push(format!(
+                "tint={:02x}{:02x}{:02x}",
+                color[0], color[1], color[2]
+            ));
+        }
+        if self.hex_mask {
+            params.push("hex".to_string());
+        }
+        if self.shadow {
+            params.push("shadow".to_string());
+        }
+        Ok(params.join(","))
+    }
+
+    /// PNGデータに後処理を適用し、同じサイズのPNGデータを返します。
+    pub fn apply(&self, png_data: &[u8]) -> Result<Vec<u8>, String> {
+        if self.is_identity() {
+            return Ok(png_data.to_vec());
+        }
+
+        let mut image = image::load_from_memory(png_data)
+            .map_err(|e| e.to_string())?
+            .to_rgba8();
+
+        if self.trim {
+            image = trim(&image);
+        }
+        let tint = self.tint_color()?;
+        if self.silhouette {
+            silhouette(&mut image, tint.unwrap_or([255, 255, 255]));
+        } else if let Some(color) = tint {
+            colorize(&mut image, color);
+        }
+        if self.hex_mask {
+            hex_mask(&mut image);
+        }
+        if self.shadow {
+            image = drop_shadow(&image);
+        }
+
+        let mut png_buffer = Vec::new();
+        image::DynamicImage::ImageRgba8(image)
+            .write_to(
+                &mut std::io::Cursor::new(&mut png_buffer),
+                ImageOutputFormat::Png,
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(png_buffer)
+    }
+}
+
+/// 透明な余白を取り除き、縦横比を保ったまま元の大きさいっぱいに拡大・縮小して中央に配置します。
+fn trim(image: &RgbaImage) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let opaque = |x: u32, y: u32| image.get_pixel(x, y)[3] > TRIM_ALPHA_THRESHOLD;
+
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for y in 0..height {
+        for x in 0..width {
+            if opaque(x, y) {
+                bounds = Some(match bounds {
+                    Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                    None => (x, y, x, y),
+                });
+            }
+        }
+    }
+
+    // 完全に透明、または余白がない場合はそのまま
+    let Some((x0, y0, x1, y1)) = bounds else {
+        return image.clone();
+    };
+    let (content_width, content_height) = (x1 - x0 + 1, y1 - y0 + 1);
+    if content_width == width && content_height == height {
+        return image.clone();
+    }
+
+    let content = imageops::crop_imm(image, x0, y0, content_width, content_height).to_image();
+    let scale = (width as f32 / content_width as f32).min(height as f32 / content_height as f32);
+    let scaled_width = ((content_width as f32 * scale).round() as u32).clamp(1, width);
+    let scaled_height = ((content_height as f32 * scale).round() as u32).clamp(1, height);
+    let scaled = imageops::resize(&content, scaled_width, scaled_height, FilterType::Lanczos3);
+
+    let mut result = RgbaImage::new(width, height);
+    imageops::overlay(
+        &mut result,
+        &scaled,
+        ((width - scaled_width) / 2) as i64,
+        ((height - scaled_height) / 2) as i64,
+    );
+    result
+}
+
+/// アルファを保ったまま、すべてのピクセルを単色で塗りつぶします。
+fn silhouette(image: &mut RgbaImage, color: [u8; 3]) {
+    for pixel in image.pixels_mut() {
+        pixel[0] = color[0];
+        pixel[1] = color[1];
+        pixel[2] = color[2];
+    }
+}
+
+/// 各ピクセルの輝度を保ったまま、指定色に染めます。
+fn colorize(image: &mut RgbaImage, color: [u8; 3]) {
+    for pixel in image.pixels_mut() {
+        let luminance =
+            (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32) / 255.0;
+        for channel in 0..3 {
+            pixel[channel] = (color[channel] as f32 * luminance).round() as u8;
+        }
+    }
+}
+
+/// 画像に内接する六角形（頂点が上下にある向き）の外側を透明にします。縁はアンチエイリアスします。
+fn hex_mask(image: &mut RgbaImage) {
+    let (width, height) = image.dimensions();
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    // 高さ方向は頂点まで、幅方向は辺までが画像に収まる外接円の半径
+    let radius = (height as f32 / 2.0).min(width as f32 / 3f32.sqrt());
+    let half_width = radius * 3f32.sqrt() / 2.0;
+
+    let inside = |x: f32, y: f32| {
+        let (dx, dy) = ((x - cx).abs(), (y - cy).abs());
+        dx <= half_width && dx * 0.5 + dy * 3f32.sqrt() / 2.0 <= half_width
+    };
+
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let mut covered = 0;
+        for sy in 0..MASK_SAMPLES {
+            for sx in 0..MASK_SAMPLES {
+                let px = x as f32 + (sx as f32 + 0.5) / MASK_SAMPLES as f32;
+                let py = y as f32 + (sy as f32 + 0.5) / MASK_SAMPLES as f32;
+                if inside(px, py) {
+                    covered += 1;
+                }
+            }
+        }
+        let coverage = covered as f32 / (MASK_SAMPLES * MASK_SAMPLES) as f32;
+        pixel[3] = (pixel[3] as f32 * coverage).round() as u8;
+    }
+}
+
+/// 右下にぼかした影を付けます。影が収まるよう、アイコンは少し縮小して配置します。
+fn drop_shadow(image: &RgbaImage) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let size = width.min(height) as f32;
+    let offset = (size / 32.0).ceil().max(1.0) as i64;
+    let sigma = (size / 24.0).max(0.5);
+
+    let inner_width = ((width as f32 * 0.875).round() as u32).max(1);
+    let inner_height = ((height as f32 * 0.875).round() as u32).max(1);
+    let icon = imageops::resize(image, inner_width, inner_height, FilterType::Lanczos3);
+    let x = ((width - inner_width) / 2) as i64 - offset / 2;
+    let y = ((height - inner_height) / 2) as i64 - offset / 2;
+
+    let mut shadow = RgbaImage::new(width, height);
+    let silhouette = RgbaImage::from_fn(inner_width, inner_height, |px, py| {
+        let alpha = icon.get_pixel(px, py)[3] as f32 * SHADOW_OPACITY;
+        Rgba([0, 0, 0, alpha.round() as u8])
+    });
+    imageops::overlay(&mut shadow, &silhouette, x + offset, y + offset);
+
+    let mut result = imageops::blur(&shadow, sigma);
+    imageops::overlay(&mut result, &icon, x, y);
+    result
+}
+
+/// `#RRGGBB` / `#RGB` 形式の色を RGB に変換します。
+fn parse_hex_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.trim().strip_prefix('#')?;
+    let value = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        6 if hex.is_ascii() => Some([value(&hex[0..2])?, value(&hex[2..4])?, value(&hex[4..6])?]),
+        3 if hex.is_ascii() => {
+            let expand = |i: usize| value(&hex[i..i + 1]).map(|v| v * 17);
+            Some([expand(0)?, expand(1)?, expand(2)?])
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(image: RgbaImage) -> Vec<u8> {
+        let mut data = Vec::new();
+        image::DynamicImage::ImageRgba8(image)
+            .write_to(&mut std::io::Cursor::new(&mut data), ImageOutputFormat::Png)
+            .unwrap();
+        data
+    }
+
+    /// 透明な画像の `(x, y)` から `size` 四方を不透明な赤で塗った画像
+    fn square_at(width: u32, height: u32, x: u32, y: u32, size: u32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |px, py| {
+            if (x..x + size).contains(&px) && (y..y + size).contains(&py) {
+                Rgba([255, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 0, 0])
+            }
+        })
+    }
+
+    /// 不透明なピクセルを囲む範囲 (x0, y0, x1, y1)
+    fn opaque_bounds(image: &RgbaImage) -> Option<(u32, u32, u32, u32)> {
+        image
+            .enumerate_pixels()
+            .filter(|(_, _, p)| p[3] > TRIM_ALPHA_THRESHOLD)
+            .fold(None, |bounds, (x, y, _)| {
+                Some(match bounds {
+                    Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                    None => (x, y, x, y),
+                })
+            })
+    }
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!(parse_hex_color("#06b6d4"), Some([0x06, 0xb6, 0xd4]));
+        assert_eq!(parse_hex_color(" #FFF "), Some([255, 255, 255]));
+        assert_eq!(parse_hex_color("#f80"), Some([0xff, 0x88, 0x00]));
+        assert_eq!(parse_hex_color("06b6d4"), None);
+        assert_eq!(parse_hex_color("#06b6d"), None);
+        assert_eq!(parse_hex_color("#ggg"), None);
+        assert_eq!(parse_hex_color("#ａｂｃ"), None);
+    }
+
+    #[test]
+    fn normalizes_cache_params() {
+        assert_eq!(IconTransform::default().cache_params().unwrap(), "");
+
+        let transform = IconTransform {
+            silhouette: true,
+            tint: Some("#F80".to_string()),
+            hex_mask: true,
+            shadow: true,
+            trim: true,
+        };
+        assert_eq!(
+            transform.cache_params().unwrap(),
+            "trim,silhouette,tint=ff8800,hex,shadow"
+        );
+
+        // 同じ色は表記によらず同じキーになる
+        let long = IconTransform {
+            tint: Some("#ff8800".to_string()),
+            ..Default::default()
+        };
+        let short = IconTransform {
+            tint: Some("#f80".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(long.cache_params(), short.cache_params());
+    }
+
+    #[test]
+    fn rejects_invalid_tint_in_cache_params_and_apply() {
+        let transform = IconTransform {
+            tint: Some("teal".to_string()),
+            ..Default::default()
+        };
+        assert!(transform.cache_params().is_err());
+        assert!(transform.apply(&png(square_at(4, 4, 0, 0, 4))).is_err());
+    }
+
+    #[test]
+    fn trims_transparent_margins() {
+        // 右下に寄った 4x4 の内容は、16x16 いっぱいに拡大される
+        let trimmed = trim(&square_at(16, 16, 10, 10, 4));
+        assert_eq!(trimmed.dimensions(), (16, 16));
+        assert_eq!(opaque_bounds(&trimmed), Some((0, 0, 15, 15)));
+
+        // 横長の内容は縦横比を保って上下中央に配置される
+        let trimmed = trim(&RgbaImage::from_fn(16, 16, |x, y| {
+            if (2..10).contains(&x) && (0..4).contains(&y) {
+                Rgba([255, 0, 0, 255])
+            } else {
+                Rgba([0, 0, 0, 0])
+            }
+        }));
+        let (x0, y0, x1, y1) = opaque_bounds(&trimmed).unwrap();
+        assert_eq!((x0, x1), (0, 15));
+        assert!(y0 >= 3 && y1 <= 12, "{:?}", (y0, y1));
+    }
+
+    #[test]
+    fn trim_keeps_images_without_margins() {
+        let transparent = RgbaImage::new(8, 8);
+        assert_eq!(trim(&transparent), transparent);
+        let full = square_at(8, 8, 0, 0, 8);
+        assert_eq!(trim(&full), full);
+    }
+
+    #[test]
+    fn hex_mask_clears_the_corners() {
+        let mut image = RgbaImage::from_pixel(32, 32, Rgba([255, 0, 0, 255]));
+        hex_mask(&mut image);
+
+        assert_eq!(image.get_pixel(16, 16)[3], 255);
+        // 上下の頂点と左右の辺は残り、四隅は透明になる
+        assert_eq!(image.get_pixel(16, 3)[3], 255);
+        assert_eq!(image.get_pixel(4, 16)[3], 255);
+        for (x, y) in [(0, 0), (31, 0), (0, 31), (31, 31)] {
+            assert_eq!(image.get_pixel(x, y)[3], 0, "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn identity_transform_returns_the_input() {
+        let data = png(square_at(4, 4, 1, 1, 2));
+        assert_eq!(IconTransform::default().apply(&data).unwrap(), data);
+    }
+}
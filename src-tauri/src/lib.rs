@@ -1,16 +1,45 @@
 use std::fs;
 use std::str::FromStr;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 use windows::Win32::UI::WindowsAndMessaging::GetCursorPos; // Needed for multi-monitor logic
 
+mod app_actions;
+mod app_index;
+mod background_service;
 mod backup_manager;
+mod cell_health;
+mod custom_icon;
+mod desktop_entry;
+mod edge_detector;
+#[cfg(target_os = "windows")]
+mod foreground_window;
+mod helper_process;
+mod ico_reader;
 mod icon_cache;
+mod icon_index;
+mod icon_maintenance;
+mod icon_memory_cache;
+mod icon_palette;
+mod icon_pixels;
+mod icon_prefetch;
+mod icon_protocol;
+#[cfg(not(target_os = "windows"))]
+mod icon_theme;
+mod icon_transform;
+mod mime_type;
 mod mouse_edge;
+mod path_utils;
+#[cfg(not(target_os = "windows"))]
+mod sandbox_apps;
 mod security;
 mod shortcut_utils;
 mod startup;
+mod svg_render;
 mod system_stats;
+mod target_repair;
+mod uwp_catalog;
+mod uwp_manifest;
 mod uwp_utils;
 mod window_behavior;
 
@@ -65,13 +94,22 @@ fn load_settings(app_handle: tauri::AppHandle) -> Result<String, String> {
 ///
 /// 実行ファイル(.exe, .bat, .cmd)の場合は、引数やカレントディレクトリを指定して起動を試みます。
 /// それ以外の場合（フォルダやショートカット）は、OSのデフォルト動作（explorerなど）を使用します。
+///
+/// `resolve_links` が有効な場合は、シンボリックリンク・ジャンクションを解決した実体パスで起動します。
 #[tauri::command]
 fn launch_app(
     _app_handle: tauri::AppHandle,
     path: String,
     args: Option<String>,
     working_dir: Option<String>,
+    resolve_links: Option<bool>,
 ) -> Result<(), String> {
+    let path = if resolve_links.unwrap_or(false) {
+        path_utils::real_path(&path)
+    } else {
+        path
+    };
+
     // 実行ファイルの場合は直接プロセス生成を試みる（引数や作業ディレクトリ対応のため）
     // 簡易的な判定: 拡張子が .exe, .bat, .cmd
     let lower_path = path.to_lowercase();
@@ -79,7 +117,7 @@ fn launch_app(
     {
         let mut cmd = std::process::Command::new(&path);
         if let Some(a) = args {
-            cmd.args(a.split_whitespace());
+            cmd.args(desktop_entry::split_args(&a));
         }
         if let Some(wd) = working_dir {
             cmd.current_dir(wd);
@@ -110,6 +148,28 @@ fn launch_app(
     Ok(())
 }
 
+/// パスのシンボリックリンク・ジャンクションを解決し、保存されたパスと実体パスの両方を返します。
+///
+/// フロントエンドは両方をセルに記録し、表示には保存されたパスを、アイコン取得や
+/// プロセスの照合には実体パスを使用します。
+#[tauri::command]
+fn normalize_path(path: String) -> path_utils::NormalizedPath {
+    path_utils::normalize(&path)
+}
+
+/// 指定したパスのうち、現在実行中のプロセスの実行ファイルと一致するものを返します。
+///
+/// プロセスの実行ファイルとの照合は、シンボリックリンク・ジャンクションを解決した実体パスで行います。
+///
+/// # 引数
+/// * `paths` - 照合するパスの一覧（セルに記録した実体パス）
+#[tauri::command]
+async fn get_running_targets(paths: Vec<String>) -> Result<Vec<String>, String> {
+    tauri::async_runtime::spawn_blocking(move || path_utils::running_targets(&paths))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
 /// ショートカットファイル(.lnk)のリンク先を解決します。
 ///
 /// # 引数
@@ -119,12 +179,60 @@ fn resolve_shortcut(path: String) -> Result<shortcut_utils::ShortcutInfo, String
     shortcut_utils::resolve_lnk(&path)
 }
 
+/// 複数のショートカットファイル(.lnk)をまとめて解決します。
+///
+/// ワーカースレッドで並列に処理し、1件解決するごとに `shortcut-resolve-progress` イベントを発行します。
+/// 個々の失敗は結果の `error` に格納され、全体の処理は中断されません。
+///
+/// # 引数
+/// * `paths` - .lnkファイルのパス一覧
+#[tauri::command]
+async fn resolve_shortcuts(
+    app_handle: tauri::AppHandle,
+    paths: Vec<String>,
+) -> Result<Vec<shortcut_utils::BatchResolveItem>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let total = paths.len();
+        shortcut_utils::resolve_lnk_batch(paths, |item, completed| {
+            let _ = app_handle.emit(
+                "shortcut-resolve-progress",
+                serde_json::json!({
+                    "completed": completed,
+                    "total": total,
+                    "item": item
+                }),
+            );
+        })
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
+
 /// インストールされているUWP(Universal Windows Platform)アプリの一覧を取得します。
 ///
-/// Powershellコマンドを使用して、AppxPackageマニフェストから情報を抽出します。
+/// ディスクに保存した一覧を返し、古い場合はバックグラウンドで取得しなおします
+/// （完了時に `uwp-apps-updated` イベントで通知します）。
 #[tauri::command]
-fn get_uwp_apps() -> Result<Vec<uwp_utils::UwpApp>, String> {
-    uwp_utils::get_installed_uwp_apps()
+async fn get_uwp_apps(app_handle: tauri::AppHandle) -> Result<Vec<uwp_utils::UwpApp>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app_handle
+            .state::<uwp_catalog::UwpCatalog>()
+            .apps(&app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// UWPアプリの一覧をPowershellコマンドで取得しなおし、保存します。
+#[tauri::command]
+async fn refresh_uwp_apps(app_handle: tauri::AppHandle) -> Result<Vec<uwp_utils::UwpApp>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app_handle
+            .state::<uwp_catalog::UwpCatalog>()
+            .refresh(&app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
 }
 
 /// 指定されたAUMID (Application User Model ID) を使用してUWPアプリを起動します。
@@ -136,44 +244,251 @@ fn launch_uwp_app(aumid: String) -> Result<(), String> {
 /// 指定されたファイルのアイコンを取得し、Base64エンコードされた画像データとして返します。
 ///
 /// パフォーマンス向上のため、2層キャッシュ（メモリ + ディスク）を使用します。
+/// `resolve_links` が有効な場合は、シンボリックリンク・ジャンクションを解決した実体パスからアイコンを抽出します。
+/// `size` を指定すると、そのピクセルサイズに最適なアイコンを抽出・縮小して返します。
+/// ドキュメントやフォルダーなど固有のアイコンを持たないファイルには、MIMEタイプごとの汎用アイコンを返します。
+/// `transform` を指定すると、シルエット化・着色・六角形マスクなどの後処理を適用した画像を返します。
 #[tauri::command]
 fn get_file_icon(
     app_handle: tauri::AppHandle,
     path: String,
     resolve_shortcut: bool,
+    resolve_links: Option<bool>,
+    size: Option<u32>,
+    transform: Option<icon_transform::IconTransform>,
 ) -> Result<String, String> {
-    if resolve_shortcut && path.to_lowercase().ends_with(".lnk") {
-        if let Ok(info) = shortcut_utils::resolve_lnk(&path) {
-            // ショートカットがアイコンリソース情報を指している場合はそれを使用
-            if !info.icon_path.is_empty() {
-                // カスタムハッシュキーを作成してキャッシュを分離する
-                // 例: "path/to/icon.dll:0"
-                let resource_key = format!("{}:{}", info.icon_path, info.icon_index);
-
-                // キャッシュ・取得ロジック (icon_cache::get_icon相当だが、extractの代わりにresource extractを使う)
-                return icon_cache::get_icon_by_resource(
-                    &app_handle,
-                    &resource_key,
-                    &info.icon_path,
-                    info.icon_index,
-                );
-            } else {
-                // アイコン指定がない場合はターゲットパスを使用 (従来のロジック)
-                if !info.target.is_empty() {
-                    return icon_cache::get_icon(&app_handle, info.target);
-                }
-            }
+    let transform = transform.unwrap_or_default();
+    match icon_cache::resolve_icon_source(
+        &app_handle,
+        path,
+        resolve_shortcut,
+        resolve_links.unwrap_or(false),
+    ) {
+        icon_cache::IconSource::File(path) => {
+            icon_cache::get_icon(&app_handle, path, size, &transform)
+        }
+        icon_cache::IconSource::Resource { key, path, index } => {
+            icon_cache::get_icon_by_resource(&app_handle, &key, &path, index, size, &transform)
+        }
+        icon_cache::IconSource::MimeType(mime) => {
+            icon_cache::get_icon_by_mime(&app_handle, &mime, size, &transform)
+        }
+        icon_cache::IconSource::FileType(extension) => {
+            icon_cache::get_icon_by_file_type(&app_handle, &extension, size, &transform)
         }
     }
-    // 通常のファイルパスまたは解決失敗時はそのまま
-    icon_cache::get_icon(&app_handle, path)
+}
+
+/// 指定されたファイルのアイコンを取得し、`hexicon` プロトコルのURLとして返します。
+///
+/// `get_file_icon` と異なり画像データをIPCで送らないため、多数のセルを表示する場合に使用します。
+/// 引数の意味は `get_file_icon` と同じです。
+#[tauri::command]
+fn get_file_icon_url(
+    app_handle: tauri::AppHandle,
+    path: String,
+    resolve_shortcut: bool,
+    resolve_links: Option<bool>,
+    size: Option<u32>,
+    transform: Option<icon_transform::IconTransform>,
+) -> Result<String, String> {
+    let source = icon_cache::resolve_icon_source(
+        &app_handle,
+        path,
+        resolve_shortcut,
+        resolve_links.unwrap_or(false),
+    );
+    icon_cache::get_source_url(&app_handle, source, size, &transform.unwrap_or_default())
+}
+
+/// 画像ファイルをカスタムアイコンとしてインポートします。
+///
+/// 画像（PNG / JPEG / ICO / SVG / WebP）を内容のハッシュでアプリのデータフォルダにコピーし、
+/// 正方形に切り抜いた各サイズのPNGを生成します。
+/// 返される参照（`custom:<hash>`）をセルの `customIcon` に保存すると、元のファイルが移動・削除されても表示できます。
+#[tauri::command]
+async fn import_custom_icon(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<custom_icon::ImportedIcon, String> {
+    tauri::async_runtime::spawn_blocking(move || custom_icon::import(&app_handle, &path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// アイコンの先読みを要求します。
+///
+/// 要求は優先度順（表示中 → 隣接 → その他）にワーカースレッドで処理され、
+/// 1件完了するごとに `icon-ready` イベントが発行されます。
+/// 待機中・処理中の要求と同じキーの要求は重複して処理しません。
+/// 新たにキューに追加した件数を返します。
+///
+/// # 引数
+/// * `requests` - 先読みするアイコンの要求
+/// * `group` - 要求を登録するグループ（表示中の画面）。`cancel_icon_prefetch` で取り消す単位です
+#[tauri::command]
+fn prefetch_icons(
+    app_handle: tauri::AppHandle,
+    requests: Vec<icon_prefetch::IconRequest>,
+    group: String,
+) -> Result<usize, String> {
+    let prefetcher = app_handle.state::<icon_prefetch::IconPrefetcher>();
+    Ok(prefetcher.enqueue(&app_handle, requests, &group))
+}
+
+/// グループに登録されたアイコンの先読みのうち、まだ開始していないものを取り消します。
+///
+/// 取り消した要求のキーを返します（これらの `icon-ready` イベントは発行されません）。
+///
+/// # 引数
+/// * `group` - 取り消すグループ。省略時は待機中のすべての要求を取り消します
+#[tauri::command]
+fn cancel_icon_prefetch(
+    app_handle: tauri::AppHandle,
+    group: Option<String>,
+) -> Result<Vec<String>, String> {
+    let prefetcher = app_handle.state::<icon_prefetch::IconPrefetcher>();
+    Ok(prefetcher.cancel(group.as_deref()))
+}
+
+/// 指定されたファイルのアイコンから配色（アクセントカラーと文字色）を抽出します。
+///
+/// セルの色をアイコンに合わせて自動設定するために使用します。
+/// 解析結果はアイコンのキャッシュファイルの隣に保存されます。
+/// `path` / `resolve_shortcut` / `resolve_links` の意味は `get_file_icon` と同じです。
+#[tauri::command]
+async fn get_icon_palette(
+    app_handle: tauri::AppHandle,
+    path: String,
+    resolve_shortcut: bool,
+    resolve_links: Option<bool>,
+) -> Result<icon_palette::IconPalette, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let source = icon_cache::resolve_icon_source(
+            &app_handle,
+            path,
+            resolve_shortcut,
+            resolve_links.unwrap_or(false),
+        );
+        icon_cache::get_icon_palette(&app_handle, source)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// アイコンキャッシュの統計情報を取得します。
+///
+/// ディスクキャッシュのエントリ数・合計サイズ・どのセルからも参照されていない孤立エントリと、
+/// メモリキャッシュのヒット・ミス回数を返します。
+/// ショートカットの解決を伴うため、バックグラウンドスレッドで実行します。
+#[tauri::command]
+async fn get_icon_cache_stats(
+    app_handle: tauri::AppHandle,
+) -> Result<icon_maintenance::IconCacheStats, String> {
+    tauri::async_runtime::spawn_blocking(move || icon_maintenance::stats(&app_handle))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// どのセルからも参照されていない孤立したアイコンキャッシュを削除します。
+#[tauri::command]
+async fn compact_icon_cache(
+    app_handle: tauri::AppHandle,
+) -> Result<icon_maintenance::CompactionResult, String> {
+    tauri::async_runtime::spawn_blocking(move || icon_maintenance::compact(&app_handle))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// アイコンのディスクキャッシュの上限サイズ（MB）を設定します。
+///
+/// 上限を超えている場合は、最も長く使われていないキャッシュから削除します。
+/// 削除したキャッシュファイルの数を返します。
+/// バイト数に換算して桁あふれする値は、表現できる最大値として扱います。
+#[tauri::command]
+fn set_icon_cache_limit(app_handle: tauri::AppHandle, max_mb: u64) -> Result<usize, String> {
+    icon_maintenance::set_limit(&app_handle, max_mb.saturating_mul(1024 * 1024))
+}
+
+/// 指定されたパスのアイコンキャッシュを破棄し、次回の取得時に再抽出させます。
+///
+/// 破棄したキャッシュファイルの数を返します。
+#[tauri::command]
+fn invalidate_icon(app_handle: tauri::AppHandle, path: String) -> Result<usize, String> {
+    icon_cache::invalidate(&app_handle, &path)
+}
+
+/// すべてのアイコンキャッシュを破棄します。
+///
+/// 破棄したキャッシュファイルの数を返します。
+#[tauri::command]
+fn invalidate_all_icons(app_handle: tauri::AppHandle) -> Result<usize, String> {
+    icon_cache::invalidate_all(&app_handle)
+}
+
+/// 指定されたターゲットが提供する追加アクション（ジャンプリストのタスクや
+/// `.desktop` の Desktop Action）の一覧を取得します。
+///
+/// 各アクションは `launch_app` にそのまま渡して起動できます。
+#[tauri::command]
+fn get_app_actions(path: String) -> Result<Vec<app_actions::AppAction>, String> {
+    app_actions::get_actions(&path)
+}
+
+/// 全セルのターゲットを検査し、リンク切れなどの問題と修正候補を返します。
+///
+/// `cells` が指定されない場合は、保存済みの `settings.json` からセル一覧を読み込みます。
+/// スタートメニューの走査やUWP一覧の取得を伴うため、バックグラウンドスレッドで実行します。
+#[tauri::command]
+async fn scan_cell_health(
+    app_handle: tauri::AppHandle,
+    cells: Option<Vec<cell_health::CellRef>>,
+) -> Result<Vec<cell_health::HealthFinding>, String> {
+    let cells = match cells {
+        Some(cells) => cells,
+        None => cell_health::load_cells_from_settings(&app_handle)?,
+    };
+    tauri::async_runtime::spawn_blocking(move || cell_health::scan(&cells))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// 壊れたターゲット（移動・更新されたアプリなど）の修正案を探します。
+///
+/// リンク追跡、バージョン番号付きの兄弟フォルダ、インストール済みアプリの索引の順に試行します。
+/// 修正案は提案のみで、セルへの反映はフロントエンドでユーザーが承認した場合に行います。
+///
+/// # 引数
+/// * `path` - セルのターゲットパス（.lnk の場合はショートカットファイル）
+/// * `title` - 名前一致の検索に使用するセルのタイトル
+#[tauri::command]
+async fn propose_target_repair(
+    path: String,
+    title: Option<String>,
+) -> Result<Option<target_repair::RepairProposal>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        target_repair::propose_repair(&path, title.as_deref().unwrap_or_default(), &mut None)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
 }
 
 /// マウスが画面端に移動した際の監視を開始します。
 #[tauri::command]
 fn start_mouse_edge_monitor(app_handle: tauri::AppHandle) -> Result<(), String> {
     let monitor = app_handle.state::<mouse_edge::MouseEdgeMonitor>();
-    monitor.start(app_handle.clone());
+    monitor.start(app_handle.clone())
+}
+
+/// マウスエッジの設定（反応する画面端・四隅、滞在時間など）を変更します。
+/// 監視中の場合もそのまま反映されます。
+#[tauri::command]
+fn set_mouse_edge_config(
+    app_handle: tauri::AppHandle,
+    config: edge_detector::MouseEdgeConfig,
+) -> Result<(), String> {
+    let monitor = app_handle.state::<mouse_edge::MouseEdgeMonitor>();
+    monitor.set_config(config);
     Ok(())
 }
 
@@ -186,11 +501,19 @@ fn stop_mouse_edge_monitor(app_handle: tauri::AppHandle) -> Result<(), String> {
 }
 
 /// システムリソース（CPU/メモリ）の監視を開始します。
+///
+/// # 引数
+/// * `interval_ms` - 取得間隔（ミリ秒、省略時は1秒）
 #[tauri::command]
-fn start_system_monitor(app_handle: tauri::AppHandle) -> Result<(), String> {
+fn start_system_monitor(
+    app_handle: tauri::AppHandle,
+    interval_ms: Option<u64>,
+) -> Result<(), String> {
     let monitor = app_handle.state::<system_stats::SystemMonitor>();
-    monitor.start(app_handle.clone());
-    Ok(())
+    monitor.start(
+        app_handle.clone(),
+        interval_ms.map(std::time::Duration::from_millis),
+    )
 }
 
 /// システムリソースの監視を停止します。
@@ -201,12 +524,34 @@ fn stop_system_monitor(app_handle: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// バックグラウンドの監視（マウスエッジ・システムリソース）の状態を返します。
+#[tauri::command]
+fn get_monitor_status(app_handle: tauri::AppHandle) -> Vec<background_service::ServiceStatus> {
+    vec![
+        app_handle.state::<mouse_edge::MouseEdgeMonitor>().status(),
+        app_handle.state::<system_stats::SystemMonitor>().status(),
+    ]
+}
+
 /// ウィンドウを非表示にします。
 #[tauri::command]
 fn hide_window(window: tauri::Window) -> Result<(), String> {
     window.hide().map_err(|e| e.to_string())
 }
 
+/// ウィンドウを表示してフォーカスします。
+///
+/// ウィンドウが非表示のまま起動したセル（マウスエッジの「セルを起動」など）で、
+/// 起動の確認ダイアログを表示する前に使用します。
+#[tauri::command]
+fn show_window(window: tauri::Window) -> Result<(), String> {
+    if window.is_minimized().unwrap_or(false) {
+        window.unminimize().map_err(|e| e.to_string())?;
+    }
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
 /// グローバルショートカット（ランチャー呼び出し用）を更新します。
 ///
 /// 既存のショートカットを登録解除し、新しいショートカットを登録します。
@@ -280,15 +625,45 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .register_asynchronous_uri_scheme_protocol(
+            icon_protocol::SCHEME,
+            |ctx, request, responder| {
+                // 失われたキャッシュの再抽出でメインスレッドを塞がないよう、別スレッドで処理する
+                let app_handle = ctx.app_handle().clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    #[cfg(target_os = "windows")]
+                    let _com = shortcut_utils::ComGuard::init();
+                    responder.respond(icon_protocol::handle(&app_handle, &request));
+                });
+            },
+        )
         .invoke_handler(tauri::generate_handler![
             save_settings,
             load_settings,
             launch_app,
             resolve_shortcut,
+            resolve_shortcuts,
+            normalize_path,
+            get_running_targets,
             get_uwp_apps,
+            refresh_uwp_apps,
             launch_uwp_app,
             get_file_icon,
+            get_file_icon_url,
+            get_icon_palette,
+            import_custom_icon,
+            prefetch_icons,
+            cancel_icon_prefetch,
+            get_icon_cache_stats,
+            compact_icon_cache,
+            set_icon_cache_limit,
+            invalidate_icon,
+            invalidate_all_icons,
+            get_app_actions,
+            scan_cell_health,
+            propose_target_repair,
             hide_window,
+            show_window,
             update_global_shortcut,
             save_dropped_file,
             export_settings_json,
@@ -301,10 +676,27 @@ pub fn run() {
             security::check_requires_admin,
             start_mouse_edge_monitor,
             stop_mouse_edge_monitor,
+            set_mouse_edge_config,
             start_system_monitor,
             stop_system_monitor,
+            get_monitor_status,
         ])
         .setup(|app| {
+            // Initialize icon memory cache
+            app.manage(icon_memory_cache::IconMemoryCache::new());
+            // Remember how each icon URL was extracted (to regenerate evicted cache files)
+            app.manage(icon_cache::IconUrlRegistry::default());
+            // Load icon cache index (source mtime / size of each cached icon)
+            match icon_cache::load_index(app.handle()) {
+                Ok(index) => {
+                    app.manage(index);
+                }
+                Err(e) => eprintln!("Failed to load icon cache index: {}", e),
+            }
+            // Load UWP app catalog (refreshed in the background when stale)
+            app.manage(uwp_catalog::UwpCatalog::load(app.handle()));
+            // Initialize icon prefetch queue (workers are started on first request)
+            app.manage(icon_prefetch::IconPrefetcher::new());
             // Initialize mouse edge monitor
             app.manage(mouse_edge::MouseEdgeMonitor::new());
              // Initialize system monitor
@@ -475,6 +867,14 @@ pub fn run() {
                 .build(app)?;
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                // まとめて書き出すために保留しているアイコンキャッシュのインデックスを書き出す
+                if let Some(index) = app_handle.try_state::<icon_index::IconIndex>() {
+                    index.flush();
+                }
+            }
+        });
 }
@@ -0,0 +1,495 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// ディレクトリの MIME タイプ
+pub const DIRECTORY: &str = "inode/directory";
+/// 種類を判定できなかったファイルの MIME タイプ
+pub const UNKNOWN: &str = "application/octet-stream";
+/// 内容がテキストと判定されたファイルの MIME タイプ
+const TEXT_PLAIN: &str = "text/plain";
+
+/// マジックバイトの判定に読み込む先頭のバイト数
+const SNIFF_LEN: usize = 512;
+
+/// MIME タイプの定義（shared-mime-info の `globs` と `generic-icons` に相当）。
+struct MimeInfo {
+    mime: &'static str,
+    /// 拡張子（小文字）。先頭のものがその種類の代表となる拡張子です
+    extensions: &'static [&'static str],
+    /// アイコンテーマに固有のアイコンがない場合に使う汎用アイコン名
+    generic_icon: &'static str,
+}
+
+const fn info(
+    mime: &'static str,
+    extensions: &'static [&'static str],
+    generic_icon: &'static str,
+) -> MimeInfo {
+    MimeInfo {
+        mime,
+        extensions,
+        generic_icon,
+    }
+}
+
+const MIME_TYPES: &[MimeInfo] = &[
+    // テキスト・ソースコード
+    info("text/plain", &["txt", "text", "log"], "text-x-generic"),
+    info("text/markdown", &["md", "markdown"], "text-x-generic"),
+    info("text/html", &["html", "htm", "xhtml"], "text-x-generic"),
+    info("text/css", &["css"], "text-x-generic"),
+    info("text/csv", &["csv", "tsv"], "x-office-spreadsheet"),
+    info("application/json", &["json"], "text-x-generic"),
+    info("application/xml", &["xml"], "text-x-generic"),
+    info("application/x-yaml", &["yaml", "yml"], "text-x-generic"),
+    info("application/toml", &["toml"], "text-x-generic"),
+    info("text/x-python", &["py", "pyw"], "text-x-script"),
+    info(
+        "application/javascript",
+        &["js", "mjs", "cjs"],
+        "text-x-script",
+    ),
+    info("application/x-typescript", &["ts", "tsx"], "text-x-script"),
+    info("text/rust", &["rs"], "text-x-generic"),
+    info("text/x-csrc", &["c", "h"], "text-x-generic"),
+    info(
+        "text/x-c++src",
+        &["cpp", "cc", "cxx", "hpp"],
+        "text-x-generic",
+    ),
+    info("text/x-java", &["java"], "text-x-generic"),
+    info("text/x-go", &["go"], "text-x-generic"),
+    info(
+        "application/x-shellscript",
+        &["sh", "bash", "zsh"],
+        "text-x-script",
+    ),
+    info("application/x-bat", &["bat", "cmd"], "text-x-script"),
+    info(
+        "application/x-powershell",
+        &["ps1", "psm1"],
+        "text-x-script",
+    ),
+    // ドキュメント
+    info("application/pdf", &["pdf"], "x-office-document"),
+    info(
+        "application/postscript",
+        &["ps", "eps"],
+        "x-office-document",
+    ),
+    info("application/rtf", &["rtf"], "x-office-document"),
+    info("application/msword", &["doc", "dot"], "x-office-document"),
+    info(
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        &["docx", "docm"],
+        "x-office-document",
+    ),
+    info(
+        "application/vnd.oasis.opendocument.text",
+        &["odt"],
+        "x-office-document",
+    ),
+    info("application/vnd.ms-excel", &["xls"], "x-office-spreadsheet"),
+    info(
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        &["xlsx", "xlsm"],
+        "x-office-spreadsheet",
+    ),
+    info(
+        "application/vnd.oasis.opendocument.spreadsheet",
+        &["ods"],
+        "x-office-spreadsheet",
+    ),
+    info(
+        "application/vnd.ms-powerpoint",
+        &["ppt", "pps"],
+        "x-office-presentation",
+    ),
+    info(
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        &["pptx", "ppsx"],
+        "x-office-presentation",
+    ),
+    info(
+        "application/vnd.oasis.opendocument.presentation",
+        &["odp"],
+        "x-office-presentation",
+    ),
+    info("application/x-ole-storage", &["msg"], "x-office-document"),
+    info("application/epub+zip", &["epub"], "x-office-document"),
+    info(
+        "application/vnd.sqlite3",
+        &["sqlite", "db"],
+        "x-office-document",
+    ),
+    // 画像
+    info("image/png", &["png"], "image-x-generic"),
+    info("image/jpeg", &["jpg", "jpeg", "jpe"], "image-x-generic"),
+    info("image/gif", &["gif"], "image-x-generic"),
+    info("image/bmp", &["bmp"], "image-x-generic"),
+    info("image/webp", &["webp"], "image-x-generic"),
+    info("image/tiff", &["tif", "tiff"], "image-x-generic"),
+    info("image/svg+xml", &["svg", "svgz"], "image-x-generic"),
+    info("image/vnd.microsoft.icon", &["ico"], "image-x-generic"),
+    info("image/x-xpixmap", &["xpm"], "image-x-generic"),
+    info("image/vnd.adobe.photoshop", &["psd"], "image-x-generic"),
+    // 音声・動画
+    info("audio/mpeg", &["mp3"], "audio-x-generic"),
+    info("audio/ogg", &["ogg", "oga", "opus"], "audio-x-generic"),
+    info("audio/flac", &["flac"], "audio-x-generic"),
+    info("audio/x-wav", &["wav"], "audio-x-generic"),
+    info("audio/mp4", &["m4a", "aac"], "audio-x-generic"),
+    info("video/mp4", &["mp4", "m4v"], "video-x-generic"),
+    info("video/x-matroska", &["mkv"], "video-x-generic"),
+    info("video/webm", &["webm"], "video-x-generic"),
+    info("video/x-msvideo", &["avi"], "video-x-generic"),
+    info("video/quicktime", &["mov"], "video-x-generic"),
+    info("video/x-ms-wmv", &["wmv"], "video-x-generic"),
+    // アーカイブ・パッケージ
+    info("application/zip", &["zip"], "package-x-generic"),
+    info("application/x-7z-compressed", &["7z"], "package-x-generic"),
+    info("application/vnd.rar", &["rar"], "package-x-generic"),
+    info("application/x-tar", &["tar"], "package-x-generic"),
+    info("application/gzip", &["gz", "tgz"], "package-x-generic"),
+    info("application/x-bzip2", &["bz2"], "package-x-generic"),
+    info("application/x-xz", &["xz", "txz"], "package-x-generic"),
+    info("application/zstd", &["zst"], "package-x-generic"),
+    info("application/x-cd-image", &["iso"], "package-x-generic"),
+    info(
+        "application/vnd.debian.binary-package",
+        &["deb"],
+        "package-x-generic",
+    ),
+    info("application/x-rpm", &["rpm"], "package-x-generic"),
+    info("application/x-msi", &["msi"], "package-x-generic"),
+    // フォント
+    info("font/ttf", &["ttf"], "font-x-generic"),
+    info("font/otf", &["otf"], "font-x-generic"),
+    info("font/woff2", &["woff2", "woff"], "font-x-generic"),
+    // 実行ファイル
+    info(
+        "application/x-msdownload",
+        &["exe", "dll", "com", "scr"],
+        "application-x-executable",
+    ),
+    info(
+        "application/x-executable",
+        &["bin", "run", "out"],
+        "application-x-executable",
+    ),
+];
+
+/// ファイルの MIME タイプを判定します。
+///
+/// 拡張子で判定できない場合は先頭のマジックバイトで判定し、それでも判定できない場合は
+/// 内容がテキストかどうかで `text/plain` か `application/octet-stream` とします。
+/// ファイルが存在しない場合も拡張子だけで判定します。
+pub fn detect(path: &Path) -> String {
+    if path.is_dir() {
+        return DIRECTORY.to_string();
+    }
+
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if let Some(info) = MIME_TYPES
+        .iter()
+        .find(|info| info.extensions.contains(&extension.as_str()))
+    {
+        return info.mime.to_string();
+    }
+
+    let Some(head) = read_head(path) else {
+        return UNKNOWN.to_string();
+    };
+    sniff(&head)
+        .or_else(|| is_text(&head).then_some(TEXT_PLAIN))
+        .unwrap_or(UNKNOWN)
+        .to_string()
+}
+
+/// MIME タイプに対応するアイコン名を、優先順に返します。
+///
+/// shared-mime-info の規則に従い、`/` を `-` に置き換えた名前（`application-pdf` など）、
+/// 汎用アイコン名（`x-office-document` など）、メディアタイプの汎用アイコン名（`text-x-generic` など）の順で、
+/// 最後にアイコン命名仕様の `unknown` を加えます。
+pub fn icon_names(mime: &str) -> Vec<String> {
+    if mime == DIRECTORY {
+        return vec!["folder".to_string(), "inode-directory".to_string()];
+    }
+
+    let mut names = vec![mime.replace('/', "-")];
+    if let Some(info) = MIME_TYPES.iter().find(|info| info.mime == mime) {
+        names.push(info.generic_icon.to_string());
+    }
+    let media = mime.split('/').next().unwrap_or_default();
+    names.push(format!("{}-x-generic", media));
+    if media != "text" {
+        // application-x-generic などを持たないテーマのための最終的なフォールバック
+        names.push("text-x-generic".to_string());
+    }
+    names.push("unknown".to_string());
+
+    let mut seen = std::collections::HashSet::new();
+    names.retain(|name| seen.insert(name.clone()));
+    names
+}
+
+/// MIME タイプを代表する拡張子を返します（Windows でシェルに種類のアイコンを問い合わせる際に使用します）。
+///
+/// 拡張子を持たない種類（`application/octet-stream` など）の場合は `None` を返します。
+pub fn extension_for(mime: &str) -> Option<&'static str> {
+    MIME_TYPES
+        .iter()
+        .find(|info| info.mime == mime)
+        .and_then(|info| info.extensions.first().copied())
+}
+
+/// ファイル自体が固有のアイコンを持つかどうかを返します。
+///
+/// 実行ファイル・ショートカット・アイコン画像などはファイルごとにアイコンが異なるため、
+/// パスごとにアイコンを抽出・キャッシュします。それ以外のファイルは種類ごとのアイコン
+/// （Windows では拡張子ごと、それ以外の OS では MIME タイプごと）を使用します。
+/// Windows では、ドライブのルートと `desktop.ini` でアイコンを設定したフォルダーも固有のアイコンを持つものとします。
+pub fn has_own_icon(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if matches!(
+        extension.as_str(),
+        "exe"
+            | "dll"
+            | "com"
+            | "scr"
+            | "cpl"
+            | "msc"
+            | "ico"
+            | "lnk"
+            | "url"
+            | "desktop"
+            | "appimage"
+            | "png"
+            | "svg"
+            | "svgz"
+            | "xpm"
+    ) {
+        return true;
+    }
+
+    if cfg!(target_os = "windows") && path.is_dir() {
+        return path.parent().is_none() || path.join("desktop.ini").is_file();
+    }
+
+    false
+}
+
+/// ファイルの先頭 `SNIFF_LEN` バイトを読み込みます。
+fn read_head(path: &Path) -> Option<Vec<u8>> {
+    let file = File::open(path).ok()?;
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    file.take(SNIFF_LEN as u64).read_to_end(&mut head).ok()?;
+    Some(head)
+}
+
+/// 先頭のマジックバイトから MIME タイプを判定します。
+fn sniff(head: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"%PDF-", "application/pdf"),
+        (b"%!PS", "application/postscript"),
+        (b"{\\rtf", "application/rtf"),
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"BM", "image/bmp"),
+        (b"II*\0", "image/tiff"),
+        (b"MM\0*", "image/tiff"),
+        (b"8BPS", "image/vnd.adobe.photoshop"),
+        (b"ID3", "audio/mpeg"),
+        (b"OggS", "audio/ogg"),
+        (b"fLaC", "audio/flac"),
+        (b"\x1a\x45\xdf\xa3", "video/x-matroska"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+        (b"Rar!\x1a\x07", "application/vnd.rar"),
+        (b"BZh", "application/x-bzip2"),
+        (b"\xfd7zXZ\0", "application/x-xz"),
+        (b"\x28\xb5\x2f\xfd", "application/zstd"),
+        (b"!<arch>\ndebian", "application/vnd.debian.binary-package"),
+        (b"\xed\xab\xee\xdb", "application/x-rpm"),
+        (
+            b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1",
+            "application/x-ole-storage",
+        ),
+        (b"SQLite format 3\0", "application/vnd.sqlite3"),
+        (b"\0\x01\0\0\0", "font/ttf"),
+        (b"OTTO", "font/otf"),
+        (b"wOF2", "font/woff2"),
+        (b"\x7fELF", "application/x-executable"),
+        (b"MZ", "application/x-msdownload"),
+        (b"#!", "application/x-shellscript"),
+    ];
+
+    if let Some((_, mime)) = SIGNATURES.iter().find(|(magic, _)| head.starts_with(magic)) {
+        return Some(mime);
+    }
+
+    // RIFF コンテナ・ISO Base Media (MP4 など) は途中のバイトで種類を判定する
+    if head.starts_with(b"RIFF") && head.len() >= 12 {
+        return match &head[8..12] {
+            b"WEBP" => Some("image/webp"),
+            b"WAVE" => Some("audio/x-wav"),
+            b"AVI " => Some("video/x-msvideo"),
+            _ => None,
+        };
+    }
+    if head.len() >= 12 && &head[4..8] == b"ftyp" {
+        return match &head[8..12] {
+            b"M4A " => Some("audio/mp4"),
+            b"qt  " => Some("video/quicktime"),
+            _ => Some("video/mp4"),
+        };
+    }
+
+    // テキスト形式のマークアップは先頭の空白と BOM を除いて判定する
+    let text = String::from_utf8_lossy(head);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    let lower = text.to_lowercase();
+    if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        Some("text/html")
+    } else if lower.starts_with("<svg") {
+        Some("image/svg+xml")
+    } else if lower.starts_with("<?xml") {
+        Some(if lower.contains("<svg") {
+            "image/svg+xml"
+        } else {
+            "application/xml"
+        })
+    } else {
+        None
+    }
+}
+
+/// 先頭のバイト列がテキストとみなせるかどうか（NUL を含まず、UTF-8 として解釈できるか）を返します。
+fn is_text(head: &[u8]) -> bool {
+    if head.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => true,
+        // 読み込み範囲の末尾でマルチバイト文字が途切れている場合は許容する
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn maps_extensions_case_insensitively() {
+        assert_eq!(detect(Path::new("/no/such/Report.PDF")), "application/pdf");
+        assert_eq!(detect(Path::new("/no/such/main.rs")), "text/rust");
+        assert_eq!(detect(Path::new("/no/such/photo.JPEG")), "image/jpeg");
+        assert_eq!(
+            detect(Path::new("/no/such/archive.tgz")),
+            "application/gzip"
+        );
+        // 存在しないファイルは拡張子で判定できなければ不明とする
+        assert_eq!(detect(Path::new("/no/such/file.unknownext")), UNKNOWN);
+        assert_eq!(detect(Path::new("/no/such/README")), UNKNOWN);
+
+        assert_eq!(extension_for("image/jpeg"), Some("jpg"));
+        assert_eq!(extension_for("text/plain"), Some("txt"));
+        assert_eq!(extension_for(UNKNOWN), None);
+    }
+
+    #[test]
+    fn sniffs_magic_bytes() {
+        assert_eq!(sniff(b"%PDF-1.7\n"), Some("application/pdf"));
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\0\0"), Some("image/png"));
+        assert_eq!(sniff(b"PK\x03\x04rest"), Some("application/zip"));
+        assert_eq!(sniff(b"\x7fELF\x02\x01"), Some("application/x-executable"));
+        assert_eq!(sniff(b"#!/bin/sh\n"), Some("application/x-shellscript"));
+        assert_eq!(sniff(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff(b"RIFF\0\0\0\0WAVEfmt "), Some("audio/x-wav"));
+        assert_eq!(sniff(b"RIFF\0\0\0\0????"), None);
+        assert_eq!(sniff(b"\0\0\0\x18ftypisom"), Some("video/mp4"));
+        assert_eq!(sniff(b"\0\0\0\x18ftypM4A "), Some("audio/mp4"));
+        assert_eq!(sniff(b"\xef\xbb\xbf  <!DOCTYPE HTML>"), Some("text/html"));
+        assert_eq!(
+            sniff(b"<?xml version=\"1.0\"?>\n<svg xmlns=\"\">"),
+            Some("image/svg+xml")
+        );
+        assert_eq!(
+            sniff(b"<?xml version=\"1.0\"?><root/>"),
+            Some("application/xml")
+        );
+        assert_eq!(sniff(b"plain words"), None);
+        assert_eq!(sniff(b""), None);
+    }
+
+    #[test]
+    fn detects_text_content() {
+        assert!(is_text(b"hello\nworld"));
+        assert!(is_text("日本語".as_bytes()));
+        assert!(is_text(b""));
+        // 末尾で途切れたマルチバイト文字は許容する
+        assert!(is_text(&"あ".as_bytes()[..2]));
+        assert!(!is_text(b"bin\0ary"));
+        assert!(!is_text(b"\xff\xfe\xfd"));
+    }
+
+    #[test]
+    fn falls_back_to_content_without_known_extension() {
+        let dir = std::env::temp_dir().join(format!("mime-type-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let png = dir.join("image.data");
+        fs::write(&png, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        let text = dir.join("notes");
+        fs::write(&text, "just some notes\n").unwrap();
+        let binary = dir.join("blob");
+        fs::write(&binary, b"\x01\x02\0\x03").unwrap();
+
+        assert_eq!(detect(&dir), DIRECTORY);
+        assert_eq!(detect(&png), "image/png");
+        assert_eq!(detect(&text), TEXT_PLAIN);
+        assert_eq!(detect(&binary), UNKNOWN);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn orders_icon_names_from_specific_to_unknown() {
+        assert_eq!(
+            icon_names("application/pdf"),
+            vec![
+                "application-pdf",
+                "x-office-document",
+                "application-x-generic",
+                "text-x-generic",
+                "unknown",
+            ]
+        );
+        // 汎用アイコン名がメディアタイプの汎用アイコン名と同じ場合は重複させない
+        assert_eq!(
+            icon_names("text/plain"),
+            vec!["text-plain", "text-x-generic", "unknown"]
+        );
+        assert_eq!(
+            icon_names("image/x-unlisted"),
+            vec![
+                "image-x-unlisted",
+                "image-x-generic",
+                "text-x-generic",
+                "unknown"
+            ]
+        );
+        assert_eq!(icon_names(DIRECTORY), vec!["folder", "inode-directory"]);
+    }
+}
@@ -1,100 +1,116 @@
 use std::sync::{Arc, Mutex};
-use std::thread;
 use std::time::Duration;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 
+#[cfg(target_os = "windows")]
+use std::time::Instant;
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::POINT;
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
 
+use crate::background_service::{BackgroundService, ServiceStatus};
+#[cfg(target_os = "windows")]
+use crate::edge_detector::{CursorSource, EdgeEvent, ForegroundWindow};
+use crate::edge_detector::{EdgeAction, EdgeDetector, MonitorRect, MouseEdgeConfig};
+#[cfg(target_os = "windows")]
+use crate::foreground_window;
+
+/// カーソル位置を確認する間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// フロントエンドで行う動作（グループ・セル・検索）を通知するイベント
+const EDGE_ACTION_EVENT: &str = "mouse-edge-action";
+
 /// マウスが画面端に移動したことを検知するモニター構造体。
 pub struct MouseEdgeMonitor {
-    running: Arc<Mutex<bool>>,
+    /// 監視スレッド
+    service: BackgroundService,
+    /// 反応するゾーンなどの設定（監視中の変更は次の確認から反映されます）
+    config: Arc<Mutex<MouseEdgeConfig>>,
 }
 
 impl MouseEdgeMonitor {
     pub fn new() -> Self {
         Self {
-            running: Arc::new(Mutex::new(false)),
+            service: BackgroundService::new("mouse-edge-monitor", POLL_INTERVAL),
+            config: Arc::new(Mutex::new(MouseEdgeConfig::default())),
         }
     }
 
-    /// 監視を開始します。
-    pub fn start(&self, app_handle: AppHandle) {
-        let running = Arc::clone(&self.running);
+    /// 設定を変更します。監視中の場合もそのまま反映されます。
+    pub fn set_config(&self, config: MouseEdgeConfig) {
+        *self.config.lock().unwrap_or_else(|e| e.into_inner()) = config;
+    }
 
-        // 二重起動防止
-        if let Ok(mut guard) = running.lock() {
-            if *guard {
-                return;
-            }
-            *guard = true;
-        } else {
-            return;
-        }
+    /// 監視を開始します。既に実行中の場合は何もしません。
+    pub fn start(&self, app_handle: AppHandle) -> Result<(), String> {
+        let config = Arc::clone(&self.config);
+        let mut detector =
+            EdgeDetector::new(config.lock().unwrap_or_else(|e| e.into_inner()).clone());
 
-        thread::spawn(move || {
+        self.service.start(move |_| {
             #[cfg(target_os = "windows")]
             {
-                loop {
-                    // Check running state safely
-                    if let Ok(guard) = running.lock() {
-                        if !*guard {
-                            break;
-                        }
-                    } else {
-                        break; // Poisoned
+                detector.set_config(config.lock().unwrap_or_else(|e| e.into_inner()).clone());
+
+                // 滞在時間・連続反応防止・抑止条件は検知側で判定する
+                let mut source = SystemCursor {
+                    app_handle: &app_handle,
+                };
+                match detector.poll(&mut source, Instant::now()) {
+                    Some(EdgeEvent::Triggered(hit)) => {
+                        let action = detector.config().action_for(hit.zone);
+                        MouseEdgeMonitor::perform(&app_handle, &action, &hit.monitor);
                     }
-
-                    unsafe {
-                        let mut point = std::mem::zeroed();
-                        if GetCursorPos(&mut point).is_ok() {
-                            let mouse_x = point.x;
-                            let mouse_y = point.y;
-
-                            // 全てのモニターをチェック
-                            if let Ok(monitors) = app_handle.available_monitors() {
-                                for monitor in monitors {
-                                    let m_pos = monitor.position(); // PhysicalPosition
-                                    let m_size = monitor.size(); // PhysicalSize
-
-                                    // カーソルがこのモニター内にあるか確認
-                                    let x_in_monitor = mouse_x >= m_pos.x
-                                        && mouse_x < m_pos.x + m_size.width as i32;
-                                    let y_in_monitor = mouse_y >= m_pos.y
-                                        && mouse_y < m_pos.y + m_size.height as i32;
-
-                                    if x_in_monitor && y_in_monitor {
-                                        // このモニターの上端、または左端にあるか？
-                                        let is_top_edge = (mouse_y - m_pos.y).abs() <= 5; // 閾値を5pxに厳格化
-                                        let is_left_edge = (mouse_x - m_pos.x).abs() <= 5;
-
-                                        if is_top_edge || is_left_edge {
-                                            MouseEdgeMonitor::try_trigger_window(
-                                                &app_handle,
-                                                &monitor,
-                                            );
-                                            // 反応後は少し待つ（連続反応防止）
-                                            thread::sleep(Duration::from_millis(1000));
-                                        }
-                                        break; // Monitor found, stop checking others
-                                    }
-                                }
-                            }
+                    Some(EdgeEvent::Suppressed(hit, reason)) => {
+                        // 抑止した理由はデバッグビルドでのみ出力する
+                        if cfg!(debug_assertions) {
+                            eprintln!(
+                                "Edge activation suppressed. Zone: {:?}, Reason: {}",
+                                hit.zone, reason
+                            );
                         }
                     }
-                    thread::sleep(Duration::from_millis(100));
+                    None => {}
                 }
+                Ok(())
             }
 
             #[cfg(not(target_os = "windows"))]
             {
-                thread::sleep(Duration::from_secs(1));
+                let _ = (&app_handle, &config, &mut detector);
+                Err("Mouse edge monitoring is not supported on this platform".to_string())
+            }
+        })?;
+        Ok(())
+    }
+
+    /// ゾーンに到達した際の動作を行います（失敗してもパニックしない）
+    ///
+    /// グループ・セル・検索の操作はフロントエンドの状態を使うため、
+    /// 必要に応じてウィンドウを表示した上で `mouse-edge-action` イベントで通知します。
+    fn perform(app_handle: &AppHandle, action: &EdgeAction, target_monitor: &MonitorRect) {
+        match action {
+            EdgeAction::Hide => {
+                if let Some(window) = app_handle.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+                return;
+            }
+            // セルの起動ではランチャーを表示しない
+            // （起動の確認ダイアログが必要な場合は、フロントエンドがダイアログの前にウィンドウを表示する）
+            EdgeAction::LaunchCell { .. } => {}
+            EdgeAction::ShowLauncher | EdgeAction::OpenGroup { .. } | EdgeAction::OpenSearch => {
+                MouseEdgeMonitor::try_trigger_window(app_handle, target_monitor);
             }
-        });
+        }
+        if *action != EdgeAction::ShowLauncher {
+            let _ = app_handle.emit(EDGE_ACTION_EVENT, action);
+        }
     }
 
     /// ウィンドウを表示・移動させる処理（失敗してもパニックしない）
-    fn try_trigger_window(app_handle: &AppHandle, target_monitor: &tauri::Monitor) {
+    fn try_trigger_window(app_handle: &AppHandle, target_monitor: &MonitorRect) {
         if let Some(window) = app_handle.get_webview_window("main") {
             let is_visible = window.is_visible().unwrap_or(false);
             let is_focused = window.is_focused().unwrap_or(false);
@@ -121,8 +137,10 @@ impl MouseEdgeMonitor {
             let _ = window.unmaximize();
 
             // 移動 (エラー無視)
-            let _ =
-                window.set_position(tauri::Position::Physical(target_monitor.position().clone()));
+            let _ = window.set_position(tauri::Position::Physical(tauri::PhysicalPosition::new(
+                target_monitor.x,
+                target_monitor.y,
+            )));
 
             // 3. 表示 & 最大化
             let _ = window.show();
@@ -135,9 +153,57 @@ impl MouseEdgeMonitor {
         }
     }
 
+    /// 監視を停止し、監視スレッドが終了するまで待ちます。
     pub fn stop(&self) {
-        if let Ok(mut guard) = self.running.lock() {
-            *guard = false;
-        }
+        self.service.stop();
+    }
+
+    /// 監視スレッドの状態を返します。
+    pub fn status(&self) -> ServiceStatus {
+        self.service.status()
+    }
+}
+
+/// OS のカーソル位置・マウスのボタン・前面のウィンドウと、Tauri が返すモニターの配置の取得元。
+#[cfg(target_os = "windows")]
+struct SystemCursor<'a> {
+    app_handle: &'a AppHandle,
+}
+
+#[cfg(target_os = "windows")]
+impl CursorSource for SystemCursor<'_> {
+    fn cursor_position(&mut self) -> Option<(i32, i32)> {
+        let mut point = POINT::default();
+        unsafe { GetCursorPos(&mut point) }.ok()?;
+        Some((point.x, point.y))
+    }
+
+    fn monitors(&mut self) -> Vec<MonitorRect> {
+        self.app_handle
+            .available_monitors()
+            .map(|monitors| monitors.iter().map(monitor_rect).collect())
+            .unwrap_or_default()
+    }
+
+    fn mouse_button_down(&mut self) -> bool {
+        foreground_window::mouse_button_down()
+    }
+
+    fn foreground_window(&mut self) -> Option<ForegroundWindow> {
+        foreground_window::foreground_window()
+    }
+}
+
+/// モニターの領域を検知用の形式に変換します。
+#[cfg(target_os = "windows")]
+fn monitor_rect(monitor: &tauri::Monitor) -> MonitorRect {
+    let position = monitor.position();
+    let size = monitor.size();
+    MonitorRect {
+        name: monitor.name().cloned(),
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
     }
 }
@@ -0,0 +1,135 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+/// セルに保存されたパスと、シンボリックリンク等を解決した実体パスの組。
+#[derive(Debug, Serialize)]
+pub struct NormalizedPath {
+    /// セルに保存されているパス（ユーザーが指定したまま）
+    pub stored: String,
+    /// シンボリックリンク・ジャンクションを解決した実体のパス
+    pub resolved: String,
+    /// `stored` 自体またはその途中のディレクトリがリンクだったかどうか
+    pub is_link: bool,
+}
+
+/// パスを正規化し、保存されたパスと実体パスの両方を返します。
+///
+/// 解決に失敗した場合（ファイルが存在しないなど）は、`resolved` に元のパスをそのまま入れます。
+pub fn normalize(path: &str) -> NormalizedPath {
+    let is_link = Path::new(path).ancestors().any(|p| {
+        fs::symlink_metadata(p)
+            .map(|m| m.file_type().is_symlink() || is_junction(&m))
+            .unwrap_or(false)
+    });
+
+    NormalizedPath {
+        stored: path.to_string(),
+        resolved: real_path(path),
+        is_link,
+    }
+}
+
+/// シンボリックリンク（Windowsではジャンクションも含む）を解決した実体パスを返します。
+///
+/// `fs::canonicalize` を使用し、Windowsで付与される `\\?\` プレフィックスは取り除きます。
+/// 解決できない場合は元のパスを返します。
+/// macOS の Finder エイリアスはファイルシステム上のリンクではないため解決しません。
+pub fn real_path(path: &str) -> String {
+    match fs::canonicalize(path) {
+        Ok(p) => strip_verbatim_prefix(&p.to_string_lossy()),
+        Err(_) => path.to_string(),
+    }
+}
+
+/// 実行中のプロセスのうち、実行ファイルの実体パスが `paths` のいずれかと一致するものを探し、
+/// 一致した `paths` の要素を返します。
+///
+/// `paths` にはセルに記録した実体パス（`resolved`）を渡すことを想定していますが、
+/// 比較の際には改めてリンクを解決するため、保存されたパスを渡しても一致します。
+pub fn running_targets(paths: &[String]) -> Vec<String> {
+    let wanted: HashMap<String, &String> = paths.iter().map(|p| (path_key(p), p)).collect();
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet),
+    );
+
+    let mut found: Vec<String> = Vec::new();
+    for process in sys.processes().values() {
+        let Some(exe) = process.exe() else { continue };
+        if let Some(path) = wanted.get(&path_key(&exe.to_string_lossy())) {
+            if !found.contains(path) {
+                found.push((*path).clone());
+            }
+        }
+    }
+    found
+}
+
+/// パスの比較用のキー（実体パス。Windowsでは大文字・小文字を区別しません）。
+fn path_key(path: &str) -> String {
+    let real = real_path(path);
+    if cfg!(target_os = "windows") {
+        real.to_lowercase()
+    } else {
+        real
+    }
+}
+
+/// `\\?\C:\foo` → `C:\foo`、`\\?\UNC\server\share` → `\\server\share` に変換します。
+///
+/// 多くのアプリケーションやシェルAPIは verbatim 形式のパスを正しく扱えないためです。
+fn strip_verbatim_prefix(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", rest)
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn is_junction(metadata: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    // FILE_ATTRIBUTE_REPARSE_POINT (ジャンクション・シンボリックリンク共通)
+    metadata.file_attributes() & 0x400 != 0
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_junction(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_running_test_binary() {
+        let exe = std::env::current_exe()
+            .unwrap()
+            .to_string_lossy()
+            .to_string();
+        let missing = exe.clone() + ".missing";
+        assert_eq!(running_targets(&[exe.clone(), missing]), vec![exe]);
+    }
+
+    #[test]
+    fn strips_verbatim_prefixes() {
+        assert_eq!(strip_verbatim_prefix(r"\\?\C:\foo"), r"C:\foo");
+        assert_eq!(
+            strip_verbatim_prefix(r"\\?\UNC\server\share"),
+            r"\\server\share"
+        );
+        assert_eq!(strip_verbatim_prefix("/usr/bin/ls"), "/usr/bin/ls");
+    }
+}
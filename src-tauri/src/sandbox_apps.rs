@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::app_actions::desktop_files_in;
+use crate::desktop_entry::{self, DesktopEntry};
+use crate::icon_theme;
+use crate::uwp_utils::UwpApp;
+
+/// Flatpak アプリのIDの接頭辞（`flatpak:<デスクトップファイルID>`）
+const FLATPAK_PREFIX: &str = "flatpak:";
+/// Snap アプリのIDの接頭辞（`snap:<コマンド名>`）
+const SNAP_PREFIX: &str = "snap:";
+/// システム全体の Flatpak のインストール先
+const FLATPAK_SYSTEM_DIR: &str = "/var/lib/flatpak";
+/// snapd が各アプリの `.desktop` ファイルを書き出すディレクトリ
+const SNAP_DESKTOP_DIR: &str = "/var/lib/snapd/desktop/applications";
+/// Snap アプリの起動用ラッパーのディレクトリ
+const SNAP_BIN_DIR: &str = "/snap/bin";
+/// ロゴを探す際の表示サイズ
+const LOGO_SIZE: u32 = 256;
+/// hicolor テーマでロゴを探すサイズのディレクトリ（優先順）
+const LOGO_SIZE_DIRS: [&str; 6] = [
+    "256x256", "512x512", "scalable", "128x128", "64x64", "48x48",
+];
+
+/// Flatpak と Snap でインストールされたアプリの一覧を返します。
+///
+/// Flatpak はユーザー・システムのインストール先の `exports/share/applications`、
+/// Snap は `/var/lib/snapd/desktop/applications` の `.desktop` ファイルから列挙します。
+/// メニューに表示しない（`NoDisplay=true` / `Hidden=true`）項目は含めません。
+///
+/// 1つの Flatpak アプリが複数の `.desktop` ファイル（別のコマンドを起動する項目など）を書き出す場合があるため、
+/// Flatpak の項目はアプリID（`X-Flatpak`）ではなくデスクトップファイルIDで区別します。
+pub fn installed_apps() -> Vec<UwpApp> {
+    let lang = desktop_entry::current_lang();
+    let mut seen = HashSet::new();
+    let mut apps = Vec::new();
+
+    for export_dir in flatpak_export_dirs() {
+        let icon_root = export_dir.join("icons");
+        for file in desktop_files_in(&export_dir.join("applications")) {
+            let Some((entry, name)) = load_visible_entry(&file, lang.as_deref()) else {
+                continue;
+            };
+            let Some(id) = file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+            else {
+                continue;
+            };
+            let aumid = format!("{}{}", FLATPAK_PREFIX, id);
+            // 同じデスクトップファイルIDはユーザーのインストール先を優先する
+            if !seen.insert(aumid.clone()) {
+                continue;
+            }
+            apps.push(UwpApp {
+                name,
+                aumid,
+                logo: find_logo(&entry, Some(&icon_root)),
+            });
+        }
+    }
+
+    for file in desktop_files_in(Path::new(SNAP_DESKTOP_DIR)) {
+        let Some((entry, name)) = load_visible_entry(&file, lang.as_deref()) else {
+            continue;
+        };
+        let Some(command) = file
+            .file_stem()
+            .map(|stem| snap_command(&stem.to_string_lossy()))
+        else {
+            continue;
+        };
+        let aumid = format!("{}{}", SNAP_PREFIX, command);
+        if !seen.insert(aumid.clone()) {
+            continue;
+        }
+        apps.push(UwpApp {
+            name,
+            aumid,
+            logo: find_logo(&entry, None),
+        });
+    }
+
+    apps
+}
+
+/// `flatpak:<デスクトップファイルID>` は `.desktop` ファイルの `Exec=`、
+/// `snap:<コマンド名>` は `/snap/bin` のラッパーで起動します。
+///
+/// Flatpak の `Exec=` には `flatpak run --command=<コマンド> <アプリID> <引数>` の形で起動するコマンドが
+/// 記録されているため、そのまま実行します。`.desktop` ファイルが見つからない場合（アプリIDで登録された
+/// 古いセルなど）は `flatpak run <ID>` で起動します。
+pub fn launch(id: &str) -> Result<(), String> {
+    let mut command = if let Some(desktop_id) = id.strip_prefix(FLATPAK_PREFIX) {
+        let desktop_id = valid_name(desktop_id)?;
+        match find_flatpak_entry(desktop_id) {
+            Some(entry) => desktop_command(&entry)
+                .ok_or_else(|| format!("Desktop entry has no Exec: {}", desktop_id))?,
+            None => {
+                let mut command = Command::new("flatpak");
+                command.arg("run").arg(desktop_id);
+                command
+            }
+        }
+    } else if let Some(name) = id.strip_prefix(SNAP_PREFIX) {
+        Command::new(Path::new(SNAP_BIN_DIR).join(valid_name(name)?))
+    } else {
+        return Err(format!("Unsupported app id: {}", id));
+    };
+
+    command
+        .spawn()
+        .map_err(|e| format!("Failed to launch {}: {}", id, e))?;
+    Ok(())
+}
+
+/// Flatpak の書き出し先から、デスクトップファイルIDの `.desktop` ファイルをユーザーのインストール先を優先して読み込みます。
+fn find_flatpak_entry(desktop_id: &str) -> Option<DesktopEntry> {
+    flatpak_export_dirs().into_iter().find_map(|dir| {
+        let file = dir
+            .join("applications")
+            .join(format!("{}.desktop", desktop_id));
+        DesktopEntry::load(&file).ok()
+    })
+}
+
+/// `.desktop` ファイルの `Exec=`（と作業ディレクトリの `Path=`）から起動するコマンドを作ります。
+fn desktop_command(entry: &DesktopEntry) -> Option<Command> {
+    let mut argv = desktop_entry::split_exec(entry.get("Desktop Entry", "Exec")?).into_iter();
+    let mut command = Command::new(argv.next()?);
+    command.args(argv);
+    if let Some(dir) = entry
+        .get("Desktop Entry", "Path")
+        .filter(|dir| !dir.is_empty())
+    {
+        command.current_dir(dir);
+    }
+    Some(command)
+}
+
+/// `.desktop` ファイルを読み込み、メニューに表示する項目であれば表示名とともに返します。
+fn load_visible_entry(file: &Path, lang: Option<&str>) -> Option<(DesktopEntry, String)> {
+    let entry = DesktopEntry::load(file).ok()?;
+    let hidden = ["NoDisplay", "Hidden"]
+        .iter()
+        .any(|key| entry.get("Desktop Entry", key) == Some("true"));
+    if hidden {
+        return None;
+    }
+    let name = entry
+        .get_localized("Desktop Entry", "Name", lang)?
+        .to_string();
+    Some((entry, name))
+}
+
+/// ユーザー（`$XDG_DATA_HOME/flatpak`）とシステム（`/var/lib/flatpak`）の Flatpak の
+/// `exports/share` ディレクトリを優先順に返します。
+fn flatpak_export_dirs() -> Vec<PathBuf> {
+    let user_data_dir = match std::env::var("XDG_DATA_HOME") {
+        Ok(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => std::env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".local/share")),
+    };
+
+    user_data_dir
+        .map(|dir| dir.join("flatpak"))
+        .into_iter()
+        .chain([PathBuf::from(FLATPAK_SYSTEM_DIR)])
+        .map(|dir| dir.join("exports").join("share"))
+        .collect()
+}
+
+/// snapd が書き出す `.desktop` ファイル名（`<snap名>_<アプリ名>`）から、`/snap/bin` のコマンド名を返します。
+///
+/// アプリ名が snap 名と同じ場合は `<snap名>`、異なる場合は `<snap名>.<アプリ名>` です。
+fn snap_command(desktop_stem: &str) -> String {
+    match desktop_stem.split_once('_') {
+        Some((snap, app)) if snap == app => snap.to_string(),
+        Some((snap, app)) => format!("{}.{}", snap, app),
+        None => desktop_stem.to_string(),
+    }
+}
+
+/// `.desktop` ファイルの `Icon=` からロゴ画像のパスを求めます。
+///
+/// 絶対パス（Snap）の場合はそのまま使用し、アイコン名（Flatpak）の場合は
+/// Flatpak の書き出し先の hicolor テーマ、現在のアイコンテーマの順に探します。
+fn find_logo(entry: &DesktopEntry, icon_root: Option<&Path>) -> Option<String> {
+    let icon = entry.get("Desktop Entry", "Icon")?;
+    if Path::new(icon).is_absolute() {
+        return Path::new(icon).is_file().then(|| icon.to_string());
+    }
+
+    let exported = icon_root.and_then(|root| {
+        LOGO_SIZE_DIRS.iter().find_map(|size| {
+            let dir = root.join("hicolor").join(size).join("apps");
+            ["png", "svg"]
+                .iter()
+                .map(|ext| dir.join(format!("{}.{}", icon, ext)))
+                .find(|path| path.is_file())
+        })
+    });
+    exported
+        .or_else(|| icon_theme::lookup_icon(icon, LOGO_SIZE))
+        // XPM はアイコンキャッシュで画像として読み込めないため使用しない
+        .filter(|path| path.extension().is_some_and(|ext| ext != "xpm"))
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+/// IDの名前部分がオプションやパスとして解釈されない文字だけで構成されているか検証します。
+fn valid_name(name: &str) -> Result<&str, String> {
+    let valid = name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if valid {
+        Ok(name)
+    } else {
+        Err(format!("Invalid app id: {}", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_command_from_flatpak_exec() {
+        let entry = DesktopEntry::parse(
+            "[Desktop Entry]\nName=Extra\nExec=/usr/bin/flatpak run --branch=stable --arch=x86_64 --command=kate-extra org.kde.kate --new %U\n",
+        );
+        let command = desktop_command(&entry).unwrap();
+        assert_eq!(command.get_program(), "/usr/bin/flatpak");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(
+            args,
+            [
+                "run",
+                "--branch=stable",
+                "--arch=x86_64",
+                "--command=kate-extra",
+                "org.kde.kate",
+                "--new"
+            ]
+        );
+        assert_eq!(command.get_current_dir(), None);
+    }
+
+    #[test]
+    fn uses_working_directory_and_requires_exec() {
+        let entry = DesktopEntry::parse("[Desktop Entry]\nExec=app\nPath=/tmp\n");
+        let command = desktop_command(&entry).unwrap();
+        assert_eq!(command.get_current_dir(), Some(Path::new("/tmp")));
+
+        assert!(desktop_command(&DesktopEntry::parse("[Desktop Entry]\nName=App\n")).is_none());
+    }
+
+    #[test]
+    fn maps_snap_desktop_files_to_commands() {
+        assert_eq!(snap_command("firefox_firefox"), "firefox");
+        assert_eq!(snap_command("gimp_gimp-extra"), "gimp.gimp-extra");
+        assert_eq!(snap_command("plain"), "plain");
+    }
+}
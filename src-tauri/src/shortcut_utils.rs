@@ -1,12 +1,16 @@
 use serde::Serialize;
 use std::ffi::OsStr;
 use std::os::windows::ffi::OsStrExt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
 use windows::core::{Interface, PCWSTR};
+use windows::Win32::Foundation::HWND;
 use windows::Win32::System::Com::{
     CoCreateInstance, CoInitializeEx, CoUninitialize, IPersistFile, CLSCTX_INPROC_SERVER,
     COINIT_APARTMENTTHREADED, STGM,
 };
-use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
+use windows::Win32::UI::Shell::{IShellLinkW, ShellLink, SLR_NOUPDATE, SLR_NO_UI};
 
 #[derive(Serialize)]
 pub struct ShortcutInfo {
@@ -22,6 +26,97 @@ pub struct ShortcutInfo {
     pub icon_index: i32,
 }
 
+/// 一括解決 (`resolve_lnk_batch`) における1件分の結果。
+#[derive(Serialize)]
+pub struct BatchResolveItem {
+    /// 解決対象の.lnkファイルのパス
+    pub path: String,
+    /// 解決結果。失敗した場合は `None`
+    pub resolved: Option<ShortcutInfo>,
+    /// 失敗した場合のエラーメッセージ
+    pub error: Option<String>,
+}
+
+impl BatchResolveItem {
+    fn new(path: String, result: Result<ShortcutInfo, String>) -> Self {
+        let (resolved, error) = match result {
+            Ok(info) => (Some(info), None),
+            Err(e) => (None, Some(e)),
+        };
+        Self {
+            path,
+            resolved,
+            error,
+        }
+    }
+}
+
+/// 一括解決で使用するワーカースレッドの最大数
+const MAX_RESOLVE_WORKERS: usize = 8;
+
+/// 複数の.lnkファイルをワーカースレッドで並列に解決します。
+///
+/// 各ワーカーはCOMを一度だけ初期化し、共有キューからパスを取り出して処理します。
+/// 1件ごとの成否は `BatchResolveItem` に格納され、1件の失敗が他に影響することはありません。
+/// 戻り値は入力と同じ順序で返されます。
+///
+/// # 引数
+/// * `paths` - .lnkファイルのパス一覧
+/// * `on_progress` - 1件解決するごとに、結果と完了件数を引数に呼び出されるコールバック
+pub fn resolve_lnk_batch<F>(paths: Vec<String>, on_progress: F) -> Vec<BatchResolveItem>
+where
+    F: Fn(&BatchResolveItem, usize) + Sync,
+{
+    let total = paths.len();
+    let workers = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(MAX_RESOLVE_WORKERS)
+        .min(total);
+
+    let next = AtomicUsize::new(0);
+    let completed = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<BatchResolveItem>>> =
+        Mutex::new((0..total).map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                let _com = ComGuard::init();
+                loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    if index >= total {
+                        break;
+                    }
+
+                    let path = paths[index].clone();
+                    let result = unsafe { resolve_lnk_initialized(&path) };
+                    let item = BatchResolveItem::new(path, result);
+
+                    let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                    on_progress(&item, done);
+
+                    if let Ok(mut guard) = results.lock() {
+                        guard[index] = Some(item);
+                    }
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .zip(paths)
+        .map(|(item, path)| {
+            item.unwrap_or_else(|| {
+                BatchResolveItem::new(path, Err("Shortcut was not resolved".to_string()))
+            })
+        })
+        .collect()
+}
+
 /// Windowsのショートカット(.lnk)ファイルの情報を解決・取得します。
 ///
 /// COMインターフェース(`IShellLinkW`)を使用して、リンク先、引数、作業ディレクトリを取得します。
@@ -29,71 +124,137 @@ pub struct ShortcutInfo {
 /// # 引数
 /// * `path` - .lnkファイルのパス
 pub fn resolve_lnk(path: &str) -> Result<ShortcutInfo, String> {
+    let _com = ComGuard::init();
+    unsafe { resolve_lnk_initialized(path) }
+}
+
+/// 現在のスレッドでCOMを初期化し、ドロップ時に解放するガード。
+///
+/// 複数のショートカットを解決する場合は、スレッドごとに一度だけ生成して使い回します。
+/// 初期化に失敗した場合（別モードで初期化済みなど）は `CoUninitialize` を呼びません。
+pub struct ComGuard {
+    initialized: bool,
+}
+
+impl ComGuard {
+    pub fn init() -> Self {
+        let initialized = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok() };
+        ComGuard { initialized }
+    }
+}
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        if self.initialized {
+            unsafe {
+                CoUninitialize();
+            }
+        }
+    }
+}
+
+/// COMが初期化済みのスレッドで.lnkファイルを解決します。
+///
+/// # Safety
+/// 呼び出し元のスレッドで `ComGuard` が保持されている必要があります。
+pub unsafe fn resolve_lnk_initialized(path: &str) -> Result<ShortcutInfo, String> {
+    let shell_link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+        .map_err(|e| format!("Failed to create ShellLink instance: {}", e))?;
+
+    let persist_file: IPersistFile = shell_link
+        .cast()
+        .map_err(|e| format!("Failed to cast to IPersistFile: {}", e))?;
+
+    let wide_path: Vec<u16> = OsStr::new(path)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    // Use STGM(0) if STGM is a struct, or default.
+    // In windows 0.58, STGM is likely a struct around u32.
+    // 0 is STGM_READ (implied).
+    persist_file
+        .Load(PCWSTR(wide_path.as_ptr()), STGM(0))
+        .map_err(|e| format!("Failed to load link file: {}", e))?;
+
+    // Get Target Path
+    let mut target_buffer = [0u16; 520];
+    // GetPath expects *mut WIN32_FIND_DATAW, and u32 flags.
+    let _ = shell_link.GetPath(&mut target_buffer, std::ptr::null_mut(), 0);
+    let target = String::from_utf16_lossy(&target_buffer)
+        .trim_matches(char::from(0))
+        .to_string();
+
+    // Get Arguments
+    let mut args_buffer = [0u16; 1024];
+    let _ = shell_link.GetArguments(&mut args_buffer);
+    let arguments = String::from_utf16_lossy(&args_buffer)
+        .trim_matches(char::from(0))
+        .to_string();
+
+    // Get Working Directory
+    let mut cwd_buffer = [0u16; 520];
+    let _ = shell_link.GetWorkingDirectory(&mut cwd_buffer);
+    let working_dir = String::from_utf16_lossy(&cwd_buffer)
+        .trim_matches(char::from(0))
+        .to_string();
+
+    // Get Icon Location
+    let mut icon_path_buffer = [0u16; 520];
+    let mut icon_index: i32 = 0;
+    let _ = shell_link.GetIconLocation(&mut icon_path_buffer, &mut icon_index);
+    let icon_path = String::from_utf16_lossy(&icon_path_buffer)
+        .trim_matches(char::from(0))
+        .to_string();
+
+    Ok(ShortcutInfo {
+        target,
+        arguments,
+        working_dir,
+        icon_path,
+        icon_index,
+    })
+}
+
+/// リンク追跡の最大待ち時間（ミリ秒）
+const TRACKING_TIMEOUT_MS: u32 = 3000;
+
+/// リンク追跡を使用して、移動したショートカットのリンク先を探します。
+///
+/// `IShellLinkW::Resolve` を UI なし・保存なしで呼び出します。これにより、.lnk に記録された
+/// 相対パスや分散リンク追跡 (Distributed Link Tracking) のデータを使って新しい場所が検索されます。
+/// .lnk ファイル自体は更新しません。
+///
+/// # 戻り値
+/// 解決後のリンク先パス（見つからない場合は元のリンク先と同じになることがあります）
+pub fn track_lnk_target(path: &str) -> Result<String, String> {
+    let _com = ComGuard::init();
     unsafe {
-        // Initialize COM library
-        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
-
-        let result = (|| -> Result<ShortcutInfo, String> {
-            let shell_link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
-                .map_err(|e| format!("Failed to create ShellLink instance: {}", e))?;
-
-            let persist_file: IPersistFile = shell_link
-                .cast()
-                .map_err(|e| format!("Failed to cast to IPersistFile: {}", e))?;
-
-            let wide_path: Vec<u16> = OsStr::new(path)
-                .encode_wide()
-                .chain(std::iter::once(0))
-                .collect();
-
-            // Use STGM(0) if STGM is a struct, or default.
-            // In windows 0.58, STGM is likely a struct around u32.
-            // 0 is STGM_READ (implied).
-            persist_file
-                .Load(PCWSTR(wide_path.as_ptr()), STGM(0))
-                .map_err(|e| format!("Failed to load link file: {}", e))?;
-
-            // Get Target Path
-            let mut target_buffer = [0u16; 520];
-            // GetPath expects *mut WIN32_FIND_DATAW, and u32 flags.
-            let _ = shell_link.GetPath(&mut target_buffer, std::ptr::null_mut(), 0);
-            let target = String::from_utf16_lossy(&target_buffer)
-                .trim_matches(char::from(0))
-                .to_string();
-
-            // Get Arguments
-            let mut args_buffer = [0u16; 1024];
-            let _ = shell_link.GetArguments(&mut args_buffer);
-            let arguments = String::from_utf16_lossy(&args_buffer)
-                .trim_matches(char::from(0))
-                .to_string();
-
-            // Get Working Directory
-            let mut cwd_buffer = [0u16; 520];
-            let _ = shell_link.GetWorkingDirectory(&mut cwd_buffer);
-            let working_dir = String::from_utf16_lossy(&cwd_buffer)
-                .trim_matches(char::from(0))
-                .to_string();
-
-            // Get Icon Location
-            let mut icon_path_buffer = [0u16; 520];
-            let mut icon_index: i32 = 0;
-            let _ = shell_link.GetIconLocation(&mut icon_path_buffer, &mut icon_index);
-            let icon_path = String::from_utf16_lossy(&icon_path_buffer)
-                .trim_matches(char::from(0))
-                .to_string();
-
-            Ok(ShortcutInfo {
-                target,
-                arguments,
-                working_dir,
-                icon_path,
-                icon_index,
-            })
-        })();
+        let shell_link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| format!("Failed to create ShellLink instance: {}", e))?;
+
+        let persist_file: IPersistFile = shell_link
+            .cast()
+            .map_err(|e| format!("Failed to cast to IPersistFile: {}", e))?;
+
+        let wide_path: Vec<u16> = OsStr::new(path)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+        persist_file
+            .Load(PCWSTR(wide_path.as_ptr()), STGM(0))
+            .map_err(|e| format!("Failed to load link file: {}", e))?;
 
-        CoUninitialize();
+        // SLR_NO_UI 指定時は上位ワードがタイムアウト値になる
+        let flags = (SLR_NO_UI.0 | SLR_NOUPDATE.0) as u32 | (TRACKING_TIMEOUT_MS << 16);
+        shell_link
+            .Resolve(HWND(std::ptr::null_mut()), flags)
+            .map_err(|e| format!("Failed to resolve link: {}", e))?;
 
-        result
+        let mut target_buffer = [0u16; 520];
+        let _ = shell_link.GetPath(&mut target_buffer, std::ptr::null_mut(), 0);
+        Ok(String::from_utf16_lossy(&target_buffer)
+            .trim_matches(char::from(0))
+            .to_string())
     }
 }
@@ -1,22 +1,26 @@
-use std::sync::{Arc, Mutex};
-use std::thread;
 use std::time::Duration;
 use sysinfo::System;
 use tauri::{AppHandle, Emitter};
 
+use crate::background_service::{BackgroundService, ServiceStatus};
+
+/// システム情報を取得する既定の間隔
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+/// 取得間隔の下限（CPU使用率の計測には一定の間隔が必要です）
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+
 /// システムモニタリング機能を提供する構造体。
 /// CPU使用率とメモリ使用状況を定期的に取得し、フロントエンドにイベントを発行します。
 pub struct SystemMonitor {
-    /// 監視スレッドが実行中かどうかを管理するフラグ。
-    /// スレッドセーフにするために Arc<Mutex<bool>> でラップしています。
-    running: Arc<Mutex<bool>>,
+    /// 監視スレッド
+    service: BackgroundService,
 }
 
 impl SystemMonitor {
     /// SystemMonitorの新しいインスタンスを作成します。
     pub fn new() -> Self {
         Self {
-            running: Arc::new(Mutex::new(false)),
+            service: BackgroundService::new("system-monitor", DEFAULT_INTERVAL),
         }
     }
 
@@ -24,67 +28,57 @@ impl SystemMonitor {
     ///
     /// # 引数
     /// * `app_handle` - TauriのAppHandle。イベント発行に使用します。
+    /// * `interval` - 取得間隔（省略時は1秒）。実行中の場合も次の取得から反映されます
     ///
     /// 既に実行中の場合は何もしません。新しいスレッドを立ち上げ、
-    /// 1秒ごとにシステム情報を取得して `system-stats` イベントを発行します。
-    pub fn start(&self, app_handle: AppHandle) {
-        let running = self.running.clone();
-        let mut run_lock = running.lock().unwrap();
-        if *run_lock {
-            return;
-        }
-        *run_lock = true;
-        drop(run_lock);
-
-        thread::spawn(move || {
-            // sysinfoのSystemオブジェクトを初期化
-            let mut sys = System::new_all();
+    /// 取得間隔ごとにシステム情報を取得して `system-stats` イベントを発行します。
+    pub fn start(&self, app_handle: AppHandle, interval: Option<Duration>) -> Result<(), String> {
+        self.service
+            .set_interval(interval.unwrap_or(DEFAULT_INTERVAL).max(MIN_INTERVAL));
 
-            loop {
-                // 実行フラグを確認
-                {
-                    let lock = running.lock().unwrap();
-                    if !*lock {
-                        break;
-                    }
-                }
+        // sysinfoのSystemオブジェクトは監視スレッドで初期化する
+        let mut sys: Option<System> = None;
+        self.service.start(move |_| {
+            let sys = sys.get_or_insert_with(System::new_all);
 
-                // CPUとメモリ情報を更新
-                sys.refresh_cpu_all();
-                sys.refresh_memory();
+            // CPUとメモリ情報を更新
+            sys.refresh_cpu_all();
+            sys.refresh_memory();
 
-                let cpu_usage = sys.global_cpu_usage();
-                let total_memory = sys.total_memory();
-                let used_memory = sys.used_memory();
+            let cpu_usage = sys.global_cpu_usage();
+            let total_memory = sys.total_memory();
+            let used_memory = sys.used_memory();
 
-                // メモリ使用率を計算（ゼロ除算防止）
-                let memory_percentage = if total_memory > 0 {
-                    (used_memory as f64 / total_memory as f64) * 100.0
-                } else {
-                    0.0
-                };
+            // メモリ使用率を計算（ゼロ除算防止）
+            let memory_percentage = if total_memory > 0 {
+                (used_memory as f64 / total_memory as f64) * 100.0
+            } else {
+                0.0
+            };
 
-                // フロントエンドにイベントを発行
-                let _ = app_handle.emit(
-                    "system-stats",
-                    serde_json::json!({
-                        "cpu": cpu_usage,
-                        "memory": memory_percentage,
-                        "memory_used": used_memory,
-                        "memory_total": total_memory
-                    }),
-                );
-
-                // 1秒待機
-                thread::sleep(Duration::from_secs(1));
-            }
-        });
+            // フロントエンドにイベントを発行
+            let _ = app_handle.emit(
+                "system-stats",
+                serde_json::json!({
+                    "cpu": cpu_usage,
+                    "memory": memory_percentage,
+                    "memory_used": used_memory,
+                    "memory_total": total_memory
+                }),
+            );
+            Ok(())
+        })?;
+        Ok(())
     }
 
     /// 監視を停止します。
-    /// 実行フラグをfalseに設定し、監視スレッドをループから抜けさせて終了させます。
+    /// 待機中の監視スレッドをすぐに起こし、終了するまで待ちます。
     pub fn stop(&self) {
-        let mut lock = self.running.lock().unwrap();
-        *lock = false;
+        self.service.stop();
+    }
+
+    /// 監視スレッドの状態を返します。
+    pub fn status(&self) -> ServiceStatus {
+        self.service.status()
     }
 }
@@ -0,0 +1,375 @@
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app_index::{self, IndexedApp};
+
+/// 修正候補を見つけた方法。
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairMethod {
+    /// .lnk の相対パス・分散リンク追跡データによる解決
+    LinkTracking,
+    /// バージョン番号付きの兄弟ディレクトリ（`app-1.2.3` → `app-1.2.4`）
+    SiblingVersion,
+    /// インストール済みアプリの索引での名前一致
+    AppIndex,
+}
+
+/// 壊れたターゲットに対する修正案。ユーザーが承認した場合にのみセルへ反映されます。
+#[derive(Debug, Serialize)]
+pub struct RepairProposal {
+    /// 壊れているターゲット（.lnk の場合はそのリンク先）
+    pub original: String,
+    /// 提案する新しいターゲット
+    pub proposed: String,
+    /// 候補を見つけた方法
+    pub method: RepairMethod,
+}
+
+/// 壊れたターゲットの修正案を探します。
+///
+/// 以下の順に試行し、最初に見つかった候補を返します。
+/// 1. `path` が .lnk の場合、リンク追跡（Windowsのみ）
+/// 2. バージョン番号付きディレクトリの兄弟から、同じ相対パスのファイルを探す
+/// 3. インストール済みアプリの索引から、ファイル名・タイトルで探す
+///
+/// `index` は索引のキャッシュです。`None` の場合、手順3に到達した時点で構築されます。
+pub fn propose_repair(
+    path: &str,
+    title: &str,
+    index: &mut Option<Vec<IndexedApp>>,
+) -> Option<RepairProposal> {
+    let original = broken_target(path)?;
+
+    #[cfg(target_os = "windows")]
+    if path.to_lowercase().ends_with(".lnk") {
+        if let Ok(tracked) = crate::shortcut_utils::track_lnk_target(path) {
+            if !tracked.is_empty()
+                && !tracked.eq_ignore_ascii_case(&original)
+                && Path::new(&tracked).exists()
+            {
+                return Some(RepairProposal {
+                    original,
+                    proposed: tracked,
+                    method: RepairMethod::LinkTracking,
+                });
+            }
+        }
+    }
+
+    if let Some(sibling) = find_versioned_sibling(Path::new(&original)) {
+        return Some(RepairProposal {
+            original,
+            proposed: sibling.to_string_lossy().to_string(),
+            method: RepairMethod::SiblingVersion,
+        });
+    }
+
+    let file_name = Path::new(&original)
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let index = index.get_or_insert_with(app_index::build_app_index);
+    app_index::find_by_name(index, &[&file_name, title]).map(|app| RepairProposal {
+        original: original.clone(),
+        proposed: app.target.clone(),
+        method: RepairMethod::AppIndex,
+    })
+}
+
+/// 修復対象となる壊れたターゲットを返します（壊れていない場合は `None`）。
+///
+/// `path` 自体が存在しなければ `path` を、.lnk が存在してリンク先が存在しなければリンク先を返します。
+fn broken_target(path: &str) -> Option<String> {
+    if !Path::new(path).exists() {
+        return Some(path.to_string());
+    }
+
+    #[cfg(target_os = "windows")]
+    if path.to_lowercase().ends_with(".lnk") {
+        let info = crate::shortcut_utils::resolve_lnk(path).ok()?;
+        if !info.target.is_empty() && !Path::new(&info.target).exists() {
+            return Some(info.target);
+        }
+    }
+
+    None
+}
+
+/// パスの祖先にあるバージョン番号付きディレクトリを兄弟ディレクトリに置き換えて、
+/// 同じ相対パスのファイルが存在するものを探します。
+///
+/// 例: `...\Discord\app-1.0.9\Discord.exe` が存在しない場合、
+/// `...\Discord\app-1.0.10\Discord.exe` のように同じ接頭辞を持つ兄弟を新しい順に確認します。
+/// 元のバージョンより新しい兄弟のみを候補とします。
+pub fn find_versioned_sibling(missing: &Path) -> Option<PathBuf> {
+    let mut remainder: Vec<&std::ffi::OsStr> = Vec::new();
+    let mut current = missing;
+
+    while let (Some(parent), Some(name)) = (current.parent(), current.file_name()) {
+        let name_str = name.to_string_lossy();
+        if let Some(version) = VersionedName::parse(&name_str) {
+            let mut siblings: Vec<(VersionedName, PathBuf)> = fs::read_dir(parent)
+                .into_iter()
+                .flatten()
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let sibling = VersionedName::parse(&e.file_name().to_string_lossy())?;
+                    (sibling.same_series(&version)
+                        && sibling.cmp_version(&version) == Ordering::Greater)
+                        .then(|| (sibling, e.path()))
+                })
+                .collect();
+            siblings.sort_by(|a, b| b.0.cmp_version(&a.0));
+
+            for (_, sibling) in siblings {
+                let candidate = remainder
+                    .iter()
+                    .rev()
+                    .fold(sibling, |acc, part| acc.join(part));
+                if candidate.exists() {
+                    return Some(candidate);
+                }
+            }
+        }
+
+        remainder.push(name);
+        current = parent;
+    }
+
+    None
+}
+
+/// バージョン番号を含む名前を「接頭辞・バージョン・ビルド番号・接尾辞」に分解したもの。
+#[derive(Debug)]
+struct VersionedName {
+    prefix: String,
+    version: Vec<u64>,
+    /// 接尾辞の先頭にあるビルド番号（`+8`、`-b12`、`_3` など）
+    build: Option<u64>,
+    /// ビルド番号を `#` に置き換えた接尾辞
+    suffix: String,
+}
+
+/// ビルド番号の前に置かれる語（`-b12`、`+build.3` の `b`、`build` など）
+const BUILD_MARKERS: [&str; 5] = ["", "b", "build", "r", "rev"];
+
+impl VersionedName {
+    /// 区切り文字（`-`、`_`、`.`、空白、または区切り文字に続く `v`）の直後にある
+    /// `数字.数字(.数字)*` をバージョンとして分解します。
+    ///
+    /// `app-1.2.3` → (`app-`, [1, 2, 3], なし, ``)、`jdk-17.0.2+8` → (`jdk-`, [17, 0, 2], 8, `+#`)。
+    /// `x64` や `Office16` のように区切りがないもの、数字が1つだけのものはバージョンとみなしません。
+    fn parse(name: &str) -> Option<Self> {
+        let mut from = 0;
+        while let Some(offset) = name[from..].find(|c: char| c.is_ascii_digit()) {
+            let start = from + offset;
+            let len = name[start..]
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(name.len() - start);
+            let version_str = name[start..start + len].trim_end_matches('.');
+
+            if Self::ends_with_separator(&name[..start]) {
+                let version: Option<Vec<u64>> = version_str
+                    .split('.')
+                    .map(|part| part.parse().ok())
+                    .collect();
+                if let Some(version) = version.filter(|v| v.len() >= 2) {
+                    let (build, suffix) =
+                        Self::split_build(&name[start + version_str.len()..].to_lowercase());
+                    return Some(Self {
+                        prefix: name[..start].to_lowercase(),
+                        version,
+                        build,
+                        suffix,
+                    });
+                }
+            }
+
+            from = start + len;
+        }
+
+        None
+    }
+
+    /// 接頭辞がバージョンの直前の区切りとして妥当かどうか。
+    fn ends_with_separator(prefix: &str) -> bool {
+        let is_separator = |c: char| matches!(c, '-' | '_' | '.' | ' ');
+        let mut chars = prefix.chars().rev();
+        match chars.next() {
+            None => true,
+            Some('v' | 'V') => chars.next().is_none_or(is_separator),
+            Some(c) => is_separator(c),
+        }
+    }
+
+    /// 接尾辞の先頭にあるビルド番号を取り出し、番号を `#` に置き換えた接尾辞とともに返します。
+    fn split_build(suffix: &str) -> (Option<u64>, String) {
+        let not_build = || (None, suffix.to_string());
+
+        let Some(marker) = suffix
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '+' | '-' | '_'))
+        else {
+            return not_build();
+        };
+        let rest = &suffix[1..];
+        let letters_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let letters = &rest[..letters_len];
+        if !BUILD_MARKERS.contains(&letters) {
+            return not_build();
+        }
+
+        let rest = rest[letters_len..].trim_start_matches('.');
+        let digits_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let tail = &rest[digits_len..];
+        if digits_len == 0 || tail.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+            return not_build();
+        }
+
+        match rest[..digits_len].parse() {
+            Ok(build) => (Some(build), format!("{}{}#{}", marker, letters, tail)),
+            Err(_) => not_build(),
+        }
+    }
+
+    /// 接頭辞・接尾辞が同じ（同じ製品の別バージョン）かどうか。
+    fn same_series(&self, other: &Self) -> bool {
+        self.prefix == other.prefix && self.suffix == other.suffix
+    }
+
+    fn cmp_version(&self, other: &Self) -> Ordering {
+        self.version
+            .cmp(&other.version)
+            .then(self.build.cmp(&other.build))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(name: &str) -> (String, Vec<u64>, Option<u64>, String) {
+        let v = VersionedName::parse(name).expect("should parse");
+        (v.prefix, v.version, v.build, v.suffix)
+    }
+
+    /// テストごとに空の一時ディレクトリを作成します。
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "hexa-target-repair-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"").unwrap();
+    }
+
+    #[test]
+    fn parses_separated_versions() {
+        assert_eq!(
+            parsed("app-1.2.3"),
+            ("app-".into(), vec![1, 2, 3], None, "".into())
+        );
+        assert_eq!(
+            parsed("Python 3.12"),
+            ("python ".into(), vec![3, 12], None, "".into())
+        );
+        assert_eq!(
+            parsed("tool_v2.0"),
+            ("tool_v".into(), vec![2, 0], None, "".into())
+        );
+        assert_eq!(parsed("1.5.0"), ("".into(), vec![1, 5, 0], None, "".into()));
+    }
+
+    #[test]
+    fn rejects_unseparated_and_single_numbers() {
+        assert!(VersionedName::parse("x64").is_none());
+        assert!(VersionedName::parse("x86").is_none());
+        assert!(VersionedName::parse("Office16").is_none());
+        assert!(VersionedName::parse("app-2").is_none());
+        assert!(VersionedName::parse("Program Files").is_none());
+        assert!(VersionedName::parse("python3.12").is_none());
+    }
+
+    #[test]
+    fn arch_suffixes_are_separate_series() {
+        let x64 = VersionedName::parse("app-1.2-x64").unwrap();
+        let x86 = VersionedName::parse("app-1.3-x86").unwrap();
+        assert_eq!(x64.build, None);
+        assert!(!x64.same_series(&x86));
+        assert!(x64.same_series(&VersionedName::parse("app-1.3-x64").unwrap()));
+    }
+
+    #[test]
+    fn build_suffixes_order_after_version() {
+        assert_eq!(
+            parsed("jdk-17.0.2+8"),
+            ("jdk-".into(), vec![17, 0, 2], Some(8), "+#".into())
+        );
+        assert_eq!(
+            parsed("tool-1.0-b12"),
+            ("tool-".into(), vec![1, 0], Some(12), "-b#".into())
+        );
+        assert_eq!(
+            parsed("tool-1.0_3"),
+            ("tool-".into(), vec![1, 0], Some(3), "_#".into())
+        );
+
+        let old = VersionedName::parse("jdk-17.0.2+8").unwrap();
+        let rebuilt = VersionedName::parse("jdk-17.0.2+9").unwrap();
+        let newer = VersionedName::parse("jdk-17.0.3+7").unwrap();
+        assert!(old.same_series(&rebuilt) && old.same_series(&newer));
+        assert_eq!(rebuilt.cmp_version(&old), Ordering::Greater);
+        assert_eq!(newer.cmp_version(&rebuilt), Ordering::Greater);
+    }
+
+    #[test]
+    fn finds_newest_sibling_with_same_file() {
+        let root = scratch_dir("newest");
+        touch(&root.join("app-1.0.10").join("App.exe"));
+        touch(&root.join("app-1.0.11").join("App.exe"));
+        fs::create_dir_all(root.join("app-1.0.12")).unwrap();
+
+        let missing = root.join("app-1.0.9").join("App.exe");
+        assert_eq!(
+            find_versioned_sibling(&missing),
+            Some(root.join("app-1.0.11").join("App.exe"))
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn ignores_older_siblings() {
+        let root = scratch_dir("older");
+        touch(&root.join("app-1.0.8").join("App.exe"));
+        touch(&root.join("app-0.9.0").join("App.exe"));
+
+        let missing = root.join("app-1.0.9").join("App.exe");
+        assert_eq!(find_versioned_sibling(&missing), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn ignores_unversioned_architecture_folders() {
+        let root = scratch_dir("arch");
+        touch(&root.join("x86").join("tool.exe"));
+
+        let missing = root.join("x64").join("tool.exe");
+        assert_eq!(find_versioned_sibling(&missing), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,291 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::background_service::lock;
+use crate::uwp_utils::{self, UwpApp};
+
+/// 一覧を保存するファイル名（`AppData/cache/` 直下）
+const CATALOG_FILE_NAME: &str = "uwp_apps.json";
+/// 保存した一覧をバックグラウンドで更新するまでの期間
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// 一覧を更新した際に発行するイベント名
+const UWP_APPS_UPDATED_EVENT: &str = "uwp-apps-updated";
+
+/// `uwp_apps.json` の内容。
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CatalogFile {
+    /// 一覧を取得した日時（UNIXエポックからのミリ秒）
+    refreshed_at: u64,
+    apps: Vec<UwpApp>,
+}
+
+/// インストールされているUWPアプリの一覧（カタログ）。
+///
+/// PowerShell での取得には数秒かかるため、取得した一覧をディスクに保存して起動時に読み込み、
+/// 一覧の要求時には保存済みの一覧をすぐに返します。
+/// 取得から `REFRESH_INTERVAL` 以上経過している場合はバックグラウンドで取得しなおし、
+/// 完了時に `uwp-apps-updated` イベントで新しい一覧を通知します。
+/// アイコンの抽出では、カタログに記録された各アプリのロゴ画像を使用します。
+pub struct UwpCatalog {
+    state: Mutex<CatalogState>,
+}
+
+struct CatalogState {
+    file: CatalogFile,
+    /// AUMID → ロゴ画像のパス
+    logos: HashMap<String, String>,
+    /// バックグラウンドでの取得中かどうか
+    refreshing: bool,
+}
+
+impl CatalogState {
+    fn set(&mut self, file: CatalogFile) {
+        self.logos = file
+            .apps
+            .iter()
+            .filter_map(|app| Some((app.aumid.clone(), app.logo.clone()?)))
+            .collect();
+        self.file = file;
+    }
+}
+
+/// バックグラウンドでの取得中であることを示すフラグを、取得の終了時（パニックを含む）に解除するガード。
+struct RefreshGuard<'a>(&'a UwpCatalog);
+
+impl Drop for RefreshGuard<'_> {
+    fn drop(&mut self) {
+        lock(&self.0.state).refreshing = false;
+    }
+}
+
+impl UwpCatalog {
+    /// ディスクに保存された一覧を読み込みます。保存されていない（読み込めない）場合は空の一覧です。
+    pub fn load(app_handle: &AppHandle) -> Self {
+        let file = catalog_path(app_handle)
+            .map(|path| read_catalog(&path))
+            .unwrap_or_default();
+        Self::from_file(file)
+    }
+
+    fn from_file(file: CatalogFile) -> Self {
+        let mut state = CatalogState {
+            file: CatalogFile::default(),
+            logos: HashMap::new(),
+            refreshing: false,
+        };
+        state.set(file);
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    /// アプリの一覧を返します。
+    ///
+    /// 一度も取得していない場合はその場で取得します。
+    /// 保存済みの一覧が古い場合は、それを返した上でバックグラウンドで取得しなおします。
+    pub fn apps(&self, app_handle: &AppHandle) -> Result<Vec<UwpApp>, String> {
+        let (refreshed_at, apps) = {
+            let state = lock(&self.state);
+            (state.file.refreshed_at, state.file.apps.clone())
+        };
+        if refreshed_at == 0 {
+            return self.refresh(app_handle);
+        }
+        if now_millis().saturating_sub(refreshed_at) >= REFRESH_INTERVAL.as_millis() as u64 {
+            self.refresh_in_background(app_handle);
+        }
+        Ok(apps)
+    }
+
+    /// アプリの一覧を取得しなおして保存し、`uwp-apps-updated` イベントで通知します。
+    pub fn refresh(&self, app_handle: &AppHandle) -> Result<Vec<UwpApp>, String> {
+        let apps = uwp_utils::get_installed_uwp_apps()?;
+        let file = CatalogFile {
+            refreshed_at: now_millis(),
+            apps: apps.clone(),
+        };
+
+        let saved = catalog_path(app_handle).and_then(|path| write_catalog(&path, &file));
+        if let Err(e) = saved {
+            eprintln!("Failed to save UWP app catalog: {}", e);
+        }
+        lock(&self.state).set(file);
+
+        let _ = app_handle.emit(UWP_APPS_UPDATED_EVENT, &apps);
+        Ok(apps)
+    }
+
+    /// バックグラウンドでアプリの一覧を取得しなおします。取得中の場合は何もしません。
+    fn refresh_in_background(&self, app_handle: &AppHandle) {
+        if !self.begin_refresh() {
+            return;
+        }
+
+        let app_handle = app_handle.clone();
+        thread::spawn(move || {
+            let catalog = app_handle.state::<UwpCatalog>();
+            let _refreshing = RefreshGuard(&catalog);
+            if let Err(e) = catalog.refresh(&app_handle) {
+                eprintln!("Failed to refresh UWP app catalog: {}", e);
+            }
+        });
+    }
+
+    /// 取得中のフラグを立てます。既に取得中の場合は `false` を返します。
+    ///
+    /// フラグは取得を行うスレッドが `RefreshGuard` で解除します。
+    fn begin_refresh(&self) -> bool {
+        let mut state = lock(&self.state);
+        if state.refreshing {
+            return false;
+        }
+        state.refreshing = true;
+        true
+    }
+
+    /// 指定したAUMIDのアプリのロゴ画像のパスを返します。カタログにない場合やロゴがない場合は `None` です。
+    pub fn logo_for(&self, aumid: &str) -> Option<String> {
+        lock(&self.state).logos.get(aumid).cloned()
+    }
+}
+
+/// 保存された一覧を読み込みます。読み込めない場合は空の一覧です。
+fn read_catalog(path: &Path) -> CatalogFile {
+    fs::read(path)
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// 一覧を保存します。
+///
+/// 書き込み中に終了しても壊れた一覧が残らないよう、一時ファイルに書き込んでから置き換えます。
+fn write_catalog(path: &Path, file: &CatalogFile) -> Result<(), String> {
+    let json = serde_json::to_vec(file).map_err(|e| e.to_string())?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).map_err(|e| e.to_string())?;
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        e.to_string()
+    })
+}
+
+/// 一覧を保存するファイルのパス（`AppData/cache/uwp_apps.json`）を返します。
+fn catalog_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let cache_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("cache");
+    if !cache_dir.exists() {
+        fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+    }
+    Ok(cache_dir.join(CATALOG_FILE_NAME))
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str, logo: Option<&str>) -> UwpApp {
+        UwpApp {
+            name: name.to_string(),
+            aumid: format!("{}!App", name),
+            logo: logo.map(str::to_string),
+        }
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("uwp-catalog-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn saves_and_loads_the_catalog() {
+        let dir = scratch_dir("load");
+        let path = dir.join(CATALOG_FILE_NAME);
+        let file = CatalogFile {
+            refreshed_at: 42,
+            apps: vec![app("Calc", Some("C:/calc.png")), app("NoLogo", None)],
+        };
+        write_catalog(&path, &file).unwrap();
+        assert!(!path.with_extension("json.tmp").exists());
+
+        let catalog = UwpCatalog::from_file(read_catalog(&path));
+        assert_eq!(lock(&catalog.state).file.refreshed_at, 42);
+        assert_eq!(lock(&catalog.state).file.apps.len(), 2);
+        assert_eq!(catalog.logo_for("Calc!App").as_deref(), Some("C:/calc.png"));
+        assert_eq!(catalog.logo_for("NoLogo!App"), None);
+        assert_eq!(catalog.logo_for("Missing!App"), None);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn replaces_logos_when_the_catalog_is_updated() {
+        let catalog = UwpCatalog::from_file(CatalogFile {
+            refreshed_at: 1,
+            apps: vec![app("Old", Some("old.png"))],
+        });
+        lock(&catalog.state).set(CatalogFile {
+            refreshed_at: 2,
+            apps: vec![app("New", Some("new.png"))],
+        });
+
+        assert_eq!(catalog.logo_for("Old!App"), None);
+        assert_eq!(catalog.logo_for("New!App").as_deref(), Some("new.png"));
+    }
+
+    #[test]
+    fn missing_or_corrupt_catalog_is_empty() {
+        let dir = scratch_dir("corrupt");
+        let path = dir.join(CATALOG_FILE_NAME);
+        assert_eq!(read_catalog(&path).refreshed_at, 0);
+
+        fs::write(&path, b"{\"refreshedAt\": 1, \"apps\": [").unwrap();
+        let file = read_catalog(&path);
+        assert_eq!(file.refreshed_at, 0);
+        assert!(file.apps.is_empty());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn refreshes_one_at_a_time() {
+        let catalog = UwpCatalog::from_file(CatalogFile::default());
+        assert!(catalog.begin_refresh());
+        assert!(!catalog.begin_refresh());
+
+        drop(RefreshGuard(&catalog));
+        assert!(catalog.begin_refresh());
+    }
+
+    #[test]
+    fn panicking_refresh_releases_the_flag() {
+        let catalog = UwpCatalog::from_file(CatalogFile::default());
+        assert!(catalog.begin_refresh());
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _refreshing = RefreshGuard(&catalog);
+            panic!("enumeration failed");
+        }));
+        assert!(result.is_err());
+        assert!(catalog.begin_refresh());
+    }
+}
@@ -0,0 +1,249 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// パッケージのマニフェストで指定されたアプリのロゴ。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestLogo {
+    /// パッケージのフォルダからの相対パス（例: `Assets\Square44x44Logo.png`）。
+    /// 実際のファイルは `scale-200` などの修飾子付きで置かれていることがあります
+    pub path: String,
+    /// 拡大率 100% のときのピクセルサイズ（`scale-N` の修飾子からサイズを求めるのに使用します）
+    pub base_size: u32,
+}
+
+/// `AppxManifest.xml` の内容から、指定したアプリのロゴを返します。
+///
+/// `Application` 要素の `VisualElements` の `Square44x44Logo`（スタートメニューのアプリ一覧のアイコン）を優先し、
+/// なければ `Square150x150Logo`、さらにパッケージの `Properties/Logo` を使用します。
+///
+/// # 引数
+/// * `manifest` - `AppxManifest.xml` の内容
+/// * `app_id` - AUMID の `!` 以降の部分（`Application` 要素の `Id`）
+pub fn app_logo(manifest: &str, app_id: &str) -> Option<ManifestLogo> {
+    let mut reader = Reader::from_str(manifest.trim_start_matches('\u{feff}'));
+    reader.config_mut().trim_text(true);
+
+    let mut path: Vec<Vec<u8>> = Vec::new();
+    let mut in_target_app = false;
+    let mut square44 = None;
+    let mut square150 = None;
+    let mut package_logo = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                let name = e.local_name().as_ref().to_vec();
+                if name == b"Application" {
+                    in_target_app = attribute(&e, "Id").is_some_and(|id| id == app_id);
+                }
+                if in_target_app && name == b"VisualElements" {
+                    square44 = attribute(&e, "Square44x44Logo");
+                    square150 = attribute(&e, "Square150x150Logo");
+                }
+                path.push(name);
+            }
+            Ok(Event::Empty(e))
+                if in_target_app && e.local_name().as_ref() == b"VisualElements" =>
+            {
+                square44 = attribute(&e, "Square44x44Logo");
+                square150 = attribute(&e, "Square150x150Logo");
+            }
+            Ok(Event::Text(text)) => {
+                let is_package_logo = path.len() >= 2
+                    && path[path.len() - 1] == b"Logo"
+                    && path[path.len() - 2] == b"Properties";
+                if is_package_logo {
+                    package_logo = text.unescape().ok().map(|logo| logo.to_string());
+                }
+            }
+            Ok(Event::End(e)) => {
+                if e.local_name().as_ref() == b"Application" {
+                    in_target_app = false;
+                }
+                path.pop();
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    let logo = |path: Option<String>, base_size: u32| {
+        path.filter(|p| !p.is_empty())
+            .map(|path| ManifestLogo { path, base_size })
+    };
+    logo(square44, 44)
+        .or_else(|| logo(square150, 150))
+        .or_else(|| logo(package_logo, 50))
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == name.as_bytes())
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.to_string())
+}
+
+/// ロゴのフォルダ内のファイル名から、`size` ピクセルでの表示に最適なファイルを選びます。
+///
+/// ロゴは `Square44x44Logo.scale-200.png` や `Square44x44Logo.targetsize-48_altform-unplated.png` のように
+/// 修飾子付きのファイル名で置かれているため、修飾子からピクセルサイズを求め、
+/// `size` 以上で最も小さいもの（なければ最も大きいもの）を選びます。
+/// 同じ条件では、タイルの背景色を前提としない `altform-unplated` を優先します。
+/// ハイコントラスト用（`contrast-*`）と明るいテーマ用（`altform-lightunplated`）のファイルは使用しません。
+///
+/// # 引数
+/// * `logo` - マニフェストで指定されたロゴ
+/// * `files` - ロゴのフォルダ内のファイル名
+/// * `size` - 表示するピクセルサイズ
+pub fn choose_logo_asset(logo: &ManifestLogo, files: &[String], size: u32) -> Option<String> {
+    let logo_name = logo.path.rsplit(['\\', '/']).next()?.to_lowercase();
+    let (stem, extension) = logo_name.rsplit_once('.')?;
+
+    files
+        .iter()
+        .filter_map(|file| {
+            let lower = file.to_lowercase();
+            let rest = lower.strip_prefix(stem)?;
+            let qualifiers = rest.strip_suffix(extension)?.strip_suffix('.')?;
+            let (pixels, unplated) = if qualifiers.is_empty() {
+                (logo.base_size, false)
+            } else {
+                parse_qualifiers(qualifiers.strip_prefix('.')?, logo.base_size)?
+            };
+            Some((file, pixels, unplated))
+        })
+        .min_by_key(|&(_, pixels, unplated)| (pixels < size, !unplated, pixels.abs_diff(size)))
+        .map(|(file, _, _)| file.clone())
+}
+
+/// `scale-200` や `targetsize-48_altform-unplated` のような修飾子から、
+/// ピクセルサイズと `altform-unplated` かどうかを返します。使用しないファイルの場合は `None` です。
+fn parse_qualifiers(qualifiers: &str, base_size: u32) -> Option<(u32, bool)> {
+    let mut pixels = base_size;
+    let mut unplated = false;
+    for qualifier in qualifiers.split('_') {
+        let (name, value) = qualifier.split_once('-')?;
+        match name {
+            "targetsize" => pixels = value.parse().ok()?,
+            "scale" => pixels = base_size * value.parse::<u32>().ok()? / 100,
+            "altform" if value == "unplated" => unplated = true,
+            "altform" | "contrast" => return None,
+            _ => {}
+        }
+    }
+    Some((pixels, unplated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Package xmlns="http://schemas.microsoft.com/appx/manifest/foundation/windows10"
+         xmlns:uap="http://schemas.microsoft.com/appx/manifest/uap/windows10">
+  <Identity Name="Microsoft.WindowsCalculator" Publisher="CN=Microsoft Corporation" Version="11.2307.4.0" />
+  <Properties>
+    <DisplayName>ms-resource:AppStoreName</DisplayName>
+    <Logo>Assets\CalculatorStoreLogo.png</Logo>
+  </Properties>
+  <Applications>
+    <Application Id="App" Executable="CalculatorApp.exe" EntryPoint="CalculatorApp.App">
+      <uap:VisualElements DisplayName="ms-resource:AppName" Square150x150Logo="Assets\CalculatorMedTile.png"
+                          Square44x44Logo="Assets\CalculatorAppList.png" BackgroundColor="transparent">
+        <uap:DefaultTile Wide310x150Logo="Assets\CalculatorWideTile.png" />
+      </uap:VisualElements>
+    </Application>
+    <Application Id="Widget" Executable="Widget.exe">
+      <uap:VisualElements DisplayName="Widget" Square150x150Logo="Assets\WidgetMedTile.png" />
+    </Application>
+  </Applications>
+</Package>"#;
+
+    fn files(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn reads_square44_logo_of_application() {
+        assert_eq!(
+            app_logo(MANIFEST, "App"),
+            Some(ManifestLogo {
+                path: r"Assets\CalculatorAppList.png".to_string(),
+                base_size: 44,
+            })
+        );
+    }
+
+    #[test]
+    fn falls_back_to_square150_logo() {
+        assert_eq!(
+            app_logo(MANIFEST, "Widget"),
+            Some(ManifestLogo {
+                path: r"Assets\WidgetMedTile.png".to_string(),
+                base_size: 150,
+            })
+        );
+    }
+
+    #[test]
+    fn falls_back_to_package_logo() {
+        let manifest =
+            "\u{feff}<Package><Properties><Logo>Images\\StoreLogo.png</Logo></Properties>\
+            <Applications><Application Id=\"App\" /></Applications></Package>";
+        assert_eq!(
+            app_logo(manifest, "App"),
+            Some(ManifestLogo {
+                path: r"Images\StoreLogo.png".to_string(),
+                base_size: 50,
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_application_has_no_visual_logo() {
+        assert_eq!(
+            app_logo(MANIFEST, "Missing").map(|logo| logo.path),
+            Some(r"Assets\CalculatorStoreLogo.png".to_string())
+        );
+        assert_eq!(app_logo("not xml", "App"), None);
+    }
+
+    #[test]
+    fn prefers_unplated_target_size_covering_request() {
+        let logo = app_logo(MANIFEST, "App").unwrap();
+        let assets = files(&[
+            "CalculatorAppList.scale-100.png",
+            "CalculatorAppList.scale-200.png",
+            "CalculatorAppList.targetsize-32.png",
+            "CalculatorAppList.targetsize-32_altform-unplated.png",
+            "CalculatorAppList.targetsize-48_altform-unplated.png",
+            "CalculatorAppList.targetsize-32_altform-lightunplated.png",
+            "CalculatorAppList.contrast-black_scale-400.png",
+            "CalculatorMedTile.scale-400.png",
+        ]);
+        assert_eq!(
+            choose_logo_asset(&logo, &assets, 32).as_deref(),
+            Some("CalculatorAppList.targetsize-32_altform-unplated.png")
+        );
+        assert_eq!(
+            choose_logo_asset(&logo, &assets, 64).as_deref(),
+            Some("CalculatorAppList.scale-200.png")
+        );
+    }
+
+    #[test]
+    fn uses_largest_asset_when_none_is_large_enough() {
+        let logo = ManifestLogo {
+            path: "Assets/StoreLogo.png".to_string(),
+            base_size: 50,
+        };
+        let assets = files(&["storelogo.png", "StoreLogo.scale-150.png", "Other.png"]);
+        assert_eq!(
+            choose_logo_asset(&logo, &assets, 256).as_deref(),
+            Some("StoreLogo.scale-150.png")
+        );
+        assert_eq!(choose_logo_asset(&logo, &files(&["Other.png"]), 32), None);
+    }
+}
@@ -1,76 +1,128 @@
 use serde::{Deserialize, Serialize};
+#[cfg(target_os = "windows")]
+use std::fs;
+#[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
+#[cfg(target_os = "windows")]
+use std::path::Path;
+#[cfg(target_os = "windows")]
 use std::process::Command;
+#[cfg(target_os = "windows")]
+use std::time::Duration;
 
+#[cfg(target_os = "windows")]
+use crate::helper_process;
+#[cfg(not(target_os = "windows"))]
+use crate::sandbox_apps;
+#[cfg(target_os = "windows")]
+use crate::uwp_manifest;
+
+#[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
+/// アプリ一覧を取得する PowerShell の実行時間の上限
+#[cfg(target_os = "windows")]
+const POWERSHELL_TIMEOUT: Duration = Duration::from_secs(30);
+/// ロゴのファイルを選ぶ際の表示サイズ（アイコンキャッシュで要求サイズに縮小されます）
+#[cfg(target_os = "windows")]
+const LOGO_SIZE: u32 = 256;
 
 /// UWP(Universal Windows Platform)アプリの情報を表す構造体。
-#[derive(Debug, Serialize, Deserialize)]
+///
+/// Windows以外のOSでは、Flatpak / Snap でインストールされたアプリを表します。
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UwpApp {
     /// アプリケーションの表示名
     pub name: String,
     /// アプリケーションを一意に識別するID (Application User Model ID)。
     /// 起動時に使用されます。
+    /// Flatpak は `flatpak:<デスクトップファイルID>`、Snap は `snap:<コマンド名>` です。
     pub aumid: String,
+    /// パッケージのロゴ画像のパス。パッケージのアプリでない場合や、ロゴが見つからない場合は `None`
+    #[serde(default)]
+    pub logo: Option<String>,
 }
 
 /// PowerShellからの出力をパースするための中間構造体
+#[cfg(target_os = "windows")]
 #[derive(Deserialize)]
 struct PsApp {
     #[serde(rename = "Name")]
     name: String,
     #[serde(rename = "AppID")]
     app_id: String,
+    /// パッケージのインストール先（パッケージのアプリでない場合は `null`）
+    #[serde(rename = "InstallLocation", default)]
+    install_location: Option<String>,
 }
 
 /// PowerShellを使用して、インストールされているUWPアプリの一覧を取得します。
 ///
 /// `Get-StartApps` コマンドレットを実行し、結果をJSONとしてパースします。
+/// PowerShell が `POWERSHELL_TIMEOUT` 以内に終了しない場合はエラーを返します。
+/// パッケージのアプリは `Get-AppxPackage` でインストール先を求め、マニフェストからロゴを取得します。
+#[cfg(target_os = "windows")]
 pub fn get_installed_uwp_apps() -> Result<Vec<UwpApp>, String> {
-    // Get-StartApps returns an array of objects with Name and AppID
-    let script = "Get-StartApps | Select-Object Name, AppID | ConvertTo-Json -Compress";
-
-    let output = Command::new("powershell")
-        .args(&["-NoProfile", "-Command", script])
-        .creation_flags(CREATE_NO_WINDOW)
-        .output()
-        .map_err(|e| format!("Failed to execute PowerShell: {}", e))?;
+    // AUMID は "<PackageFamilyName>!<ApplicationId>" の形式
+    // 出力をリダイレクトした場合の既定はOEMコードページのため、アプリ名が文字化けしないようUTF-8で出力する
+    let script = "[Console]::OutputEncoding = [System.Text.Encoding]::UTF8; \
+        $locations = @{}; \
+        Get-AppxPackage | ForEach-Object { $locations[$_.PackageFamilyName] = $_.InstallLocation }; \
+        Get-StartApps | ForEach-Object { [PSCustomObject]@{ \
+            Name = $_.Name; AppID = $_.AppID; \
+            InstallLocation = $locations[($_.AppID -split '!')[0]] } } \
+        | ConvertTo-Json -Compress";
 
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let trimmed = stdout.trim();
-    if trimmed.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    // PowerShellのConvertTo-Jsonは、結果が単数の場合はオブジェクト、複数の場合は配列を返します。
-    // まず配列としてパースを試み、失敗した場合は単一オブジェクトとしてパースします。
-    let apps: Vec<PsApp> = match serde_json::from_str::<Vec<PsApp>>(trimmed) {
-        Ok(list) => list,
-        Err(_) => {
-            // 単一オブジェクトの場合のフォールバック
-            match serde_json::from_str::<PsApp>(trimmed) {
-                Ok(single) => vec![single],
-                Err(e) => return Err(format!("Failed to parse JSON: {} | Output: {}", e, trimmed)),
-            }
-        }
-    };
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .creation_flags(CREATE_NO_WINDOW);
+    let apps: Vec<PsApp> = helper_process::run_json(command, POWERSHELL_TIMEOUT)?;
 
     Ok(apps
         .into_iter()
         .map(|a| UwpApp {
+            logo: a
+                .install_location
+                .as_deref()
+                .and_then(|location| find_logo(location, &a.app_id)),
             name: a.name,
             aumid: a.app_id,
         })
         .collect())
 }
 
+/// Windows以外のOSでは、Flatpak と Snap でインストールされたアプリの一覧を返します。
+#[cfg(not(target_os = "windows"))]
+pub fn get_installed_uwp_apps() -> Result<Vec<UwpApp>, String> {
+    Ok(sandbox_apps::installed_apps())
+}
+
+/// パッケージの `AppxManifest.xml` からアプリのロゴを求め、実際に置かれている画像ファイルのパスを返します。
+///
+/// # 引数
+/// * `install_location` - パッケージのインストール先
+/// * `aumid` - アプリのAUMID
+#[cfg(target_os = "windows")]
+fn find_logo(install_location: &str, aumid: &str) -> Option<String> {
+    let (_, app_id) = aumid.split_once('!')?;
+    let package_dir = Path::new(install_location);
+    let manifest = fs::read_to_string(package_dir.join("AppxManifest.xml")).ok()?;
+    let logo = uwp_manifest::app_logo(&manifest, app_id)?;
+
+    let logo_dir = package_dir.join(logo.path.as_str()).parent()?.to_path_buf();
+    let files: Vec<String> = fs::read_dir(&logo_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    let file = uwp_manifest::choose_logo_asset(&logo, &files, LOGO_SIZE)?;
+    Some(logo_dir.join(file).to_string_lossy().to_string())
+}
+
 /// 指定されたAUMIDを使用してUWPアプリを起動します。
 ///
 /// `explorershell:AppsFolder\{AUMID}` を実行することで、通常のアプリと同様に起動できます。
+#[cfg(target_os = "windows")]
 pub fn launch_uwp(aumid: &str) -> Result<(), String> {
     // shell:AppsFolder\{AUMID} is the standard way to launch UWP apps from explorer
     let _ = Command::new("explorer")
@@ -81,3 +133,9 @@ pub fn launch_uwp(aumid: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Windows以外のOSでは、Flatpak のアプリは `.desktop` ファイルの `Exec=`、Snap のアプリは `/snap/bin` のラッパーで起動します。
+#[cfg(not(target_os = "windows"))]
+pub fn launch_uwp(aumid: &str) -> Result<(), String> {
+    sandbox_apps::launch(aumid)
+}
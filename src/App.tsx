@@ -9,11 +9,16 @@ import { CustomCSSInjector } from './components/CustomCSSInjector';
 import { ToastContainer } from './components/ToastContainer';
 import { CellEditDialog } from './components/CellEditDialog';
 import { UwpSelectorModal } from './components/Uwp/UwpSelectorModal';
-import { loadSettings, startMouseEdgeMonitor, stopMouseEdgeMonitor } from './utils/tauri';
+import { loadSettings, setIconCacheLimit, setMouseEdgeConfig, startMouseEdgeMonitor, stopMouseEdgeMonitor } from './utils/tauri';
+import { launchCellTarget } from './utils/cellLaunch';
 import { useLauncherStore } from './store/launcherStore';
+import { EdgeAction } from './types/models';
 import './i18n/config'; // Initialize i18n
 import i18n from './i18n/config';
 
+/** アイコンキャッシュの上限サイズの変更をバックエンドに反映するまでの待ち時間 (ms) */
+const ICON_CACHE_LIMIT_DEBOUNCE_MS = 500;
+
 /**
  * アプリケーションのルートコンポーネント。
  *
@@ -29,6 +34,8 @@ function App() {
   const loadFromSettings = useLauncherStore(state => state.loadFromSettings);
   const hideOnBlur = useLauncherStore(state => state.general?.windowBehavior?.hideOnBlur ?? false);
   const showOnMouseEdge = useLauncherStore(state => state.general?.windowBehavior?.showOnMouseEdge ?? false);
+  const mouseEdge = useLauncherStore(state => state.general?.mouseEdge);
+  const iconCacheMaxMb = useLauncherStore(state => state.advanced?.iconCacheMaxMb);
 
   /**
    * 初期化エフェクト：設定ファイル (settings.json) を読み込み、ストアに適用します。
@@ -65,6 +72,39 @@ function App() {
     };
   }, []);
 
+  /**
+   * マウスエッジのゾーンに割り当てた動作 (グループ・セル・検索) を実行します。
+   * ランチャーの表示・非表示はバックエンドで行われ、ここではストアの状態のみを操作します。
+   */
+  useEffect(() => {
+    const setupListener = async () => {
+      const { listen } = await import('@tauri-apps/api/event');
+      const unlisten = await listen<EdgeAction>('mouse-edge-action', ({ payload: action }) => {
+        const state = useLauncherStore.getState();
+        if (action.type === 'openGroup') {
+          if (state.groups[action.groupId]) {
+            state.setActiveGroup(action.groupId);
+          } else {
+            console.warn('Mouse edge action: group not found', action.groupId);
+          }
+        } else if (action.type === 'launchCell') {
+          const cell = state.cells[action.cellId];
+          if (!cell || !launchCellTarget(cell)) {
+            console.warn('Mouse edge action: cell cannot be launched', action.cellId);
+          }
+        } else if (action.type === 'openSearch') {
+          state.setSearchActive(true);
+        }
+      });
+      return unlisten;
+    };
+
+    let unlistenPromise = setupListener();
+    return () => {
+      unlistenPromise.then(unlisten => unlisten && unlisten());
+    };
+  }, []);
+
   // Hide on Blur（設定フラグが true の時のみ）
   const isExiting = useLauncherStore(state => state.isExiting);
   const setIsExiting = useLauncherStore(state => state.setIsExiting);
@@ -118,6 +158,16 @@ function App() {
     };
   }, [hideOnBlur, setIsExiting]);
 
+  /**
+   * マウスエッジの反応するゾーン・タイミングの設定をバックエンドに反映します。
+   * 監視の開始より先に反映するため、開始・停止のエフェクトより前に置いています。
+   */
+  useEffect(() => {
+    if (mouseEdge) {
+      setMouseEdgeConfig(mouseEdge);
+    }
+  }, [mouseEdge]);
+
   /**
    * マウスが画面端に移動した際のウィンドウ表示モニターの設定を反映します。
    */
@@ -133,6 +183,19 @@ function App() {
     };
   }, [showOnMouseEdge]);
 
+  /**
+   * アイコンのディスクキャッシュの上限サイズを反映します。
+   * 未設定の場合はバックエンドに保存されている上限 (既定 256MB) をそのまま使用します。
+   * 上限を下げるとキャッシュの削除が走るため、スライダー操作中は反映せず、値が落ち着いてから反映します。
+   */
+  useEffect(() => {
+    if (iconCacheMaxMb === undefined) return;
+    const timer = setTimeout(() => {
+      setIconCacheLimit(iconCacheMaxMb);
+    }, ICON_CACHE_LIMIT_DEBOUNCE_MS);
+    return () => clearTimeout(timer);
+  }, [iconCacheMaxMb]);
+
   /**
    * 言語設定の変更をi18nライブラリに適用します。
    */
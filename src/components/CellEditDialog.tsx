@@ -1,7 +1,8 @@
 import React, { useState, useEffect } from 'react';
 import { useLauncherStore } from '../store/launcherStore';
 import { useTranslation } from 'react-i18next';
-import { convertFileSrc } from '@tauri-apps/api/core';
+import { importCustomIcon } from '../utils/tauri';
+import { getCustomIconSrc } from '../utils/cellIcon';
 
 /**
  * セル（アプリケーション、ショートカット）のプロパティを編集するダイアログコンポーネント。
@@ -96,7 +97,13 @@ export const CellEditDialog: React.FC = () => {
                 }]
             });
             if (selected) {
-                setCustomIcon(selected as string);
+                // 元のファイルが移動・削除されても表示できるよう、アプリのデータフォルダにインポートして参照を保存する
+                const imported = await importCustomIcon(selected as string);
+                if (imported) {
+                    setCustomIcon(imported.reference);
+                } else {
+                    addToast(t('toast.error.failedToImportIcon'), 'error');
+                }
             }
         } catch (error) {
             console.error('Failed to open file dialog:', error);
@@ -162,7 +169,7 @@ export const CellEditDialog: React.FC = () => {
                             <div className="w-16 h-16 bg-gray-700 rounded-lg flex items-center justify-center overflow-hidden border border-gray-600 shrink-0">
                                 {customIcon ? (
                                     <img
-                                        src={convertFileSrc(customIcon)}
+                                        src={getCustomIconSrc(customIcon, 64)}
                                         alt="Preview"
                                         className="w-full h-full object-contain"
                                     />
@@ -6,7 +6,12 @@ import { cubeToPixel } from '../utils/hexUtils';
 import { useKeyboardShortcuts } from './HexGrid/hooks/useKeyboardShortcuts';
 import { useFileDropHandler } from './HexGrid/hooks/useFileDropHandler';
 import { useDragAndDrop } from './HexGrid/hooks/useDragAndDrop';
+import { useRunningTargets } from './HexGrid/hooks/useRunningTargets';
 import { useCellHandlers } from './HexGrid/handlers/cellHandlers';
+import { cancelIconPrefetch, prefetchIcons } from '../utils/tauri';
+import { getCellIconParams, getSilhouetteColor } from '../utils/cellIcon';
+import { IconPrefetchItem } from '../utils/iconCache';
+import { Cell, IconPrefetchPriority } from '../types/models';
 
 /**
  * 六角形グリッド（ハニカム構造）を描画し、セル管理を行うメインコンポーネント。
@@ -69,9 +74,49 @@ export const HexGrid: React.FC = () => {
         ? (groups[activeGroupId]?.cells.map(id => cellsMap[id]).filter(Boolean) || [])
         : rootCellIds.map(id => cellsMap[id]).filter(Boolean);
 
+    // アイコンの先読み: 画面内のセル → 画面外のセルと子グループのセル → 親グループのセルの順にバックエンドで抽出する
+    useEffect(() => {
+        const group = activeGroupId || 'root';
+        const layoutSize = currentHexSize + (grid.gapSize || 0);
+        const halfWidth = window.innerWidth / 2 + currentHexSize;
+        const halfHeight = window.innerHeight / 2 + currentHexSize;
+
+        const items: IconPrefetchItem[] = [];
+        const addCells = (targets: Cell[], priorityOf: (cell: Cell) => IconPrefetchPriority) => {
+            for (const cell of targets) {
+                const params = getCellIconParams(cell, appearance, getSilhouetteColor(cell, appearance, appearance.themeColor));
+                if (params) {
+                    items.push({ ...params, priority: priorityOf(cell) });
+                }
+            }
+        };
+        const cellsOf = (groupId: string | null | undefined) => (groupId
+            ? (groups[groupId]?.cells.map(id => cellsMap[id]).filter(Boolean) || [])
+            : rootCellIds.map(id => cellsMap[id]).filter(Boolean));
+
+        const currentCells = cellsOf(activeGroupId);
+        addCells(currentCells, cell => {
+            const { x, y } = cubeToPixel(cell.cube, layoutSize);
+            return Math.abs(x) <= halfWidth && Math.abs(y) <= halfHeight ? 'visible' : 'neighbor';
+        });
+        for (const cell of currentCells) {
+            if (cell.type === 'group' && cell.groupId) {
+                addCells(cellsOf(cell.groupId), () => 'neighbor');
+            }
+        }
+        if (activeGroupId) {
+            addCells(cellsOf(groups[activeGroupId]?.parentId), () => 'rest');
+        }
+
+        prefetchIcons(items, group);
+        // グループを離れたら、まだ開始していない先読みを取り消す
+        return () => { cancelIconPrefetch(group); };
+    }, [activeGroupId, groups, cellsMap, rootCellIds, appearance, currentHexSize, grid.gapSize]);
+
     // Custom Hooks
     const { hoveredCellId: fileDropHoveredCellId } = useFileDropHandler(svgRef);
     useKeyboardShortcuts();
+    const runningCellIds = useRunningTargets(cells);
 
     const {
         draggedCellId,
@@ -167,6 +212,7 @@ export const HexGrid: React.FC = () => {
                                 isSelected={isSelected}
                                 isSearchMatch={isSearchMatch}
                                 isSearchActive={isSearchActive}
+                                isRunning={runningCellIds.has(cell.id)}
                                 showLabel={grid.showLabels}
                                 hoverEffect={grid.hoverEffect}
                             />
@@ -1,7 +1,7 @@
 import { useEffect, RefObject, useState } from 'react';
 import { useLauncherStore } from '../../../store/launcherStore';
 import { cubeToPixel, HEX_SIZE } from '../../../utils/hexUtils';
-import { getFileIcon } from '../../../utils/tauri';
+import { getFileIcon, resolveShortcuts } from '../../../utils/tauri';
 
 /**
  * 外部からのファイルドロップ処理を行うカスタムフック。
@@ -245,8 +245,12 @@ export const useFileDropHandler = (svgRef: RefObject<SVGSVGElement | null>) => {
                     // Resolve shortcut if it is .lnk
                     let resolvedInfo = { target: filePath, arguments: '', working_dir: '' };
                     if (filePath.toLowerCase().endsWith('.lnk')) {
-                        const { resolveShortcut } = await import('../../../utils/tauri');
-                        resolvedInfo = await resolveShortcut(filePath);
+                        const [item] = await resolveShortcuts([filePath]);
+                        if (item?.resolved) {
+                            resolvedInfo = item.resolved;
+                        } else {
+                            console.warn('Failed to resolve shortcut:', item?.error);
+                        }
                     }
 
                     state.updateCell(targetCell.id, {
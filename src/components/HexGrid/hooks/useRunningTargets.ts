@@ -0,0 +1,59 @@
+import { useEffect, useState } from 'react';
+import { Cell } from '../../../types/models';
+import { getRunningTargets } from '../../../utils/tauri';
+
+/** 実行中プロセスの照合を行う間隔 (ms) */
+const POLL_INTERVAL_MS = 3000;
+
+/**
+ * プロセスとの照合に使用するセルの実体パス。
+ * リンクを解決した resolvedTargetPath を優先し、未記録の場合は保存されたパスを使用します。
+ */
+const processPathOf = (cell: Cell): string | undefined => {
+    if (cell.shortcut?.kind === 'file') {
+        return cell.shortcut.resolvedTargetPath || cell.shortcut.targetPath;
+    }
+    return cell.type === 'app' ? cell.target : undefined;
+};
+
+/**
+ * 表示中のセルのうち、起動対象のプロセスが実行中のセルのIDを定期的に取得するカスタムフック。
+ *
+ * シンボリックリンク経由で登録したアプリでも、実体パスで照合するため実行中として検出できます。
+ *
+ * @param cells 表示中のセル
+ * @returns 実行中のセルのIDの集合
+ */
+export const useRunningTargets = (cells: Cell[]): Set<string> => {
+    const [runningIds, setRunningIds] = useState<Set<string>>(new Set());
+
+    // セルの配列は描画ごとに作り直されるため、照合対象のパスの組で依存関係を判定する
+    const targets = cells
+        .map(cell => [cell.id, processPathOf(cell)] as const)
+        .filter((entry): entry is readonly [string, string] => !!entry[1]);
+    const targetsKey = JSON.stringify(targets);
+
+    useEffect(() => {
+        if (targets.length === 0) {
+            setRunningIds(new Set());
+            return;
+        }
+
+        let cancelled = false;
+        const poll = async () => {
+            const running = new Set(await getRunningTargets([...new Set(targets.map(([, path]) => path))]));
+            if (!cancelled) {
+                setRunningIds(new Set(targets.filter(([, path]) => running.has(path)).map(([id]) => id)));
+            }
+        };
+
+        poll();
+        const timer = setInterval(poll, POLL_INTERVAL_MS);
+        return () => {
+            cancelled = true;
+            clearInterval(timer);
+        };
+    }, [targetsKey]);
+
+    return runningIds;
+};
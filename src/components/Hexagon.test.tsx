@@ -6,6 +6,7 @@ import { Cell } from '../types/models';
 // Mock the tauri utils
 jest.mock('../utils/tauri', () => ({
     getFileIcon: jest.fn(() => Promise.resolve('data:image/png;base64,mock')),
+    getFileIconUrl: jest.fn(() => Promise.resolve('http://hexicon.localhost/mock.png')),
 }));
 
 describe('Hexagon Component', () => {
@@ -4,8 +4,8 @@ import { Cell } from '../types/models';
 import { THEMES } from '../utils/theme';
 import { clsx } from 'clsx';
 import { useLauncherStore } from '../store/launcherStore';
-import { getFileIcon } from '../utils/tauri';
-import { convertFileSrc } from '@tauri-apps/api/core';
+import { getFileIconUrl } from '../utils/tauri';
+import { getCellIconParams, getCustomIconSrc, getSilhouetteColor } from '../utils/cellIcon';
 import { ClockWidget } from './Widgets/ClockWidget';
 import { SystemWidget } from './Widgets/SystemWidget';
 
@@ -41,6 +41,8 @@ export interface HexagonProps {
     isSearchMatch?: boolean;
     /** 検索モードがアクティブかどうか（非一致セルの非強調表示に使用） */
     isSearchActive?: boolean;
+    /** 起動対象のプロセスが実行中かどうか（下部にインジケーターを表示） */
+    isRunning?: boolean;
     /** ラベルの表示設定 ('always' | 'hover' | 'never') */
     showLabel?: 'always' | 'hover' | 'never';
     /** ホバーエフェクトを有効にするか */
@@ -69,6 +71,7 @@ export const HexagonComponent: React.FC<HexagonProps> = ({
     isSelected = false,
     isSearchMatch = false,
     isSearchActive = false,
+    isRunning = false,
     showLabel = 'hover',
     hoverEffect = true,
 }) => {
@@ -84,26 +87,28 @@ export const HexagonComponent: React.FC<HexagonProps> = ({
     const appearance = useLauncherStore(state => state.appearance);
     const isCyberpunk = appearance.style === 'cyberpunk';
     const [iconUrl, setIconUrl] = useState<string | null>(cell.icon || null);
+    // iconUrl がバックエンドでシルエット化済みの画像かどうか (未処理の場合は CSS マスクで単色化する)
+    const [isIconTransformed, setIsIconTransformed] = useState(false);
+
+    const effectiveColorName = isCyberpunk ? 'cyan' : (cell.themeColor || themeColor || 'cyan');
+    const theme = THEMES[effectiveColorName] || THEMES['cyan'];
+    const silhouetteColor = getSilhouetteColor(cell, appearance, themeColor);
 
     useEffect(() => {
         let isMounted = true;
-        const targetPath = cell.shortcut?.targetPath || cell.target;
 
+        setIsIconTransformed(false);
         if (cell.customIcon) {
-            const url = convertFileSrc(cell.customIcon);
+            const url = getCustomIconSrc(cell.customIcon);
             setIconUrl(url);
         } else {
-            const isShortcut = targetPath?.toLowerCase().endsWith('.lnk');
-            // settings.jsonに保存されたアイコン(cell.icon)があっても、「ショートカットアイコン非表示」設定時は
-            // 強制的にバックエンドから実体アイコンを取得しなおす
-            const forceFetch = isShortcut && appearance.showShortcutIcon === false;
-
-            if ((!cell.icon || forceFetch) && targetPath && cell.type !== 'launcher_setting') {
-                // showShortcutIcon が false の場合、resolveShortcut: true を渡して実体のアイコン（矢印なし）を取得する
-                const resolveShortcut = appearance.showShortcutIcon === false;
-                getFileIcon(targetPath, resolveShortcut).then(icon => {
+            const params = getCellIconParams(cell, appearance, silhouetteColor);
+            if (params) {
+                const { path, resolveShortcut, size, transform } = params;
+                getFileIconUrl(path, resolveShortcut, size, transform).then(icon => {
                     if (isMounted && icon) {
                         setIconUrl(icon);
+                        setIsIconTransformed(!!transform);
                     }
                 });
             } else {
@@ -111,10 +116,7 @@ export const HexagonComponent: React.FC<HexagonProps> = ({
             }
         }
         return () => { isMounted = false; };
-    }, [cell.customIcon, cell.icon, cell.target, cell.shortcut?.targetPath, cell.type, appearance.showShortcutIcon]); // Add dependency
-
-    const effectiveColorName = isCyberpunk ? 'cyan' : (cell.themeColor || themeColor || 'cyan');
-    const theme = THEMES[effectiveColorName] || THEMES['cyan'];
+    }, [cell.customIcon, cell.icon, cell.target, cell.shortcut?.targetPath, cell.type, appearance.showShortcutIcon, appearance.enableIconSilhouette, silhouetteColor]); // Add dependency
 
     const renderContent = (layerClass: string = "") => (
         <>
@@ -195,7 +197,8 @@ export const HexagonComponent: React.FC<HexagonProps> = ({
                             ) : (
 
                                 iconUrl ? (
-                                    appearance.enableIconSilhouette ? (
+                                    appearance.enableIconSilhouette && !isIconTransformed ? (
+                                        // カスタムアイコン・保存済みアイコンはバックエンドを経由しないため CSS マスクで単色化する
                                         <div
                                             className={clsx("w-8 h-8", isCyberpunk ? "bg-[#00f2ea]" : "")}
                                             style={{
@@ -207,7 +210,7 @@ export const HexagonComponent: React.FC<HexagonProps> = ({
                                                 WebkitMaskRepeat: 'no-repeat',
                                                 maskPosition: 'center',
                                                 WebkitMaskPosition: 'center',
-                                                backgroundColor: isCyberpunk ? '#00f2ea' : (theme.color || 'currentColor')
+                                                backgroundColor: silhouetteColor || 'currentColor'
                                             }}
                                         />
                                     ) : (
@@ -237,6 +240,17 @@ export const HexagonComponent: React.FC<HexagonProps> = ({
                     </div>
                 )}
             </foreignObject>
+
+            {/* Running Indicator */}
+            {isRunning && !layerClass.includes('glitch') && (
+                <circle
+                    cx={0}
+                    cy={size * 0.72}
+                    r={Math.max(2, size * 0.05)}
+                    className="pointer-events-none"
+                    fill={isCyberpunk ? '#00f2ea' : theme.color}
+                />
+            )}
         </>
     );
 
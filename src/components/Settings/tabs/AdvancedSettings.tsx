@@ -1,19 +1,37 @@
-import React from 'react';
+import React, { useEffect, useState } from 'react';
 import { useTranslation } from 'react-i18next';
 import { useLauncherStore } from '../../../store/launcherStore';
 import { SettingsSection } from '../shared/SettingsSection';
 import { SettingsToggle } from '../shared/SettingsToggle';
-import { saveSettings } from '../../../utils/tauri';
+import { SettingsSlider } from '../shared/SettingsSlider';
+import { compactIconCache, getIconCacheStats, invalidateAllIcons, saveSettings } from '../../../utils/tauri';
+import { IconCacheStats } from '../../../types/models';
+
+const toMb = (bytes: number) => (bytes / (1024 * 1024)).toFixed(1);
 
 export const AdvancedSettings: React.FC = () => {
     const { t } = useTranslation();
     const advanced = useLauncherStore(state => state.advanced);
     const setAdvancedSettings = useLauncherStore(state => state.setAdvancedSettings);
     const resetAdvancedSettings = useLauncherStore(state => state.resetAdvancedSettings);
+    const [cacheStats, setCacheStats] = useState<IconCacheStats | null>(null);
+
+    useEffect(() => {
+        getIconCacheStats().then(setCacheStats);
+    }, []);
+
+    const handleCompactIconCache = async () => {
+        const result = await compactIconCache();
+        if (result) {
+            alert(t('advanced.compactIconCacheSuccess', { count: result.removed_entries, size: toMb(result.freed_bytes) }));
+        }
+        setCacheStats(await getIconCacheStats());
+    };
 
     const handleClearIconCache = async () => {
         if (confirm(t('advanced.clearIconCacheConfirm'))) {
             try {
+                await invalidateAllIcons();
                 const state = useLauncherStore.getState();
                 await saveSettings({
                     schemaVersion: 1,
@@ -65,9 +83,45 @@ export const AdvancedSettings: React.FC = () => {
                     checked={advanced.disableAnimations || false}
                     onChange={(checked) => setAdvancedSettings({ disableAnimations: checked })}
                 />
+                <SettingsToggle
+                    label={t('advanced.resolveSymlinks')}
+                    description={t('advanced.resolveSymlinksDesc')}
+                    checked={advanced.resolveSymlinks || false}
+                    onChange={(checked) => setAdvancedSettings({ resolveSymlinks: checked })}
+                />
+                <SettingsSlider
+                    label={t('advanced.iconCacheMaxMb')}
+                    value={advanced.iconCacheMaxMb ?? 256}
+                    min={32}
+                    max={1024}
+                    step={32}
+                    unit=" MB"
+                    onChange={(val) => setAdvancedSettings({ iconCacheMaxMb: val })}
+                />
             </SettingsSection>
 
             <SettingsSection title="Maintenance">
+                <div className="flex justify-between items-center p-3 mb-3 rounded-lg border border-white/10">
+                    <div>
+                        <span className="block text-sm font-medium text-white">{t('advanced.compactIconCache')}</span>
+                        <span className="text-xs text-gray-400">{t('advanced.compactIconCacheDesc')}</span>
+                        {cacheStats && (
+                            <span className="block text-xs text-gray-500 mt-1">
+                                {t('advanced.iconCacheStats', {
+                                    count: cacheStats.entries,
+                                    size: toMb(cacheStats.total_bytes),
+                                    orphans: cacheStats.orphaned_entries,
+                                })}
+                            </span>
+                        )}
+                    </div>
+                    <button
+                        onClick={handleCompactIconCache}
+                        className="px-3 py-1.5 bg-white/10 hover:bg-white/20 text-gray-200 text-xs rounded border border-white/20 transition-colors"
+                    >
+                        {t('advanced.compactIconCache')}
+                    </button>
+                </div>
                 <div className="flex justify-between items-center bg-red-900/10 p-3 rounded-lg border border-red-900/30">
                     <div>
                         <span className="block text-sm font-medium text-red-200">Icon Cache</span>
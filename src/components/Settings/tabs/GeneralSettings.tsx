@@ -1,12 +1,286 @@
 import React, { useState, useEffect } from 'react';
 import { useTranslation } from 'react-i18next';
+import { clsx } from 'clsx';
+import { availableMonitors } from '@tauri-apps/api/window';
 import { useLauncherStore } from '../../../store/launcherStore';
+import { DEFAULT_MOUSE_EDGE_SETTINGS } from '../../../store/slices/settingsSlice';
 import { SettingsSection } from '../shared/SettingsSection';
 import { SettingsToggle } from '../shared/SettingsToggle';
 import { SettingsSelect } from '../shared/SettingsSelect';
+import { SettingsSlider } from '../shared/SettingsSlider';
 import { getStartupStatus, setStartup, setAlwaysOnTop } from '../../../utils/tauri';
+import { EdgeAction, HotZone, MouseEdgeSettings } from '../../../types/models';
 import i18n from '../../../i18n/config';
 
+/** ゾーン選択の 3x3 の配置 (中央は空き) */
+const HOT_ZONE_LAYOUT: (HotZone | null)[] = [
+    'topLeft', 'top', 'topRight',
+    'left', null, 'right',
+    'bottomLeft', 'bottom', 'bottomRight',
+];
+
+/**
+ * 画面を模した 3x3 のボタンで、マウスエッジで反応する画面端・四隅を選択します。
+ */
+const HotZonePicker: React.FC<{
+    zones: HotZone[];
+    onChange: (zones: HotZone[]) => void;
+}> = ({ zones, onChange }) => {
+    const { t } = useTranslation();
+    const isCyberpunk = useLauncherStore(state => state.appearance.style === 'cyberpunk');
+
+    const toggle = (zone: HotZone) => {
+        onChange(zones.includes(zone) ? zones.filter(z => z !== zone) : [...zones, zone]);
+    };
+
+    return (
+        <div className="grid grid-cols-3 gap-1 w-48 p-1 rounded border border-gray-600 bg-gray-800/50">
+            {HOT_ZONE_LAYOUT.map((zone, i) => zone ? (
+                <button
+                    key={zone}
+                    title={t(`general.hotZones.${zone}`)}
+                    onClick={() => toggle(zone)}
+                    className={clsx(
+                        "h-8 rounded text-[10px] transition-colors",
+                        zones.includes(zone)
+                            ? (isCyberpunk ? "bg-[#00f2ea]/30 text-[#00f2ea] border border-[#00f2ea]/60" : "bg-cyan-600 text-white")
+                            : "bg-gray-700 text-gray-400 hover:bg-gray-600"
+                    )}
+                >
+                    {t(`general.hotZones.${zone}`)}
+                </button>
+            ) : (
+                <div key={i} />
+            ))}
+        </div>
+    );
+};
+
+/** ゾーンに割り当てられる動作 */
+const EDGE_ACTION_TYPES: EdgeAction['type'][] = ['showLauncher', 'openGroup', 'launchCell', 'openSearch', 'hide'];
+
+/**
+ * ゾーンに到達した際の動作 (と、開くグループ・起動するセル) を選択します。
+ */
+const ZoneActionEditor: React.FC<{
+    zone: HotZone;
+    action: EdgeAction;
+    onChange: (action: EdgeAction) => void;
+}> = ({ zone, action, onChange }) => {
+    const { t } = useTranslation();
+    const groups = useLauncherStore(state => state.groups);
+    const cells = useLauncherStore(state => state.cells);
+
+    const groupOptions = Object.values(groups).map(group => ({ value: group.id, label: group.title }));
+    const cellOptions = Object.values(cells)
+        .filter(cell => cell.type === 'app' || cell.type === 'shortcut')
+        .map(cell => ({ value: cell.id, label: cell.title }));
+
+    const changeType = (type: string) => {
+        if (type === 'openGroup') {
+            onChange({ type, groupId: groupOptions[0]?.value ?? '' });
+        } else if (type === 'launchCell') {
+            onChange({ type, cellId: cellOptions[0]?.value ?? '' });
+        } else {
+            onChange({ type } as EdgeAction);
+        }
+    };
+
+    return (
+        <div className="pl-4 border-l border-gray-700">
+            <SettingsSelect
+                label={t('general.zoneAction', { zone: t(`general.hotZones.${zone}`) })}
+                value={action.type}
+                options={EDGE_ACTION_TYPES.map(type => ({ value: type, label: t(`general.edgeActions.${type}`) }))}
+                onChange={changeType}
+            />
+            {action.type === 'openGroup' && (
+                <SettingsSelect
+                    label={t('general.edgeActionGroup')}
+                    value={action.groupId}
+                    options={groupOptions}
+                    onChange={(groupId) => onChange({ type: 'openGroup', groupId })}
+                />
+            )}
+            {action.type === 'launchCell' && (
+                <SettingsSelect
+                    label={t('general.edgeActionCell')}
+                    value={action.cellId}
+                    options={cellOptions}
+                    onChange={(cellId) => onChange({ type: 'launchCell', cellId })}
+                />
+            )}
+        </div>
+    );
+};
+
+/**
+ * マウスエッジの反応するゾーン・タイミング・モニターの設定。
+ */
+const MouseEdgeOptions: React.FC = () => {
+    const { t } = useTranslation();
+    const mouseEdge = useLauncherStore(state => state.general.mouseEdge);
+    const setGeneralSettings = useLauncherStore(state => state.setGeneralSettings);
+    const [monitorNames, setMonitorNames] = useState<string[]>([]);
+    const [newProcess, setNewProcess] = useState('');
+    const isCyberpunk = useLauncherStore(state => state.appearance.style === 'cyberpunk');
+
+    const settings: MouseEdgeSettings = { ...DEFAULT_MOUSE_EDGE_SETTINGS, ...mouseEdge };
+    const update = (patch: Partial<MouseEdgeSettings>) => {
+        setGeneralSettings({ mouseEdge: { ...settings, ...patch } });
+    };
+
+    useEffect(() => {
+        availableMonitors()
+            .then(monitors => setMonitorNames(
+                monitors.map(m => m.name).filter((name): name is string => !!name)
+            ))
+            .catch(console.error);
+    }, []);
+
+    const setAction = (zone: HotZone, action: EdgeAction) => {
+        update({ actions: { ...settings.actions, [zone]: action } });
+    };
+
+    const toggleMonitor = (name: string, enabled: boolean) => {
+        const others = settings.disabledMonitors.filter(n => n !== name);
+        update({ disabledMonitors: enabled ? others : [...others, name] });
+    };
+
+    const blockedProcesses = settings.blockedProcesses ?? [];
+    const handleAddProcess = () => {
+        const name = newProcess.trim();
+        if (name && !blockedProcesses.some(p => p.toLowerCase() === name.toLowerCase())) {
+            update({ blockedProcesses: [...blockedProcesses, name] });
+            setNewProcess('');
+        }
+    };
+
+    return (
+        <SettingsSection title={t('general.mouseEdge')}>
+            <div className="py-2">
+                <label className="block text-sm font-medium text-white mb-1">{t('general.hotZones.label')}</label>
+                <p className="text-xs text-gray-500 mb-2">{t('general.hotZones.description')}</p>
+                <HotZonePicker zones={settings.zones} onChange={(zones) => update({ zones })} />
+            </div>
+            {HOT_ZONE_LAYOUT
+                .filter((zone): zone is HotZone => zone !== null && settings.zones.includes(zone))
+                .map(zone => (
+                    <ZoneActionEditor
+                        key={zone}
+                        zone={zone}
+                        action={settings.actions?.[zone] ?? { type: 'showLauncher' }}
+                        onChange={(action) => setAction(zone, action)}
+                    />
+                ))}
+            <SettingsSlider
+                label={t('general.edgeThreshold')}
+                value={settings.edgeThreshold}
+                min={0}
+                max={50}
+                unit="px"
+                onChange={(edgeThreshold) => update({ edgeThreshold })}
+            />
+            <SettingsSlider
+                label={t('general.cornerSize')}
+                value={settings.cornerSize}
+                min={4}
+                max={200}
+                unit="px"
+                onChange={(cornerSize) => update({ cornerSize })}
+            />
+            <SettingsSlider
+                label={t('general.dwellTime')}
+                value={settings.dwellMs}
+                min={0}
+                max={2000}
+                step={50}
+                unit="ms"
+                onChange={(dwellMs) => update({ dwellMs })}
+            />
+            <SettingsSlider
+                label={t('general.edgeCooldown')}
+                value={settings.cooldownMs}
+                min={0}
+                max={5000}
+                step={100}
+                unit="ms"
+                onChange={(cooldownMs) => update({ cooldownMs })}
+            />
+            <SettingsToggle
+                label={t('general.ignoreSharedEdges')}
+                description={t('general.ignoreSharedEdgesDesc')}
+                checked={settings.ignoreSharedEdges}
+                onChange={(ignoreSharedEdges) => update({ ignoreSharedEdges })}
+            />
+            {monitorNames.length > 1 && monitorNames.map(name => (
+                <SettingsToggle
+                    key={name}
+                    label={t('general.monitorEnabled', { name })}
+                    checked={!settings.disabledMonitors.includes(name)}
+                    onChange={(enabled) => toggleMonitor(name, enabled)}
+                />
+            ))}
+            <SettingsToggle
+                label={t('general.suppressWhileButtonDown')}
+                description={t('general.suppressWhileButtonDownDesc')}
+                checked={settings.suppressWhileButtonDown ?? true}
+                onChange={(suppressWhileButtonDown) => update({ suppressWhileButtonDown })}
+            />
+            <SettingsToggle
+                label={t('general.suppressInFullscreen')}
+                description={t('general.suppressInFullscreenDesc')}
+                checked={settings.suppressInFullscreen ?? true}
+                onChange={(suppressInFullscreen) => update({ suppressInFullscreen })}
+            />
+            <div className="py-2">
+                <label className="block text-sm font-medium text-white mb-1">{t('general.blockedProcesses')}</label>
+                <p className="text-xs text-gray-500 mb-2">{t('general.blockedProcessesDesc')}</p>
+                <div className="flex gap-2 mb-2">
+                    <input
+                        type="text"
+                        value={newProcess}
+                        onChange={(e) => setNewProcess(e.target.value)}
+                        onKeyDown={(e) => e.key === 'Enter' && handleAddProcess()}
+                        placeholder="game.exe"
+                        className={clsx(
+                            "flex-1 rounded-md px-3 py-2 text-sm focus:outline-none focus:ring-2 transition-all",
+                            isCyberpunk
+                                ? "bg-black/50 border border-[#00f2ea]/30 text-[#00f2ea] focus:ring-[#00f2ea]/50 placeholder-gray-600"
+                                : "bg-gray-700 border border-gray-600 text-white focus:ring-cyan-500 placeholder-gray-400"
+                        )}
+                    />
+                    <button
+                        onClick={handleAddProcess}
+                        className={clsx(
+                            "px-4 py-2 rounded-lg border transition-all text-sm font-medium",
+                            isCyberpunk
+                                ? "bg-[#00f2ea]/10 border-[#00f2ea] text-[#00f2ea] hover:bg-[#00f2ea]/20"
+                                : "bg-gray-700 border-cyan-500 text-white hover:bg-gray-600"
+                        )}
+                    >
+                        {t('common.add')}
+                    </button>
+                </div>
+                {blockedProcesses.map(name => (
+                    <div
+                        key={name}
+                        className="flex items-center justify-between px-3 py-1.5 rounded text-sm group bg-gray-700/50 hover:bg-gray-700 mb-1"
+                    >
+                        <span className="text-gray-300 truncate flex-1 font-mono text-xs">{name}</span>
+                        <button
+                            onClick={() => update({ blockedProcesses: blockedProcesses.filter(p => p !== name) })}
+                            className="ml-2 text-gray-500 hover:text-red-400 transition-colors opacity-0 group-hover:opacity-100"
+                        >
+                            {t('common.remove')}
+                        </button>
+                    </div>
+                ))}
+            </div>
+        </SettingsSection>
+    );
+};
+
 export const GeneralSettings: React.FC = () => {
     const { t } = useTranslation();
     const general = useLauncherStore(state => state.general);
@@ -116,6 +390,8 @@ export const GeneralSettings: React.FC = () => {
                     })}
                 />
             </SettingsSection>
+
+            {general.windowBehavior.showOnMouseEdge && <MouseEdgeOptions />}
         </div>
     );
 };
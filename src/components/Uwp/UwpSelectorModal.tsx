@@ -1,11 +1,37 @@
 import React, { useEffect, useState, useMemo } from 'react';
+import { listen } from '@tauri-apps/api/event';
 // import { useTranslation } from 'react-i18next';
 import { useLauncherStore } from '../../store/launcherStore';
-import { getUwpApps } from '../../utils/tauri';
+import { getFileIconUrl, getUwpApps, refreshUwpApps } from '../../utils/tauri';
+import { ICON_DISPLAY_SIZE } from '../../utils/cellIcon';
 import { UwpApp } from '../../types/models';
-import { IoClose, IoSearch } from 'react-icons/io5';
+import { IoClose, IoRefresh, IoSearch } from 'react-icons/io5';
 import { DiWindows } from 'react-icons/di';
 
+const sortByName = (list: UwpApp[]) => [...list].sort((a, b) => a.name.localeCompare(b.name));
+
+/**
+ * UWPアプリのロゴを表示します。ロゴがない場合や取得できない場合は Windows のアイコンを表示します。
+ */
+const UwpAppIcon: React.FC<{ app: UwpApp }> = ({ app }) => {
+    const [src, setSrc] = useState<string | null>(null);
+
+    useEffect(() => {
+        setSrc(null);
+        if (!app.logo) return;
+        let cancelled = false;
+        // AUMID を渡すと、バックエンドがカタログのロゴを抽出してキャッシュする
+        getFileIconUrl(app.aumid, false, Math.ceil(ICON_DISPLAY_SIZE * (window.devicePixelRatio || 1))).then(url => {
+            if (!cancelled) setSrc(url);
+        });
+        return () => { cancelled = true; };
+    }, [app.aumid, app.logo]);
+
+    return src
+        ? <img src={src} alt="" className="w-6 h-6 object-contain" draggable={false} />
+        : <DiWindows className="text-xl" />;
+};
+
 /**
  * UWP（Universal Windows Platform）アプリを選択するためのモーダルコンポーネント。
  * インストールされているUWPアプリの一覧を取得・表示し、検索や選択が可能です。
@@ -27,8 +53,7 @@ export const UwpSelectorModal: React.FC = () => {
             setLoading(true);
             getUwpApps().then(list => {
                 // Sort alphabetically
-                list.sort((a, b) => a.name.localeCompare(b.name));
-                setApps(list);
+                setApps(sortByName(list));
             }).finally(() => {
                 setLoading(false);
             });
@@ -36,18 +61,38 @@ export const UwpSelectorModal: React.FC = () => {
         }
     }, [isOpen]);
 
+    // 保存済みの一覧が古い場合、バックエンドが取得しなおした一覧が通知される
+    useEffect(() => {
+        if (!isOpen) return;
+        const unlistenPromise = listen<UwpApp[]>('uwp-apps-updated', event => {
+            setApps(sortByName(event.payload));
+        });
+        return () => {
+            unlistenPromise.then(unlisten => unlisten());
+        };
+    }, [isOpen]);
+
     const filteredApps = useMemo(() => {
         if (!searchTerm) return apps;
         const lower = searchTerm.toLowerCase();
         return apps.filter(app => app.name.toLowerCase().includes(lower));
     }, [apps, searchTerm]);
 
+    const handleRefresh = () => {
+        setLoading(true);
+        refreshUwpApps().then(list => {
+            setApps(sortByName(list));
+        }).finally(() => {
+            setLoading(false);
+        });
+    };
+
     const handleSelect = (app: UwpApp) => {
         if (targetCellId) {
             updateCell(targetCellId, {
                 type: 'shortcut',
                 title: app.name,
-                icon: undefined, // Default icon or specific logic if we had icons
+                icon: undefined, // アイコンはカタログのロゴをバックエンドから取得する
                 shortcut: {
                     kind: 'uwp',
                     aumid: app.aumid,
@@ -80,9 +125,14 @@ export const UwpSelectorModal: React.FC = () => {
                         <DiWindows className="text-2xl" />
                         Select UWP Application
                     </h2>
-                    <button onClick={() => setOpen(false)} className="hover:opacity-70">
-                        <IoClose className="text-2xl" />
-                    </button>
+                    <div className="flex items-center gap-3">
+                        <button onClick={handleRefresh} disabled={loading} className="hover:opacity-70 disabled:opacity-30" title="Refresh">
+                            <IoRefresh className="text-xl" />
+                        </button>
+                        <button onClick={() => setOpen(false)} className="hover:opacity-70">
+                            <IoClose className="text-2xl" />
+                        </button>
+                    </div>
                 </div>
 
                 {/* Search */}
@@ -122,7 +172,7 @@ export const UwpSelectorModal: React.FC = () => {
                                         }`}
                                 >
                                     <div className={`w-8 h-8 flex items-center justify-center rounded ${isCyberpunk ? 'bg-[#00f2ea]/20' : 'bg-gray-700'}`}>
-                                        <DiWindows className="text-xl" />
+                                        <UwpAppIcon app={app} />
                                     </div>
                                     <div className="flex-1 min-w-0">
                                         <div className="font-medium truncate">{app.name}</div>
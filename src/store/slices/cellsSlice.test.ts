@@ -1,9 +1,11 @@
 import { createCellsSlice } from './cellsSlice';
 import { Cell } from '../../types/models';
+import { normalizePath } from '../../utils/tauri';
 
 // Mock saveSettings
 jest.mock('../../utils/tauri', () => ({
     saveSettings: jest.fn(() => Promise.resolve()),
+    normalizePath: jest.fn((path: string) => Promise.resolve({ stored: path, resolved: path, is_link: false })),
 }));
 
 describe('cellsSlice', () => {
@@ -86,5 +88,24 @@ describe('cellsSlice', () => {
 
             expect(setState).toHaveBeenCalled();
         });
+
+        it('resolves the real path of a new file target', () => {
+            const state = {
+                ...getState(),
+                cells: {
+                    'test-cell-1': {
+                        id: 'test-cell-1',
+                        type: 'shortcut',
+                        cube: { x: 0, y: 0, z: 0 },
+                        title: 'Test',
+                    },
+                },
+            };
+            getState.mockReturnValue(state);
+
+            store.updateCell('test-cell-1', { shortcut: { kind: 'file', targetPath: '/usr/local/bin/app' } });
+
+            expect(normalizePath).toHaveBeenCalledWith('/usr/local/bin/app');
+        });
     });
 });
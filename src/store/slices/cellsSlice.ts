@@ -1,5 +1,5 @@
 import { Cell } from '../../types/models';
-import { saveSettings } from '../../utils/tauri';
+import { normalizePath, saveSettings } from '../../utils/tauri';
 import { SCHEMA_VERSION } from './settingsSlice';
 
 export const initialCells: Record<string, Cell> = {
@@ -25,6 +25,22 @@ export const initialCells: Record<string, Cell> = {
 
 const initialRootCellIds = ['root-center', 'root-close', 'root-tree'];
 
+/**
+ * ファイルを対象とするショートカットセルに、リンクを解決した実体パス (resolvedTargetPath) を記録します。
+ * 既に記録済みの場合や、解決中に対象が変更された場合は何もしません。
+ */
+const recordResolvedTarget = (get: any, cellId: string) => {
+    const shortcut = get().cells[cellId]?.shortcut;
+    if (shortcut?.kind !== 'file' || !shortcut.targetPath || shortcut.resolvedTargetPath) return;
+
+    const targetPath = shortcut.targetPath;
+    normalizePath(targetPath).then(({ resolved }) => {
+        const current = get().cells[cellId];
+        if (current?.shortcut?.targetPath !== targetPath) return;
+        get().updateCell(cellId, { shortcut: { ...current.shortcut, resolvedTargetPath: resolved } });
+    }).catch(console.error);
+};
+
 /**
  * セル（アプリケーション、ショートカット、ウィジェット等）の管理を行うスライス。
  *
@@ -42,7 +58,7 @@ export interface CellsSlice {
     swapCells: (cellId1: string, cellId2: string) => void;
 }
 
-export const createCellsSlice = (set: any, _get: any): CellsSlice => ({
+export const createCellsSlice = (set: any, get: any): CellsSlice => ({
     cells: initialCells,
     rootCellIds: initialRootCellIds,
 
@@ -72,6 +88,7 @@ export const createCellsSlice = (set: any, _get: any): CellsSlice => ({
             }).catch(console.error);
             return newState;
         });
+        recordResolvedTarget(get, cell.id);
     },
 
     removeCell: (cellId) => {
@@ -157,6 +174,9 @@ export const createCellsSlice = (set: any, _get: any): CellsSlice => ({
 
             return newState;
         });
+        if (updates.shortcut) {
+            recordResolvedTarget(get, cellId);
+        }
     },
 
     moveCell: (cellId, newCube) => {
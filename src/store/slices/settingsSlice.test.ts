@@ -0,0 +1,51 @@
+import { createSettingsSlice, DEFAULT_MOUSE_EDGE_SETTINGS } from './settingsSlice';
+import { Settings } from '../../types/models';
+
+jest.mock('../../utils/tauri', () => ({
+    saveSettings: jest.fn(() => Promise.resolve()),
+    updateGlobalShortcut: jest.fn(() => Promise.resolve()),
+}));
+
+describe('settingsSlice', () => {
+    let state: any;
+    let store: ReturnType<typeof createSettingsSlice>;
+
+    beforeEach(() => {
+        state = { appearance: {}, keyBindings: {} };
+        const setState = jest.fn((fn) => {
+            Object.assign(state, typeof fn === 'function' ? fn(state) : fn);
+        });
+        store = createSettingsSlice(setState, () => state);
+    });
+
+    describe('loadFromSettings', () => {
+        it('fills missing mouse edge settings with defaults', () => {
+            store.loadFromSettings({
+                general: {
+                    startOnBoot: true,
+                    language: 'ja',
+                    windowBehavior: { showOnMouseEdge: true },
+                    mouseEdge: { zones: ['right'], edgeThreshold: 10 },
+                },
+            } as unknown as Settings);
+
+            expect(state.general.startOnBoot).toBe(true);
+            expect(state.general.windowBehavior).toEqual({
+                alwaysOnTop: false,
+                hideOnBlur: false,
+                showOnMouseEdge: true,
+            });
+            expect(state.general.mouseEdge).toEqual({
+                ...DEFAULT_MOUSE_EDGE_SETTINGS,
+                zones: ['right'],
+                edgeThreshold: 10,
+            });
+        });
+
+        it('uses the default mouse edge settings when none are saved', () => {
+            store.loadFromSettings({} as Settings);
+
+            expect(state.general.mouseEdge).toEqual(DEFAULT_MOUSE_EDGE_SETTINGS);
+        });
+    });
+});
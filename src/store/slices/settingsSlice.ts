@@ -1,4 +1,4 @@
-import { Settings, Cell, Group, GeneralSettings, GridSettings, SecuritySettings, AdvancedSettings } from '../../types/models';
+import { Settings, Cell, Group, GeneralSettings, GridSettings, SecuritySettings, AdvancedSettings, MouseEdgeSettings } from '../../types/models';
 import { initialCells } from './cellsSlice';
 import { updateGlobalShortcut, saveSettings } from '../../utils/tauri';
 import { debounce } from '../../utils/debounce';
@@ -32,6 +32,20 @@ export interface SettingsSlice {
 
 const SCHEMA_VERSION = 1;
 
+export const DEFAULT_MOUSE_EDGE_SETTINGS: MouseEdgeSettings = {
+    zones: ['top', 'left'],
+    edgeThreshold: 5,
+    cornerSize: 32,
+    dwellMs: 0,
+    cooldownMs: 1000,
+    disabledMonitors: [],
+    ignoreSharedEdges: true,
+    actions: {},
+    suppressWhileButtonDown: true,
+    suppressInFullscreen: true,
+    blockedProcesses: [],
+};
+
 const DEFAULT_GENERAL_SETTINGS: GeneralSettings = {
     startOnBoot: false,
     language: 'en',
@@ -41,6 +55,7 @@ const DEFAULT_GENERAL_SETTINGS: GeneralSettings = {
         hideOnBlur: false,
         showOnMouseEdge: false,
     },
+    mouseEdge: DEFAULT_MOUSE_EDGE_SETTINGS,
 };
 
 const DEFAULT_GRID_SETTINGS: GridSettings = {
@@ -63,8 +78,20 @@ const DEFAULT_ADVANCED_SETTINGS: AdvancedSettings = {
     showPerformanceMetrics: false,
     customCSS: '',
     disableAnimations: false,
+    resolveSymlinks: false,
 };
 
+/**
+ * 保存された全般設定を既定値と統合します。
+ * 入れ子の設定 (ウィンドウの挙動・マウスエッジ) も既定値と統合し、古い設定ファイルにない項目を補います。
+ */
+const mergeGeneralSettings = (general?: Partial<GeneralSettings>): GeneralSettings => ({
+    ...DEFAULT_GENERAL_SETTINGS,
+    ...general,
+    windowBehavior: { ...DEFAULT_GENERAL_SETTINGS.windowBehavior, ...general?.windowBehavior },
+    mouseEdge: { ...DEFAULT_MOUSE_EDGE_SETTINGS, ...general?.mouseEdge },
+});
+
 // Debounced saver instance
 // 1 second delay to batch rapid changes (e.g. sliders)
 const debouncedSave = debounce((state: any) => {
@@ -124,7 +151,7 @@ export const createSettingsSlice = (set: any, get: any): SettingsSlice => ({
             groups: groupsMap,
             activeGroupId: settings.activeGroupId || null,
             appearance: settings.appearance || get().appearance,
-            general: { ...DEFAULT_GENERAL_SETTINGS, ...settings.general },
+            general: mergeGeneralSettings(settings.general),
             grid: { ...DEFAULT_GRID_SETTINGS, ...settings.grid },
             security: { ...DEFAULT_SECURITY_SETTINGS, ...settings.security },
             advanced: { ...DEFAULT_ADVANCED_SETTINGS, ...settings.advanced },
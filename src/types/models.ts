@@ -21,6 +21,20 @@ export interface ShortcutInfo {
     runAsAdmin?: boolean;
     /** 起動時の警告レベル ('none': 警告なし, 'warn': 確認ダイアログ表示) */
     warningLevel?: "none" | "warn";
+    /** targetPath のシンボリックリンク・ジャンクションを解決した実体パス (normalize_path で取得) */
+    resolvedTargetPath?: string;
+}
+
+/**
+ * normalize_path によるパス正規化の結果。
+ */
+export interface NormalizedPath {
+    /** セルに保存されているパス */
+    stored: string;
+    /** シンボリックリンク・ジャンクションを解決した実体パス */
+    resolved: string;
+    /** パスの途中にリンクが含まれていたかどうか */
+    is_link: boolean;
 }
 
 /**
@@ -35,6 +49,18 @@ export interface ResolvedShortcut {
     working_dir: string;
 }
 
+/**
+ * resolve_shortcuts による一括解決の1件分の結果。
+ */
+export interface BatchResolvedShortcut {
+    /** 解決対象の.lnkファイルのパス */
+    path: string;
+    /** 解決結果。失敗した場合は null */
+    resolved: ResolvedShortcut | null;
+    /** 失敗した場合のエラーメッセージ */
+    error: string | null;
+}
+
 /**
  * UWP(Universal Windows Platform)アプリの情報。
  */
@@ -43,6 +69,206 @@ export interface UwpApp {
     name: string;
     /** アプリID (AUMID) */
     aumid: string;
+    /** パッケージのロゴ画像のパス (ロゴがない場合は null) */
+    logo?: string | null;
+}
+
+/**
+ * アプリが提供する追加アクション (ジャンプリストのタスク / Desktop Action)。
+ * `path` / `arguments` / `working_dir` はそのまま launch_app に渡せます。
+ */
+export interface AppAction {
+    /** アクションの識別子 */
+    id: string;
+    /** 表示名 */
+    name: string;
+    /** 起動するプログラムのパス */
+    path: string;
+    /** 起動引数 (空白を含む引数はダブルクォートで囲まれます) */
+    arguments: string;
+    /** 作業ディレクトリ */
+    working_dir: string;
+    /** アイコン (Icon= の値、またはアイコンリソース) */
+    icon: string;
+    /** 取得元 */
+    source: 'desktop' | 'jumplist';
+}
+
+/**
+ * セルのヘルスチェック (scan_cell_health) で検出された問題。
+ */
+export interface CellHealthFinding {
+    /** 対象セルのID */
+    cell_id: string;
+    /** 対象セルのタイトル */
+    title: string;
+    /** 問題の種類 */
+    issue: 'missing_target' | 'stale_shortcut' | 'missing_uwp_app' | 'unreadable_working_dir';
+    /** 問題のあるパス (またはAUMID) */
+    path: string;
+    /** 問題の説明 */
+    message: string;
+    /** 修正候補 */
+    suggestion: {
+        /** 新しいターゲット (パスまたはAUMID) */
+        target: string;
+        /** 候補の表示名 */
+        name: string;
+        /** 候補が見つかった理由 */
+        reason: string;
+    } | null;
+}
+
+/**
+ * 壊れたターゲットの修正案 (propose_target_repair)。
+ */
+export interface TargetRepairProposal {
+    /** 壊れているターゲット */
+    original: string;
+    /** 提案する新しいターゲット */
+    proposed: string;
+    /** 候補を見つけた方法 */
+    method: 'link_tracking' | 'sibling_version' | 'app_index';
+}
+
+/**
+ * バックエンドでアイコンに適用する後処理 (get_file_icon / get_file_icon_url)。
+ * 適用結果はパラメータごとにキャッシュされます。
+ */
+export interface IconTransform {
+    /** 不透明な部分を単色 (tint の色、未指定時は白) で塗りつぶしたシルエットにする */
+    silhouette?: boolean;
+    /** 着色する色 (#RRGGBB) */
+    tint?: string;
+    /** 六角形のマスクで切り抜く */
+    hexMask?: boolean;
+    /** ドロップシャドウを付ける */
+    shadow?: boolean;
+    /** 透明な余白を取り除いて中央に配置する */
+    trim?: boolean;
+}
+
+/**
+ * インポートしたカスタムアイコン (import_custom_icon)。
+ */
+export interface ImportedIcon {
+    /** セルの customIcon に保存する参照 (custom:<hash>) */
+    reference: string;
+    /** 元の画像の内容のハッシュ */
+    hash: string;
+    /** 生成された正方形PNGのサイズ (ピクセル) */
+    sizes: number[];
+}
+
+/**
+ * アイコンの先読みの優先度 (表示中のセル → 隣接するセル → その他)。
+ */
+export type IconPrefetchPriority = 'visible' | 'neighbor' | 'rest';
+
+/**
+ * アイコンの先読みの要求 (prefetch_icons)。
+ */
+export interface IconPrefetchRequest {
+    /** 要求の識別子。同じキーの要求はバックエンドで重複して処理されず、icon-ready イベントでそのまま返されます */
+    key: string;
+    /** ファイルパス */
+    path: string;
+    /** ショートカット(.lnk)の実体アイコンを取得するかどうか */
+    resolveShortcut: boolean;
+    /** シンボリックリンク・ジャンクションの実体パスのアイコンを取得するかどうか */
+    resolveLinks: boolean;
+    /** 要求するピクセルサイズ */
+    size?: number;
+    /** バックエンドで適用する後処理 */
+    transform?: IconTransform;
+    /** 優先度 */
+    priority: IconPrefetchPriority;
+}
+
+/**
+ * 先読みしたアイコンの抽出完了時に発行される icon-ready イベントのペイロード。
+ */
+export interface IconReadyEvent {
+    /** 要求時に指定したキー */
+    key: string;
+    /** ファイルパス */
+    path: string;
+    /** アイコンのURL (hexicon プロトコル)。失敗した場合は null */
+    url: string | null;
+    /** 失敗した場合のエラーメッセージ */
+    error: string | null;
+}
+
+/**
+ * アイコンから抽出した配色 (get_icon_palette)。
+ */
+export interface IconPalette {
+    /** アクセントカラー (#rrggbb) */
+    accent: string;
+    /** アクセントカラーの上に載せる文字色 (#000000 または #ffffff) */
+    text: string;
+    /** 代表色 (#rrggbb)。占める面積の大きい順 */
+    palette: string[];
+}
+
+/**
+ * アイコンのメモリキャッシュの統計情報。
+ */
+export interface IconMemoryStats {
+    /** 保持しているエントリ数 */
+    entries: number;
+    /** 保持しているデータの合計サイズ (バイト) */
+    bytes: number;
+    /** 上限サイズ (バイト) */
+    max_bytes: number;
+    /** キャッシュヒット数 */
+    hits: number;
+    /** キャッシュミス数 */
+    misses: number;
+}
+
+/**
+ * アイコンキャッシュの統計情報 (get_icon_cache_stats)。
+ */
+export interface IconCacheStats {
+    /** ディスク上のキャッシュファイル数 */
+    entries: number;
+    /** ディスク上のキャッシュファイルの合計サイズ (バイト) */
+    total_bytes: number;
+    /** ディスクキャッシュの上限サイズ (バイト) */
+    max_bytes: number;
+    /** どのセルからも参照されていないキャッシュファイル数 */
+    orphaned_entries: number;
+    /** どのセルからも参照されていないキャッシュファイルの合計サイズ (バイト) */
+    orphaned_bytes: number;
+    /** メモリキャッシュの統計情報 */
+    memory: IconMemoryStats;
+}
+
+/**
+ * アイコンキャッシュのコンパクション結果 (compact_icon_cache)。
+ */
+export interface IconCacheCompaction {
+    /** 削除したキャッシュファイル数 */
+    removed_entries: number;
+    /** 解放したサイズ (バイト) */
+    freed_bytes: number;
+}
+
+/**
+ * バックグラウンドの監視スレッドの状態 (get_monitor_status)。
+ */
+export interface MonitorStatus {
+    /** サービス名 ('mouse-edge-monitor' | 'system-monitor') */
+    name: string;
+    /** 実行中かどうか */
+    running: boolean;
+    /** 処理の間隔 (ミリ秒) */
+    intervalMs: number;
+    /** 開始してから処理を行った回数 */
+    ticks: number;
+    /** 監視が停止する原因となったエラー */
+    lastError: string | null;
 }
 
 /**
@@ -80,7 +306,7 @@ export interface Cell {
     title: string;
     /** 自動取得されたアイコンのパスまたはデータURI */
     icon?: string;
-    /** ユーザーが設定したカスタムアイコンのパス */
+    /** ユーザーが設定したカスタムアイコン (インポート済みアイコンの参照 `custom:<hash>`、または画像ファイルのパス) */
     customIcon?: string;
     /** このセルのテーマカラー上書き設定 */
     themeColor?: string;
@@ -178,6 +404,52 @@ export interface KeyBindings {
     search: string; // "Ctrl+F"
 }
 
+/**
+ * マウスエッジで反応する画面端・四隅。
+ */
+export type HotZone =
+    | 'top' | 'bottom' | 'left' | 'right'
+    | 'topLeft' | 'topRight' | 'bottomLeft' | 'bottomRight';
+
+/**
+ * マウスエッジのゾーンに到達した際の動作。
+ * グループ・セル・検索の操作はバックエンドから `mouse-edge-action` イベントで通知されます。
+ */
+export type EdgeAction =
+    | { type: 'showLauncher' }
+    | { type: 'openGroup'; groupId: string }
+    | { type: 'launchCell'; cellId: string }
+    | { type: 'openSearch' }
+    | { type: 'hide' };
+
+/**
+ * マウスエッジの設定 (set_mouse_edge_config)。
+ */
+export interface MouseEdgeSettings {
+    /** 反応する画面端・四隅 */
+    zones: HotZone[];
+    /** 画面端とみなすカーソルと端の距離 (ピクセル) */
+    edgeThreshold: number;
+    /** 画面端のうち、四隅とみなす角からの範囲 (ピクセル) */
+    cornerSize: number;
+    /** 反応するまでにゾーンに留まる必要がある時間 (ミリ秒) */
+    dwellMs: number;
+    /** 反応後、次に反応できるようになるまでの時間 (ミリ秒) */
+    cooldownMs: number;
+    /** 監視しないモニターの名前 */
+    disabledMonitors: string[];
+    /** 他のモニターと接している画面端を無視するか */
+    ignoreSharedEdges: boolean;
+    /** ゾーンごとの動作 (指定がないゾーンはランチャーを表示) */
+    actions?: Partial<Record<HotZone, EdgeAction>>;
+    /** マウスのボタンが押されている間 (ドラッグ中など) は反応しないか */
+    suppressWhileButtonDown?: boolean;
+    /** 前面のウィンドウが全画面表示の間 (ゲーム・プレゼンテーションなど) は反応しないか */
+    suppressInFullscreen?: boolean;
+    /** 前面にある間は反応しないプロセスの実行ファイル名 (例: "game.exe") */
+    blockedProcesses?: string[];
+}
+
 /**
  * 全般設定。
  */
@@ -194,6 +466,8 @@ export interface GeneralSettings {
         hideOnBlur: boolean;       // フォーカスが外れたら隠す
         showOnMouseEdge: boolean;  // マウスエッジで表示
     };
+    /** マウスエッジの反応するゾーン・タイミングの設定 */
+    mouseEdge?: MouseEdgeSettings;
 }
 
 /**
@@ -238,6 +512,10 @@ export interface AdvancedSettings {
     customCSS: string;
     /** UIアニメーションの強制無効化 */
     disableAnimations: boolean;
+    /** シンボリックリンク・ジャンクションを解決した実体パスでアイコン取得・起動を行うか */
+    resolveSymlinks?: boolean;
+    /** アイコンのディスクキャッシュの上限サイズ (MB) */
+    iconCacheMaxMb?: number;
 }
 
 /**
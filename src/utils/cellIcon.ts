@@ -0,0 +1,79 @@
+import { convertFileSrc } from '@tauri-apps/api/core';
+import { AppearanceSettings, Cell, IconTransform } from '../types/models';
+import { THEMES } from './theme';
+
+/** セル内のアイコンの表示サイズ (CSS px, `w-8 h-8` に対応) */
+export const ICON_DISPLAY_SIZE = 32;
+
+/** インポート済みカスタムアイコンの参照の接頭辞 */
+const CUSTOM_ICON_PREFIX = 'custom:';
+/** インポート時にバックエンドで生成されるカスタムアイコンのサイズ (ピクセル) */
+const CUSTOM_ICON_SIZES = [32, 64, 128, 256];
+
+/**
+ * バックエンドからセルのアイコンを取得する際のパラメータ。
+ */
+export interface CellIconParams {
+    /** アイコンを取得するパス */
+    path: string;
+    /** ショートカット(.lnk)の実体アイコンを取得するかどうか */
+    resolveShortcut: boolean;
+    /** 要求するピクセルサイズ */
+    size: number;
+    /** バックエンドで適用する後処理 */
+    transform?: IconTransform;
+}
+
+/**
+ * カスタムアイコンの表示用URLを返します。
+ * インポート済みアイコンの参照 (custom:<hash>) の場合は、表示サイズ以上で最小の正規化済みPNGを hexicon プロトコルで読み込みます。
+ * それ以外 (インポート機能以前に設定されたファイルパス) の場合は、ファイルをそのまま読み込みます。
+ * @param customIcon セルの customIcon
+ * @param displaySize 表示サイズ (CSS px)
+ */
+export const getCustomIconSrc = (customIcon: string, displaySize: number = ICON_DISPLAY_SIZE): string => {
+    if (!customIcon.startsWith(CUSTOM_ICON_PREFIX)) {
+        return convertFileSrc(customIcon);
+    }
+    const hash = customIcon.slice(CUSTOM_ICON_PREFIX.length);
+    const pixelSize = Math.ceil(displaySize * (window.devicePixelRatio || 1));
+    const size = CUSTOM_ICON_SIZES.find(s => s >= pixelSize) ?? CUSTOM_ICON_SIZES[CUSTOM_ICON_SIZES.length - 1];
+    return convertFileSrc(`custom-${hash}-${size}.png`, 'hexicon');
+};
+
+/**
+ * シルエットモードでアイコンを塗りつぶす色を返します。
+ */
+export const getSilhouetteColor = (cell: Cell, appearance: AppearanceSettings, themeColor?: string): string => {
+    if (appearance.style === 'cyberpunk') return '#00f2ea';
+    const theme = THEMES[cell.themeColor || themeColor || 'cyan'] || THEMES['cyan'];
+    return theme.color;
+};
+
+/**
+ * セルのアイコンをバックエンドから取得する際のパラメータを返します。
+ * カスタムアイコンや保存済みのアイコンを表示するセルなど、バックエンドから取得しない場合は null を返します。
+ * セルの描画 (Hexagon) と先読み (HexGrid) で同じキャッシュキーになるよう、両方からこの関数を使用します。
+ */
+export const getCellIconParams = (cell: Cell, appearance: AppearanceSettings, silhouetteColor: string): CellIconParams | null => {
+    const targetPath = cell.shortcut?.targetPath || cell.target;
+    if (cell.customIcon || !targetPath || cell.type === 'launcher_setting') return null;
+
+    const isShortcut = targetPath.toLowerCase().endsWith('.lnk');
+    // settings.jsonに保存されたアイコン(cell.icon)があっても、「ショートカットアイコン非表示」設定時は
+    // 強制的にバックエンドから実体アイコンを取得しなおす
+    const forceFetch = isShortcut && appearance.showShortcutIcon === false;
+    if (cell.icon && !forceFetch) return null;
+
+    return {
+        path: targetPath,
+        // showShortcutIcon が false の場合、resolveShortcut: true を渡して実体のアイコン（矢印なし）を取得する
+        resolveShortcut: appearance.showShortcutIcon === false,
+        // アイコンの表示サイズ (w-8 = 32px) を実ピクセルに換算して要求する
+        size: Math.ceil(ICON_DISPLAY_SIZE * (window.devicePixelRatio || 1)),
+        // シルエットモードでは、単色化をバックエンドで一度だけ行った画像を取得する
+        transform: appearance.enableIconSilhouette
+            ? { silhouette: true, tint: silhouetteColor }
+            : undefined,
+    };
+};
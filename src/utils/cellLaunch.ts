@@ -0,0 +1,26 @@
+import { Cell } from '../types/models';
+import { launchAppWithSecurity, launchUwpApp } from './tauri';
+
+/**
+ * アプリ・ショートカットのセルの起動対象を起動します。
+ * グリッドでのクリックとマウスエッジの「セルを起動」で共通して使用します。
+ *
+ * @returns 起動対象のあるセルであれば true (起動の成否は問わない)
+ */
+export const launchCellTarget = (cell: Cell): boolean => {
+    if (cell.type === 'app' && cell.target) {
+        launchAppWithSecurity(cell.target, cell.args, cell.workingDir).catch(console.error);
+        return true;
+    }
+    if (cell.type === 'shortcut' && cell.shortcut) {
+        if (cell.shortcut.kind === 'uwp' && cell.shortcut.aumid) {
+            launchUwpApp(cell.shortcut.aumid).catch(console.error);
+            return true;
+        }
+        if (cell.shortcut.targetPath) {
+            launchAppWithSecurity(cell.shortcut.targetPath, cell.shortcut.arguments, cell.shortcut.workingDirectory).catch(console.error);
+            return true;
+        }
+    }
+    return false;
+};
@@ -1,5 +1,8 @@
 import { LRUCache } from 'lru-cache';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { useLauncherStore } from '../store/launcherStore';
+import { IconPrefetchPriority, IconPrefetchRequest, IconReadyEvent, IconTransform } from '../types/models';
 
 // Create LRU cache instance
 // Max 500 items, default TTL 1 hour (though icons rarely change)
@@ -8,20 +11,73 @@ const cache = new LRUCache<string, string>({
     ttl: 1000 * 60 * 60,
 });
 
+/**
+ * 後処理のキャッシュキー用の表現を返します (後処理がない場合は空文字列)。
+ */
+const transformKey = (transform?: IconTransform): string => {
+    if (!transform) return '';
+    const parts = [
+        transform.trim && 'trim',
+        transform.silhouette && 'silhouette',
+        transform.tint && `tint=${transform.tint.toLowerCase()}`,
+        transform.hexMask && 'hex',
+        transform.shadow && 'shadow',
+    ].filter(Boolean);
+    return parts.length > 0 ? `:${parts.join(',')}` : '';
+};
+
+/**
+ * アイコンURLのキャッシュキーを返します。先読みの要求キーとしても使用します。
+ */
+const urlCacheKey = (path: string, resolveShortcut: boolean, resolveLinks: boolean, size?: number, transform?: IconTransform): string =>
+    `${path}:url${resolveShortcut ? ':resolved' : ''}${resolveLinks ? ':real' : ''}${size ? `:${size}` : ''}${transformKey(transform)}`;
+
+/**
+ * 先読みするアイコン。
+ */
+export interface IconPrefetchItem {
+    /** ファイルパス */
+    path: string;
+    /** ショートカット(.lnk)の実体アイコンを取得するかどうか */
+    resolveShortcut: boolean;
+    /** 要求するピクセルサイズ */
+    size?: number;
+    /** バックエンドで適用する後処理 */
+    transform?: IconTransform;
+    /** 優先度 */
+    priority: IconPrefetchPriority;
+}
+
+/**
+ * 先読み中のアイコンの完了待ち。icon-ready イベント、または取り消しで解決されます。
+ */
+interface PrefetchWaiter {
+    promise: Promise<string | null>;
+    resolve: (url: string | null) => void;
+}
+
 /**
  * フロントエンド側のアイコンキャッシュ管理クラス。
  * LRUキャッシュを使用してバックエンド（Tauri）への不要な呼び出しを削減します。
  */
 class IconCache {
+    /** 先読み中のアイコン (キャッシュキー → 完了待ち) */
+    private prefetching = new Map<string, PrefetchWaiter>();
+    /** icon-ready イベントのリスナー登録 (初回の先読み時に登録) */
+    private readyListener: Promise<unknown> | null = null;
+
     /**
      * キャッシュからアイコンを取得、無ければバックエンドから取得してキャッシュします。
      * @param path ファイルパス
      * @param resolveShortcut ショートカット(.lnk)の実体アイコンを取得するかどうか
+     * @param size 要求するピクセルサイズ (省略時はバックエンドの既定サイズ)
+     * @param transform バックエンドで適用する後処理 (シルエット化・着色など)
      * @returns Base64エンコードされたPNG画像文字列、またはnull
      */
-    async getIcon(path: string, resolveShortcut: boolean = false): Promise<string | null> {
-        // Cache key logic: append suffix if resolving shortcut
-        const cacheKey = resolveShortcut ? `${path}:resolved` : path;
+    async getIcon(path: string, resolveShortcut: boolean = false, size?: number, transform?: IconTransform): Promise<string | null> {
+        // Cache key logic: append suffix if resolving shortcut / symlinks / size
+        const resolveLinks = useLauncherStore.getState().advanced.resolveSymlinks ?? false;
+        const cacheKey = `${resolveShortcut ? `${path}:resolved` : path}${resolveLinks ? ':real' : ''}${size ? `:${size}` : ''}${transformKey(transform)}`;
 
         // 1. Check Memory Cache
         if (cache.has(cacheKey)) {
@@ -30,7 +86,7 @@ class IconCache {
 
         // 2. Fetch from Backend
         try {
-            const icon = await invoke<string>('get_file_icon', { path, resolveShortcut });
+            const icon = await invoke<string>('get_file_icon', { path, resolveShortcut, resolveLinks, size, transform });
             if (icon) {
                 cache.set(cacheKey, icon);
             }
@@ -41,6 +97,126 @@ class IconCache {
         }
     }
 
+    /**
+     * キャッシュからアイコンのURL (hexicon プロトコル) を取得、無ければバックエンドから取得してキャッシュします。
+     * 画像データ自体は WebView が URL から読み込み、HTTP キャッシュとして保持します。
+     * @param path ファイルパス
+     * @param resolveShortcut ショートカット(.lnk)の実体アイコンを取得するかどうか
+     * @param size 要求するピクセルサイズ (省略時はバックエンドの既定サイズ)
+     * @param transform バックエンドで適用する後処理 (シルエット化・着色など)
+     * @returns アイコンのURL、またはnull
+     */
+    async getIconUrl(path: string, resolveShortcut: boolean = false, size?: number, transform?: IconTransform): Promise<string | null> {
+        const resolveLinks = useLauncherStore.getState().advanced.resolveSymlinks ?? false;
+        const cacheKey = urlCacheKey(path, resolveShortcut, resolveLinks, size, transform);
+
+        if (cache.has(cacheKey)) {
+            return cache.get(cacheKey) || null;
+        }
+
+        // 先読み中の場合は、バックエンドのワーカーの完了を待つ (失敗・取り消し時は直接取得する)
+        const waiter = this.prefetching.get(cacheKey);
+        if (waiter) {
+            const url = await waiter.promise;
+            if (url) return url;
+        }
+
+        try {
+            const url = await invoke<string>('get_file_icon_url', { path, resolveShortcut, resolveLinks, size, transform });
+            if (url) {
+                cache.set(cacheKey, url);
+            }
+            return url;
+        } catch (error) {
+            console.warn(`Failed to get icon url for ${path}:`, error);
+            return null;
+        }
+    }
+
+    /**
+     * アイコンURLをバックエンドのワーカーで先読みします。
+     * 抽出が完了したものから icon-ready イベントでキャッシュに格納され、先読み中の getIconUrl はその完了を待ちます。
+     * @param items 先読みするアイコン (優先度はバックエンドで処理順の決定に使用されます)
+     * @param group 要求を登録するグループ (cancelPrefetch で取り消す単位)
+     */
+    async prefetch(items: IconPrefetchItem[], group: string): Promise<void> {
+        const resolveLinks = useLauncherStore.getState().advanced.resolveSymlinks ?? false;
+        const requests: IconPrefetchRequest[] = items
+            .map(item => ({ ...item, resolveLinks, key: urlCacheKey(item.path, item.resolveShortcut, resolveLinks, item.size, item.transform) }))
+            .filter(request => !cache.has(request.key));
+        if (requests.length === 0) return;
+
+        for (const request of requests) {
+            if (!this.prefetching.has(request.key)) {
+                let resolve: (url: string | null) => void = () => {};
+                const promise = new Promise<string | null>(r => { resolve = r; });
+                this.prefetching.set(request.key, { promise, resolve });
+            }
+        }
+
+        try {
+            await this.listenReady();
+            await invoke<number>('prefetch_icons', { requests, group });
+        } catch (error) {
+            console.warn('Failed to prefetch icons:', error);
+            this.settle(requests.map(request => request.key), null);
+        }
+    }
+
+    /**
+     * グループの先読みのうち、まだ開始していないものを取り消します。
+     * @param group 取り消すグループ (省略時はすべて)
+     */
+    async cancelPrefetch(group?: string): Promise<void> {
+        try {
+            const cancelled = await invoke<string[]>('cancel_icon_prefetch', { group });
+            this.settle(cancelled, null);
+        } catch (error) {
+            console.warn('Failed to cancel icon prefetch:', error);
+        }
+    }
+
+    /**
+     * icon-ready イベントのリスナーを (まだであれば) 登録します。
+     */
+    private listenReady(): Promise<unknown> {
+        if (!this.readyListener) {
+            this.readyListener = listen<IconReadyEvent>('icon-ready', event => {
+                const { key, url } = event.payload;
+                if (url) {
+                    cache.set(key, url);
+                }
+                this.settle([key], url);
+            });
+        }
+        return this.readyListener;
+    }
+
+    /**
+     * 先読みの完了待ちを解決します。
+     */
+    private settle(keys: string[], url: string | null) {
+        for (const key of keys) {
+            const waiter = this.prefetching.get(key);
+            if (waiter) {
+                this.prefetching.delete(key);
+                waiter.resolve(url);
+            }
+        }
+    }
+
+    /**
+     * 指定されたパスのアイコンをキャッシュから削除します (ショートカット解決・実体パス解決の有無を問わず)。
+     * @param path ファイルパス
+     */
+    invalidate(path: string) {
+        for (const key of [...cache.keys()]) {
+            if (key === path || key.startsWith(`${path}:`)) {
+                cache.delete(key);
+            }
+        }
+    }
+
     /**
      * Clear cache
      */
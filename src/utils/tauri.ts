@@ -37,13 +37,27 @@ export async function launchApp(
     workingDir?: string
 ): Promise<void> {
     try {
-        await invoke('launch_app', { path, args, workingDir });
+        const resolveLinks = useLauncherStore.getState().advanced.resolveSymlinks ?? false;
+        await invoke('launch_app', { path, args, workingDir, resolveLinks });
     } catch (error) {
         console.error('Failed to launch app:', error);
         throw error;
     }
 }
 
+/**
+ * 起動の確認ダイアログを表示します。
+ *
+ * マウスエッジの「セルを起動」などウィンドウが非表示のまま起動する場合、ダイアログが表示されず
+ * 起動が止まってしまうため、先にウィンドウを表示してフォーカスします。
+ *
+ * @returns ユーザーが起動を許可した場合 true
+ */
+async function confirmLaunch(message: string): Promise<boolean> {
+    await showWindow();
+    return confirm(message);
+}
+
 export async function launchAppWithSecurity(
     path: string,
     args?: string,
@@ -60,7 +74,7 @@ export async function launchAppWithSecurity(
 
     // 起動確認ダイアログ
     if (security.showLaunchConfirmation && !isTrusted) {
-        const confirmed = confirm(`Launch application?\n\n${path}`);
+        const confirmed = await confirmLaunch(`Launch application?\n\n${path}`);
         if (!confirmed) return;
     }
 
@@ -71,7 +85,7 @@ export async function launchAppWithSecurity(
             path.toLowerCase().includes('powershell');
 
         if (requiresAdmin) {
-            const confirmed = confirm(
+            const confirmed = await confirmLaunch(
                 `This application may require administrator privileges.\n\n` +
                 `${path}\n\n` +
                 `Continue?`
@@ -89,7 +103,7 @@ export async function launchAppWithSecurity(
     }
 }
 
-import { ResolvedShortcut, UwpApp } from '../types/models';
+import { AppAction, BatchResolvedShortcut, Cell, CellHealthFinding, IconCacheCompaction, IconCacheStats, IconPalette, IconTransform, ImportedIcon, MonitorStatus, MouseEdgeSettings, NormalizedPath, ResolvedShortcut, TargetRepairProposal, UwpApp } from '../types/models';
 
 // ショートカット解決
 export const resolveShortcut = async (path: string): Promise<ResolvedShortcut> => {
@@ -101,6 +115,36 @@ export const resolveShortcut = async (path: string): Promise<ResolvedShortcut> =
     }
 };
 
+// シンボリックリンク・ジャンクションの解決 (保存パスと実体パスの両方を返す)
+export const normalizePath = async (path: string): Promise<NormalizedPath> => {
+    try {
+        return await invoke<NormalizedPath>('normalize_path', { path });
+    } catch (error) {
+        console.error('Failed to normalize path:', error);
+        return { stored: path, resolved: path, is_link: false };
+    }
+};
+
+// 実行中のプロセスの照合 (リンクを解決した実体パスで比較し、実行中のものだけを返す)
+export const getRunningTargets = async (paths: string[]): Promise<string[]> => {
+    try {
+        return await invoke<string[]>('get_running_targets', { paths });
+    } catch (error) {
+        console.error('Failed to get running targets:', error);
+        return [];
+    }
+};
+
+// ショートカット一括解決 (進捗は 'shortcut-resolve-progress' イベントで通知される)
+export const resolveShortcuts = async (paths: string[]): Promise<BatchResolvedShortcut[]> => {
+    try {
+        return await invoke<BatchResolvedShortcut[]>('resolve_shortcuts', { paths });
+    } catch (error) {
+        console.error('Failed to resolve shortcuts:', error);
+        return paths.map(path => ({ path, resolved: null, error: String(error) }));
+    }
+};
+
 // UWP Apps
 export const getUwpApps = async (): Promise<UwpApp[]> => {
     try {
@@ -111,6 +155,16 @@ export const getUwpApps = async (): Promise<UwpApp[]> => {
     }
 }
 
+// UWPアプリの一覧を取得しなおす (get_uwp_apps は保存済みの一覧を返すため、最新の状態が必要な場合に使用)
+export const refreshUwpApps = async (): Promise<UwpApp[]> => {
+    try {
+        return await invoke<UwpApp[]>('refresh_uwp_apps');
+    } catch (error) {
+        console.error('Failed to refresh UWP apps:', error);
+        return [];
+    }
+}
+
 export const launchUwpApp = async (aumid: string): Promise<void> => {
     try {
         await invoke('launch_uwp_app', { aumid });
@@ -120,7 +174,37 @@ export const launchUwpApp = async (aumid: string): Promise<void> => {
     }
 }
 
-import { iconCache } from './iconCache';
+// アプリの追加アクション (ジャンプリスト / Desktop Action)
+export const getAppActions = async (path: string): Promise<AppAction[]> => {
+    try {
+        return await invoke<AppAction[]>('get_app_actions', { path });
+    } catch (error) {
+        console.error('Failed to get app actions:', error);
+        return [];
+    }
+}
+
+// セルのヘルスチェック (cells を省略すると保存済みの設定を検査する)
+export const scanCellHealth = async (cells?: Cell[]): Promise<CellHealthFinding[]> => {
+    try {
+        return await invoke<CellHealthFinding[]>('scan_cell_health', { cells });
+    } catch (error) {
+        console.error('Failed to scan cell health:', error);
+        return [];
+    }
+}
+
+// 壊れたターゲットの修正案を取得 (見つからない場合は null)
+export const proposeTargetRepair = async (path: string, title?: string): Promise<TargetRepairProposal | null> => {
+    try {
+        return await invoke<TargetRepairProposal | null>('propose_target_repair', { path, title });
+    } catch (error) {
+        console.error('Failed to propose target repair:', error);
+        return null;
+    }
+}
+
+import { iconCache, IconPrefetchItem } from './iconCache';
 
 // getFileIcon は iconCache を使用するが、iconCache は現在 resolve_shortcut をサポートしていない。
 // そのため、iconCache の getIcon メソッドも更新する必要があるが、
@@ -149,10 +233,96 @@ import { iconCache } from './iconCache';
 // Frontend `iconCache.ts` might have its own cache.
 // Let's modify `tauri.ts` to accept the argument and pass it to `iconCache`.
 
-export const getFileIcon = async (path: string, resolveShortcut: boolean = false): Promise<string | null> => {
-    return await iconCache.getIcon(path, resolveShortcut);
+export const getFileIcon = async (path: string, resolveShortcut: boolean = false, size?: number, transform?: IconTransform): Promise<string | null> => {
+    return await iconCache.getIcon(path, resolveShortcut, size, transform);
+};
+
+// アイコンのURL (hexicon プロトコル) を取得。画像データはIPCを経由せずWebViewが直接読み込む
+export const getFileIconUrl = async (path: string, resolveShortcut: boolean = false, size?: number, transform?: IconTransform): Promise<string | null> => {
+    return await iconCache.getIconUrl(path, resolveShortcut, size, transform);
 };
 
+// アイコンをバックエンドのワーカーで先読み (完了したものから icon-ready イベントで通知される)
+export const prefetchIcons = async (items: IconPrefetchItem[], group: string): Promise<void> => {
+    await iconCache.prefetch(items, group);
+};
+
+// グループのアイコンの先読みを取り消し (グループを離れる際に呼び出す)
+export const cancelIconPrefetch = async (group?: string): Promise<void> => {
+    await iconCache.cancelPrefetch(group);
+};
+
+// 画像ファイルをカスタムアイコンとしてインポート (アプリのデータフォルダにコピーし、正方形に正規化する)
+export const importCustomIcon = async (path: string): Promise<ImportedIcon | null> => {
+    try {
+        return await invoke<ImportedIcon>('import_custom_icon', { path });
+    } catch (error) {
+        console.error('Failed to import custom icon:', error);
+        return null;
+    }
+}
+
+// アイコンから配色 (アクセントカラーと文字色) を抽出
+export const getIconPalette = async (path: string, resolveShortcut: boolean = false): Promise<IconPalette | null> => {
+    try {
+        return await invoke<IconPalette>('get_icon_palette', { path, resolveShortcut });
+    } catch (error) {
+        console.error('Failed to get icon palette:', error);
+        return null;
+    }
+}
+
+// アイコンキャッシュの破棄 (次回の取得時に再抽出される)
+export const invalidateIcon = async (path: string): Promise<number> => {
+    iconCache.invalidate(path);
+    try {
+        return await invoke<number>('invalidate_icon', { path });
+    } catch (error) {
+        console.error('Failed to invalidate icon:', error);
+        return 0;
+    }
+}
+
+// すべてのアイコンキャッシュの破棄
+export const invalidateAllIcons = async (): Promise<number> => {
+    iconCache.clear();
+    try {
+        return await invoke<number>('invalidate_all_icons');
+    } catch (error) {
+        console.error('Failed to invalidate all icons:', error);
+        return 0;
+    }
+}
+
+// アイコンキャッシュの統計情報 (ディスク・メモリ・孤立エントリ)
+export const getIconCacheStats = async (): Promise<IconCacheStats | null> => {
+    try {
+        return await invoke<IconCacheStats>('get_icon_cache_stats');
+    } catch (error) {
+        console.error('Failed to get icon cache stats:', error);
+        return null;
+    }
+}
+
+// どのセルからも参照されていないアイコンキャッシュの削除
+export const compactIconCache = async (): Promise<IconCacheCompaction | null> => {
+    try {
+        return await invoke<IconCacheCompaction>('compact_icon_cache');
+    } catch (error) {
+        console.error('Failed to compact icon cache:', error);
+        return null;
+    }
+}
+
+// アイコンのディスクキャッシュの上限サイズ (MB) の設定
+export const setIconCacheLimit = async (maxMb: number): Promise<void> => {
+    try {
+        await invoke('set_icon_cache_limit', { maxMb });
+    } catch (error) {
+        console.error('Failed to set icon cache limit:', error);
+    }
+}
+
 export const openDialog = async (options?: any): Promise<any> => {
     try {
         // Tauri v2 uses plugin-dialog
@@ -172,6 +342,14 @@ export const hideWindow = async (): Promise<void> => {
     }
 };
 
+export const showWindow = async (): Promise<void> => {
+    try {
+        await invoke('show_window');
+    } catch (error) {
+        console.error('Failed to show window:', error);
+    }
+};
+
 export const updateGlobalShortcut = async (shortcut: string): Promise<void> => {
     try {
         await invoke('update_global_shortcut', { shortcut });
@@ -256,10 +434,29 @@ export async function startMouseEdgeMonitor(): Promise<void> {
     }
 }
 
+// マウスエッジの設定 (反応するゾーン・滞在時間など) を反映 (監視中も即時反映)
+export async function setMouseEdgeConfig(config: MouseEdgeSettings): Promise<void> {
+    try {
+        await invoke('set_mouse_edge_config', { config });
+    } catch (error) {
+        console.error('Failed to set mouse edge config:', error);
+    }
+}
+
 export async function stopMouseEdgeMonitor(): Promise<void> {
     try {
         await invoke('stop_mouse_edge_monitor');
     } catch (error) {
         console.error('Failed to stop mouse edge monitor:', error);
     }
+}
+
+// バックグラウンドの監視 (マウスエッジ・システムリソース) の状態を取得
+export async function getMonitorStatus(): Promise<MonitorStatus[]> {
+    try {
+        return await invoke<MonitorStatus[]>('get_monitor_status');
+    } catch (error) {
+        console.error('Failed to get monitor status:', error);
+        return [];
+    }
 }
\ No newline at end of file